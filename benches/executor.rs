@@ -124,6 +124,116 @@ fn bench_execute_sum_variable_graph_size(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_execute_filtered_relationship_variable_graph_size(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filtered_relationship_graph_size");
+
+    for size in [10, 50, 100, 500].iter() {
+        let data = create_test_data(*size);
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, _| {
+            b.iter(|| {
+                engine.execute(std::hint::black_box(
+                    "MATCH (a)-[:friends]->(b) WHERE a.role = \"admin\" RETURN b.id",
+                ))
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_execute_query_cache(c: &mut Criterion) {
+    let mut group = c.benchmark_group("query_cache");
+    let data = create_test_data(500);
+    let query = "MATCH (a)-[:friends]->(b) WHERE a.role = \"admin\" RETURN b.id";
+
+    let uncached = CypherEngine::from_json_auto(&data).unwrap();
+    group.bench_function("uncached", |b| {
+        b.iter(|| uncached.execute(std::hint::black_box(query)));
+    });
+
+    let cached = CypherEngine::from_json_auto(&data)
+        .unwrap()
+        .with_query_cache(16);
+    group.bench_function("cached", |b| {
+        b.iter(|| cached.execute(std::hint::black_box(query)));
+    });
+
+    group.finish();
+}
+
+fn bench_execute_label_predicate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("label_predicate");
+    // Label each user by its role, so a third of them are `admin`
+    let mut data = create_test_data(500);
+    for user in data["users"].as_array_mut().unwrap() {
+        user["type"] = user["role"].clone();
+    }
+    let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+    group.bench_function("property_only", |b| {
+        b.iter(|| {
+            engine.execute(std::hint::black_box(
+                "MATCH (n) WHERE n.role = \"admin\" AND n.age > 30 RETURN n.id",
+            ))
+        });
+    });
+    group.bench_function("label_and_property", |b| {
+        b.iter(|| {
+            engine.execute(std::hint::black_box(
+                "MATCH (n) WHERE n:admin AND n.age > 30 RETURN n.id",
+            ))
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_execute_prepared(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prepared");
+    let data = create_test_data(100);
+    let engine = CypherEngine::from_json_auto(&data).unwrap();
+    let query = "MATCH (n:admin) WHERE n.age > 25 RETURN n.id";
+
+    group.bench_function("execute", |b| {
+        b.iter(|| engine.execute(std::hint::black_box(query)));
+    });
+
+    let compiled = engine.prepare(query).unwrap();
+    group.bench_function("run", |b| {
+        b.iter(|| engine.run(std::hint::black_box(&compiled)));
+    });
+
+    group.finish();
+}
+
+fn bench_execute_optional_match(c: &mut Criterion) {
+    let mut group = c.benchmark_group("optional_match");
+    // A ring: every user has exactly one friend, the next user
+    let users: Vec<_> = (0..4000)
+        .map(|i| json!({ "id": i.to_string(), "friends": [((i + 1) % 4000).to_string()] }))
+        .collect();
+    let engine = CypherEngine::from_json_auto(&json!({ "users": users })).unwrap();
+
+    group.bench_function("match", |b| {
+        b.iter(|| {
+            engine.execute(std::hint::black_box(
+                "MATCH (u:users)-[:friends]->(f) RETURN COUNT(f)",
+            ))
+        });
+    });
+    group.bench_function("optional_match", |b| {
+        b.iter(|| {
+            engine.execute(std::hint::black_box(
+                "MATCH (u:users) OPTIONAL MATCH (u)-[:friends]->(f) RETURN COUNT(f)",
+            ))
+        });
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_execute_simple_match,
@@ -135,6 +245,11 @@ criterion_group!(
     bench_execute_variable_graph_size,
     bench_execute_count_variable_graph_size,
     bench_execute_sum_variable_graph_size,
+    bench_execute_filtered_relationship_variable_graph_size,
+    bench_execute_query_cache,
+    bench_execute_label_predicate,
+    bench_execute_prepared,
+    bench_execute_optional_match,
 );
 
 criterion_main!(benches);
@@ -0,0 +1,149 @@
+//! Memory measurements, which criterion can't take: a counting global
+//! allocator records the bytes a graph keeps alive and the peak while
+//! building it.
+//!
+//! Run with `cargo bench --bench memory`.
+
+use cypher_rs::engine::QueryExecutor;
+use cypher_rs::engine::storage::json::build_graph_from_shared;
+use cypher_rs::{Graph, GraphConfig, Node, parser};
+use serde_json::{Value, json};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+struct CountingAlloc;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// Run `f`, returning its result with the bytes it left allocated and the
+/// peak allocated above the starting point.
+fn measure<T>(f: impl FnOnce() -> T) -> (T, usize, usize) {
+    let start = LIVE.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let value = f();
+    let retained = LIVE.load(Ordering::Relaxed).saturating_sub(start);
+    let peak = PEAK.load(Ordering::Relaxed).saturating_sub(start);
+    (value, retained, peak)
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn create_large_document(node_count: usize) -> Value {
+    let users: Vec<Value> = (0..node_count)
+        .map(|i| {
+            json!({
+                "id": i.to_string(),
+                "name": format!("User{}", i),
+                "email": format!("user{}@example.com", i),
+                "age": 20 + (i % 50),
+                "tags": ["alpha", "beta", "gamma"],
+                "bio": format!("User {} writes a short biography to pad the record.", i),
+                "friends": [((i + 1) % node_count).to_string()]
+            })
+        })
+        .collect();
+    json!({ "users": users })
+}
+
+/// Node data copied into the graph versus referenced in the shared source
+/// document, over a large generated dataset.
+fn measure_shared_data() {
+    let node_count = 100_000;
+    let document = Arc::new(create_large_document(node_count));
+    let query = parser::parse_query("MATCH (u:users) WHERE u.age > 40 RETURN COUNT(u)").unwrap();
+
+    println!("shared_data ({} nodes)", node_count);
+    for shared_data in [false, true] {
+        let config = GraphConfig::default().with_shared_data(shared_data);
+        let (graph, retained, peak) =
+            measure(|| build_graph_from_shared(&document, "Root", &config).unwrap());
+
+        // Each property read on shared data resolves a JSON pointer
+        let started = Instant::now();
+        let result = QueryExecutor::execute(&query, &graph).unwrap();
+        let elapsed = started.elapsed();
+        std::hint::black_box(result);
+
+        println!(
+            "  {:<5} retained {:>8.2} MiB  peak {:>8.2} MiB  query {:>8.2?}",
+            shared_data,
+            mib(retained),
+            mib(peak),
+            elapsed
+        );
+    }
+}
+
+/// Labels owned by each node versus interned by the graph, over many nodes
+/// sharing a few labels.
+fn measure_label_interning() {
+    let node_count = 200_000;
+    let labels = ["customer", "supplier", "employee"];
+    let nodes = |labeled: bool| -> Vec<Node> {
+        (0..node_count)
+            .map(|i| {
+                let label = labeled.then(|| labels[i % labels.len()].to_string());
+                Node::new(i.to_string(), label, json!({ "n": i }))
+            })
+            .collect()
+    };
+    let graph = |labeled: bool| {
+        let mut graph = Graph::new();
+        for node in nodes(labeled) {
+            graph.add_node(node);
+        }
+        graph
+    };
+
+    // The label cost is the difference from the same nodes without labels
+    let (_, owned, _) = measure(|| nodes(true));
+    let (_, owned_base, _) = measure(|| nodes(false));
+    let (_, interned, _) = measure(|| graph(true));
+    let (_, interned_base, _) = measure(|| graph(false));
+
+    println!(
+        "label interning ({} nodes, {} labels)",
+        node_count,
+        labels.len()
+    );
+    for (name, total, base) in [
+        ("owned", owned, owned_base),
+        ("interned", interned, interned_base),
+    ] {
+        println!(
+            "  {:<8} labels {:>8.2} MiB  total {:>8.2} MiB",
+            name,
+            mib(total.saturating_sub(base)),
+            mib(total)
+        );
+    }
+}
+
+fn main() {
+    measure_shared_data();
+    measure_label_interning();
+}
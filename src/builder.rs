@@ -0,0 +1,316 @@
+//! Programmatic construction of Cypher queries.
+//!
+//! [`QueryBuilder`] produces an [`ast::Query`] directly, so values never
+//! pass through string interpolation and can't alter the query's shape.
+//!
+//! # Example
+//!
+//! ```rust
+//! use cypher_rs::builder::QueryBuilder;
+//!
+//! let query = QueryBuilder::match_node("u")
+//!     .with_label("users")
+//!     .where_eq("u.role", "admin")
+//!     .return_prop("u.id")
+//!     .build();
+//! assert_eq!(
+//!     query.to_string(),
+//!     "MATCH (u:users) WHERE u.role = \"admin\" RETURN u.id"
+//! );
+//! ```
+
+use crate::parser::ast;
+
+/// Fluent builder for MATCH ... WHERE ... RETURN queries.
+#[derive(Debug, Clone)]
+pub struct QueryBuilder {
+    patterns: Vec<ast::PatternPart>,
+    conditions: Vec<ast::Expression>,
+    return_clause: ast::ReturnClause,
+    order_by: Vec<ast::SortItem>,
+    skip: Option<usize>,
+    limit: Option<usize>,
+}
+
+impl QueryBuilder {
+    /// Start a query matching a node bound to `variable`.
+    pub fn match_node(variable: &str) -> Self {
+        Self {
+            patterns: Vec::new(),
+            conditions: Vec::new(),
+            return_clause: ast::ReturnClause::default(),
+            order_by: Vec::new(),
+            skip: None,
+            limit: None,
+        }
+        .and_match_node(variable)
+    }
+
+    /// Add another comma-separated pattern starting at `variable`.
+    pub fn and_match_node(mut self, variable: &str) -> Self {
+        self.patterns.push(ast::PatternPart {
+            chains: vec![ast::PatternChain::Node(node(variable))],
+        });
+        self
+    }
+
+    /// Add a label to the most recently added node.
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.last_node().labels.push(label.to_string());
+        self
+    }
+
+    /// Add an inline `{key: value}` equality to the most recently added node.
+    pub fn with_property(mut self, key: &str, value: impl Into<ast::Literal>) -> Self {
+        self.last_node()
+            .properties
+            .push((key.to_string(), value.into()));
+        self
+    }
+
+    /// Follow an outgoing `rel_type` relationship to a node bound to `variable`.
+    pub fn outgoing(self, rel_type: &str, variable: &str) -> Self {
+        self.relationship(rel_type, ast::Direction::Right, variable)
+    }
+
+    /// Follow an incoming `rel_type` relationship to a node bound to `variable`.
+    pub fn incoming(self, rel_type: &str, variable: &str) -> Self {
+        self.relationship(rel_type, ast::Direction::Left, variable)
+    }
+
+    /// Follow a `rel_type` relationship in either direction.
+    pub fn undirected(self, rel_type: &str, variable: &str) -> Self {
+        self.relationship(rel_type, ast::Direction::Both, variable)
+    }
+
+    fn relationship(mut self, rel_type: &str, direction: ast::Direction, variable: &str) -> Self {
+        let rel = ast::RelationshipPattern {
+            variable: None,
+            rel_type: Some(rel_type.to_string()),
+            range: None,
+            direction,
+        };
+        self.current_part()
+            .chains
+            .push(ast::PatternChain::Relationship(rel, node(variable)));
+        self
+    }
+
+    /// Require `property = value`, where `property` is `var.prop` or `var`.
+    pub fn where_eq(self, property: &str, value: impl Into<ast::Literal>) -> Self {
+        self.where_cmp(property, ast::ComparisonOperator::Eq, value)
+    }
+
+    /// Require `property <op> value`. Conditions are combined with AND.
+    pub fn where_cmp(
+        mut self,
+        property: &str,
+        operator: ast::ComparisonOperator,
+        value: impl Into<ast::Literal>,
+    ) -> Self {
+        self.conditions
+            .push(ast::Expression::Comparison(ast::Comparison {
+                left: ast::ValueExpression::PropertyOrVariable(property_or_variable(property)),
+                operator: Some(operator),
+                right: Some(ast::ValueExpression::Literal(value.into())),
+            }));
+        self
+    }
+
+    /// Return `var.prop` (or a bare `var`).
+    pub fn return_prop(self, property: &str) -> Self {
+        self.return_item(comparison_of(property), None)
+    }
+
+    /// Return `var.prop` under the column name `alias`.
+    pub fn return_prop_as(self, property: &str, alias: &str) -> Self {
+        self.return_item(comparison_of(property), Some(alias))
+    }
+
+    /// Return `COUNT(variable)`.
+    pub fn return_count(self, variable: &str) -> Self {
+        let count = ast::Expression::Aggregate(ast::AggregateExpression {
+            func: ast::AggregateFunction::Count,
+            distinct: false,
+            argument: Some(ast::ValueExpression::PropertyOrVariable(
+                ast::PropertyOrVariable {
+                    variable: variable.to_string(),
+                    property: None,
+                },
+            )),
+        });
+        self.return_item(count, None)
+    }
+
+    /// Drop duplicate result rows.
+    pub fn distinct(mut self) -> Self {
+        self.return_clause.distinct = true;
+        self
+    }
+
+    /// Sort by `var.prop`, ascending or descending.
+    pub fn order_by(mut self, property: &str, direction: ast::SortDirection) -> Self {
+        self.order_by.push(ast::SortItem {
+            expression: ast::ValueExpression::PropertyOrVariable(property_or_variable(property)),
+            direction,
+        });
+        self
+    }
+
+    /// Drop the first `count` rows after sorting.
+    pub fn skip(mut self, count: usize) -> Self {
+        self.skip = Some(count);
+        self
+    }
+
+    /// Return at most `count` rows.
+    pub fn limit(mut self, count: usize) -> Self {
+        self.limit = Some(count);
+        self
+    }
+
+    /// Finish the query.
+    pub fn build(self) -> ast::Query {
+        let mut conditions = self.conditions;
+        let where_clause = match conditions.len() {
+            0 => None,
+            1 => conditions.pop(),
+            _ => Some(ast::Expression::And(conditions)),
+        }
+        .map(|expression| ast::WhereClause { expression });
+
+        ast::Query {
+            match_clause: ast::MatchClause {
+                patterns: self.patterns,
+            },
+            where_clause,
+            optional_matches: Vec::new(),
+            with_clauses: Vec::new(),
+            return_clause: self.return_clause,
+            order_by_clause: (!self.order_by.is_empty()).then_some(ast::OrderByClause {
+                items: self.order_by,
+            }),
+            skip: self.skip,
+            limit: self.limit,
+            set_clause: None,
+            delete_clause: None,
+        }
+    }
+
+    fn return_item(mut self, expression: ast::Expression, alias: Option<&str>) -> Self {
+        self.return_clause.items.push(ast::ReturnItem {
+            expression,
+            alias: alias.map(String::from),
+        });
+        self
+    }
+
+    fn current_part(&mut self) -> &mut ast::PatternPart {
+        self.patterns
+            .last_mut()
+            .expect("QueryBuilder always starts with a node")
+    }
+
+    fn last_node(&mut self) -> &mut ast::NodePattern {
+        match self.current_part().chains.last_mut() {
+            Some(ast::PatternChain::Node(node))
+            | Some(ast::PatternChain::Relationship(_, node)) => node,
+            None => unreachable!("pattern parts are never empty"),
+        }
+    }
+}
+
+fn node(variable: &str) -> ast::NodePattern {
+    ast::NodePattern {
+        variable: Some(variable.to_string()),
+        labels: Vec::new(),
+        any_label: false,
+        properties: Vec::new(),
+    }
+}
+
+fn property_or_variable(property: &str) -> ast::PropertyOrVariable {
+    match property.split_once('.') {
+        Some((variable, prop)) => ast::PropertyOrVariable {
+            variable: variable.to_string(),
+            property: Some(prop.to_string()),
+        },
+        None => ast::PropertyOrVariable {
+            variable: property.to_string(),
+            property: None,
+        },
+    }
+}
+
+fn comparison_of(property: &str) -> ast::Expression {
+    ast::Expression::Comparison(ast::Comparison {
+        left: ast::ValueExpression::PropertyOrVariable(property_or_variable(property)),
+        operator: None,
+        right: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CypherEngine;
+    use crate::engine::QueryExecutor;
+    use crate::parser::parse_query;
+    use serde_json::json;
+
+    #[test]
+    fn test_builder_matches_parsed_query() {
+        let built = QueryBuilder::match_node("u")
+            .with_label("users")
+            .outgoing("friends", "f")
+            .with_property("active", "yes")
+            .where_eq("u.role", "admin")
+            .where_cmp("f.age", ast::ComparisonOperator::Gt, 20)
+            .distinct()
+            .return_prop("u.id")
+            .return_prop_as("f.name", "friend")
+            .order_by("f.name", ast::SortDirection::Desc)
+            .skip(1)
+            .limit(2)
+            .build();
+
+        let parsed = parse_query(
+            "MATCH (u:users)-[:friends]->(f {active: \"yes\"}) \
+             WHERE u.role = \"admin\" AND f.age > 20 \
+             RETURN DISTINCT u.id, f.name AS friend ORDER BY f.name DESC SKIP 1 LIMIT 2",
+        )
+        .unwrap();
+        assert_eq!(built, parsed);
+    }
+
+    #[test]
+    fn test_builder_executes_like_parsed_query() {
+        let data = json!({
+            "users": [
+                { "id": "1", "role": "admin" },
+                { "id": "2", "role": "user" },
+                { "id": "3", "role": "admin" }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let built = QueryBuilder::match_node("u")
+            .with_label("users")
+            .where_eq("u.role", "admin \" OR u.role = \"user")
+            .return_count("u")
+            .build();
+        let result = QueryExecutor::execute(&built, engine.graph()).unwrap();
+        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(0));
+
+        let built = QueryBuilder::match_node("u")
+            .with_label("users")
+            .where_eq("u.role", "admin")
+            .return_prop("u.id")
+            .build();
+        let expected = engine
+            .execute("MATCH (u:users) WHERE u.role = \"admin\" RETURN u.id")
+            .unwrap();
+        let result = QueryExecutor::execute(&built, engine.graph()).unwrap();
+        assert_eq!(result.rows, expected.rows);
+    }
+}
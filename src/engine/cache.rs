@@ -0,0 +1,109 @@
+//! Least-recently-used cache of query results keyed by query string.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::QueryResult;
+
+/// A bounded cache mapping query strings to their results.
+///
+/// When full, inserting a new query evicts the least recently used one.
+/// The cache knows nothing about the graph; its owner must [`clear`] it
+/// whenever the graph or the execution options change.
+///
+/// [`clear`]: QueryCache::clear
+#[derive(Debug, Clone)]
+pub struct QueryCache {
+    capacity: usize,
+    entries: HashMap<String, QueryResult>,
+    /// Queries from least to most recently used
+    order: VecDeque<String>,
+}
+
+impl QueryCache {
+    /// Create a cache holding at most `capacity` results.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Look up a cached result, marking it as most recently used.
+    pub fn get(&mut self, query: &str) -> Option<QueryResult> {
+        let result = self.entries.get(query)?.clone();
+        self.touch(query);
+        Some(result)
+    }
+
+    /// Cache the result of `query`, evicting the least recently used entry
+    /// if the cache is full.
+    pub fn insert(&mut self, query: &str, result: QueryResult) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.insert(query.to_string(), result).is_some() {
+            self.touch(query);
+            return;
+        }
+        self.order.push_back(query.to_string());
+        if self.order.len() > self.capacity
+            && let Some(evicted) = self.order.pop_front()
+        {
+            self.entries.remove(&evicted);
+        }
+    }
+
+    /// Remove every cached result.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    /// Number of cached results.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache holds no results.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn touch(&mut self, query: &str) {
+        if let Some(pos) = self.order.iter().position(|q| q == query) {
+            let q = self.order.remove(pos).unwrap();
+            self.order.push_back(q);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(n: i64) -> QueryResult {
+        QueryResult::new(vec!["n".to_string()], vec![serde_json::json!({ "n": n })])
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = QueryCache::new(2);
+        cache.insert("a", result(1));
+        cache.insert("b", result(2));
+        assert!(cache.get("a").is_some());
+        cache.insert("c", result(3));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").unwrap().rows, result(1).rows);
+        assert!(cache.get("c").is_some());
+
+        cache.clear();
+        assert!(cache.is_empty());
+
+        let mut disabled = QueryCache::new(0);
+        disabled.insert("a", result(1));
+        assert!(disabled.is_empty());
+    }
+}
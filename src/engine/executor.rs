@@ -1,8 +1,9 @@
-use crate::engine::functions::EvalContext;
+use crate::engine::functions::FunctionRegistry;
 use crate::graph::Graph;
 use crate::parser::ast;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 
 use super::{EngineError, QueryResult, Result};
 
@@ -11,63 +12,641 @@ use super::{EngineError, QueryResult, Result};
 pub enum EntityId {
     Node(usize),
     Relationship {
+        /// Index of the matched edge in `Graph::edges`, so that parallel
+        /// edges of the same type keep their own properties.
+        edge_idx: usize,
         from_idx: usize,
         to_idx: usize,
         rel: String,
+        /// Whether the edge runs from the left node of the matched hop to
+        /// its right node, as the pattern is written.
+        forward: bool,
     },
+    /// A computed value bound by `WITH`, such as a property or a `COLLECT` list
+    Value(Value),
 }
 
 /// Type alias for variable bindings during query execution.
 pub type Bindings = HashMap<String, EntityId>;
 
+/// Options controlling query execution.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionOptions {
+    /// Maximum number of intermediate bindings allowed while matching.
+    ///
+    /// Checked incrementally during pattern matching so that runaway
+    /// queries (e.g. cartesian products) abort before exhausting memory.
+    pub max_rows: Option<usize>,
+    /// Maximum number of hops for variable-length relationships, such as
+    /// `[*]`, `[*2..]` or `[*1..50]`.
+    ///
+    /// Applies to explicit upper bounds too, so that a large range can't
+    /// outrun it. Defaults to [`ExecutionOptions::DEFAULT_MAX_PATH_DEPTH`]
+    /// when unset.
+    pub max_path_depth: Option<usize>,
+    /// Fail a query whose variable-length pattern would go deeper than
+    /// [`ExecutionOptions::max_path_depth`], instead of silently stopping
+    /// there. Off by default.
+    pub strict_path_depth: bool,
+    /// Compare `CONTAINS`, `STARTS WITH` and `ENDS WITH` operands, including
+    /// the elements of a list searched with `CONTAINS`, without regard to
+    /// case. Off by default, matching Cypher.
+    pub case_insensitive_text: bool,
+    /// Record the row count before `SKIP`/`LIMIT` in
+    /// [`QueryResult::total_count`]. Off by default.
+    pub total_count: bool,
+    /// Scalar functions callable from queries; the built-ins plus any
+    /// registered custom functions.
+    pub functions: FunctionRegistry,
+}
+
+impl ExecutionOptions {
+    /// Hop limit applied to unbounded variable-length relationships.
+    pub const DEFAULT_MAX_PATH_DEPTH: usize = 10;
+}
+
+/// Adjacency lists of the graph's edges, as (neighbor, edge index, forward)
+/// entries; the flag records whether the edge is traversed in its stored
+/// direction.
+///
+/// Built once per query and shared by every relationship hop, so patterns
+/// run once per row (OPTIONAL MATCH) don't rescan the edge list each time.
+struct Adjacency {
+    forward: HashMap<usize, Vec<(usize, usize, bool)>>,
+    backward: HashMap<usize, Vec<(usize, usize, bool)>>,
+}
+
+impl Adjacency {
+    fn new(graph: &Graph) -> Self {
+        let mut forward: HashMap<usize, Vec<(usize, usize, bool)>> = HashMap::new();
+        let mut backward: HashMap<usize, Vec<(usize, usize, bool)>> = HashMap::new();
+        for (edge_idx, edge) in graph.edges.iter().enumerate() {
+            forward
+                .entry(edge.from)
+                .or_default()
+                .push((edge.to, edge_idx, true));
+            backward
+                .entry(edge.to)
+                .or_default()
+                .push((edge.from, edge_idx, false));
+        }
+        Self { forward, backward }
+    }
+}
+
 /// Cypher query executor.
 ///
 /// Executes parsed Cypher queries against a graph.
+///
+/// # Row order
+///
+/// Without `ORDER BY`, rows come out in an order that depends only on the
+/// query and the graph, so `SKIP`/`LIMIT` pages are stable across runs:
+///
+/// - A node pattern yields nodes in index order (the order of
+///   [`Graph::nodes`], i.e. insertion order).
+/// - Each hop yields, for every row so far, the edges of the node being
+///   expanded in [`Graph::edges`] order; for undirected hops outgoing edges
+///   come before incoming ones. So rows are ordered by start node index,
+///   then edge order.
+/// - Comma-separated patterns nest from left to right.
+/// - A pattern is expanded from its first node unless its last node is
+///   more selective (already bound, pinned by a WHERE equality, or the
+///   only labeled end), in which case the last node's index order leads.
+/// - Aggregation emits groups in order of first appearance, and
+///   `DISTINCT` keeps the first occurrence of each row.
 pub struct QueryExecutor;
 
 impl QueryExecutor {
     /// Execute a parsed query against a graph.
     pub fn execute(query: &ast::Query, graph: &Graph) -> Result<QueryResult> {
-        // 1. Match patterns
-        let mut bindings_list: Vec<Bindings> = vec![HashMap::new()];
+        Self::execute_with_options(query, graph, &ExecutionOptions::default())
+    }
+
+    /// Execute a parsed query against a graph with the given options.
+    pub fn execute_with_options(
+        query: &ast::Query,
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> Result<QueryResult> {
+        if query.set_clause.is_some() || query.delete_clause.is_some() {
+            return Err(EngineError::ExecutionError(
+                "SET and DELETE require a mutable graph; use execute_mut".to_string(),
+            ));
+        }
+
+        let bindings_list = Self::match_and_filter(query, graph, options)?;
+        Self::project(query, bindings_list, graph, options)
+    }
+
+    /// Execute a parsed query, passing each row to `emit` instead of
+    /// collecting a [`QueryResult`]; returns the number of rows emitted.
+    ///
+    /// Plain projections are emitted as each match is projected, so only
+    /// the matches are held in memory. Queries with aggregates, `DISTINCT`
+    /// or `ORDER BY` need every row first; they are projected as by
+    /// [`QueryExecutor::execute_with_options`] and emitted afterwards.
+    /// [`ExecutionOptions::total_count`] has no effect here.
+    pub fn execute_for_each(
+        query: &ast::Query,
+        graph: &Graph,
+        options: &ExecutionOptions,
+        mut emit: impl FnMut(Value) -> Result<()>,
+    ) -> Result<usize> {
+        if query.set_clause.is_some() || query.delete_clause.is_some() {
+            return Err(EngineError::ExecutionError(
+                "SET and DELETE require a mutable graph; use execute_mut".to_string(),
+            ));
+        }
+
+        let bindings_list = Self::match_and_filter(query, graph, options)?;
+        let streamable = !query.return_clause.distinct
+            && query.order_by_clause.is_none()
+            && !query
+                .return_clause
+                .items
+                .iter()
+                .any(|item| matches!(&item.expression, ast::Expression::Aggregate(_)));
+        if !streamable {
+            let options = ExecutionOptions {
+                total_count: false,
+                ..options.clone()
+            };
+            let result = Self::project(query, bindings_list, graph, &options)?;
+            let count = result.rows.len();
+            for row in &result.rows {
+                emit(result.row_object(row))?;
+            }
+            return Ok(count);
+        }
+
+        let columns = Self::return_columns(&query.return_clause);
+        let rows = bindings_list
+            .iter()
+            .skip(query.skip.unwrap_or(0))
+            .take(query.limit.unwrap_or(usize::MAX));
+        let mut count = 0;
+        for bindings in rows {
+            emit(Self::project_row(
+                &query.return_clause,
+                &columns,
+                bindings,
+                graph,
+                options,
+            ))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Execute a parsed query that may mutate the graph.
+    ///
+    /// Read-only queries behave exactly as in [`QueryExecutor::execute_with_options`].
+    pub fn execute_mut(
+        query: &ast::Query,
+        graph: &mut Graph,
+        options: &ExecutionOptions,
+    ) -> Result<QueryResult> {
+        let bindings_list = Self::match_and_filter(query, graph, options)?;
+
+        if let Some(set_clause) = &query.set_clause {
+            return super::mutation::apply_set(set_clause, &bindings_list, graph, options);
+        }
+        if let Some(delete_clause) = &query.delete_clause {
+            return super::mutation::apply_delete(delete_clause, &bindings_list, graph)
+                .map_err(|e| e.context("DELETE"));
+        }
+
+        Self::project(query, bindings_list, graph, options)
+    }
+
+    /// Match the MATCH patterns and apply the WHERE filter, then run each
+    /// WITH stage over the result.
+    fn match_and_filter(
+        query: &ast::Query,
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> Result<Vec<Bindings>> {
+        Self::validate_pattern_variables(&query.match_clause).map_err(|e| e.context("MATCH"))?;
+        for optional_match in &query.optional_matches {
+            Self::validate_pattern_variables(&optional_match.match_clause)
+                .map_err(|e| e.context("OPTIONAL MATCH"))?;
+        }
+        for match_clause in query.with_clauses.iter().flat_map(|w| &w.match_clause) {
+            Self::validate_pattern_variables(match_clause).map_err(|e| e.context("MATCH"))?;
+        }
+        Self::validate_functions(query, &options.functions)?;
+        Self::validate_item_variables(query)?;
+
+        // Built once per query: OPTIONAL MATCH runs its pattern per row
+        let adjacency = Adjacency::new(graph);
+        let mut bindings_list = Self::match_patterns(
+            &query.match_clause,
+            query.where_clause.as_ref(),
+            vec![HashMap::new()],
+            graph,
+            &adjacency,
+            options,
+        )
+        .map_err(|e| e.context("MATCH"))?;
+
+        for optional_match in &query.optional_matches {
+            bindings_list =
+                Self::optional_match(optional_match, bindings_list, graph, &adjacency, options)
+                    .map_err(|e| e.context("OPTIONAL MATCH"))?;
+        }
+
+        for with_clause in &query.with_clauses {
+            bindings_list =
+                Self::project_with(&with_clause.projection, bindings_list, graph, options)?;
+            if let Some(where_clause) = &with_clause.where_clause {
+                bindings_list.retain(|bindings| {
+                    Self::evaluate_expression(&where_clause.expression, bindings, graph, options)
+                });
+            }
+            if let Some(match_clause) = &with_clause.match_clause {
+                bindings_list = Self::match_patterns(
+                    match_clause,
+                    with_clause.match_where.as_ref(),
+                    bindings_list,
+                    graph,
+                    &adjacency,
+                    options,
+                )
+                .map_err(|e| e.context("MATCH"))?;
+            }
+        }
+
+        Ok(bindings_list)
+    }
+
+    /// Extend each row with the matches of an OPTIONAL MATCH.
+    ///
+    /// A row without any match (after the clause's WHERE) is kept once, with
+    /// the pattern's new variables bound to null.
+    fn optional_match(
+        optional_match: &ast::OptionalMatchClause,
+        bindings_list: Vec<Bindings>,
+        graph: &Graph,
+        adjacency: &Adjacency,
+        options: &ExecutionOptions,
+    ) -> Result<Vec<Bindings>> {
+        let mut extended = Vec::with_capacity(bindings_list.len());
+        for bindings in bindings_list {
+            let matches = Self::match_patterns(
+                &optional_match.match_clause,
+                optional_match.where_clause.as_ref(),
+                vec![bindings.clone()],
+                graph,
+                adjacency,
+                options,
+            )?;
+            if matches.is_empty() {
+                let mut row = bindings;
+                for var in optional_match.match_clause.variables() {
+                    row.entry(var.to_string())
+                        .or_insert(EntityId::Value(Value::Null));
+                }
+                extended.push(row);
+            } else {
+                extended.extend(matches);
+            }
+            Self::check_row_limit(&extended, options)?;
+        }
+        Ok(extended)
+    }
+
+    /// Extend each of `bindings_list` with the matches of a MATCH clause,
+    /// filtered by its WHERE clause.
+    ///
+    /// The WHERE clause is split into its top-level AND conjuncts, and each
+    /// conjunct is applied as soon as every variable it references is bound,
+    /// so selective filters prune bindings before further expansion.
+    fn match_patterns(
+        match_clause: &ast::MatchClause,
+        where_clause: Option<&ast::WhereClause>,
+        mut bindings_list: Vec<Bindings>,
+        graph: &Graph,
+        adjacency: &Adjacency,
+        options: &ExecutionOptions,
+    ) -> Result<Vec<Bindings>> {
+        // 1. Match patterns, filtering with WHERE conjuncts as their variables are bound
+        let mut pending: Vec<(&ast::Expression, HashSet<&str>)> = where_clause
+            .iter()
+            .flat_map(|w| Self::conjuncts(&w.expression))
+            .map(|expr| {
+                let mut vars = HashSet::new();
+                expr.collect_variables(&mut vars);
+                (expr, vars)
+            })
+            .collect();
+        // Variables carried over from an earlier WITH are bound from the start
+        let mut bound: HashSet<String> = bindings_list
+            .first()
+            .map(|bindings| bindings.keys().cloned().collect())
+            .unwrap_or_default();
 
-        for pattern_part in &query.match_clause.patterns {
+        for (part_idx, pattern_part) in match_clause.patterns.iter().enumerate() {
+            let pattern_part = Self::push_down_labels(pattern_part, where_clause);
+            let pattern_part = Self::plan_pattern_part(&pattern_part, &bindings_list, where_clause);
+            // The planner only builds a new pattern when it reverses one
+            let reversed = matches!(pattern_part, Cow::Owned(_));
             let mut last_node_variable: Option<String> = None;
 
-            for chain in &pattern_part.chains {
+            for (chain_idx, chain) in pattern_part.chains.iter().enumerate() {
                 match chain {
                     ast::PatternChain::Node(node_pat) => {
-                        if let Some(ref v) = node_pat.variable {
-                            last_node_variable = Some(v.clone());
-                        }
-                        bindings_list = Self::match_node_pattern(node_pat, graph, bindings_list);
+                        let node_pat = Self::bind_anonymous(node_pat, part_idx, chain_idx);
+                        last_node_variable = node_pat.variable.clone();
+                        bindings_list =
+                            Self::match_node_pattern(&node_pat, graph, bindings_list, options)?;
+                        bound.extend(node_pat.variable.iter().cloned());
                     }
                     ast::PatternChain::Relationship(rel_pat, node_pat) => {
+                        let node_pat = Self::bind_anonymous(node_pat, part_idx, chain_idx);
                         if let Some(ref start_var) = last_node_variable {
                             bindings_list = Self::match_relationship_pattern(
                                 start_var,
                                 rel_pat,
-                                node_pat,
+                                &node_pat,
+                                reversed,
                                 graph,
+                                adjacency,
                                 bindings_list,
-                            );
+                                options,
+                            )?;
+                        }
+                        last_node_variable = node_pat.variable.clone();
+                        bound.extend(rel_pat.variable.iter().cloned());
+                        bound.extend(node_pat.variable.iter().cloned());
+                    }
+                }
+                Self::apply_ready_filters(
+                    &mut pending,
+                    |var| bound.contains(var),
+                    &mut bindings_list,
+                    graph,
+                    options,
+                );
+            }
+        }
+
+        // 2. Apply the remaining conjuncts (those naming variables MATCH never binds)
+        Self::apply_ready_filters(&mut pending, |_| true, &mut bindings_list, graph, options);
+
+        // 3. Drop the anonymous nodes' placeholders, whose names repeat in
+        // every clause and would otherwise pin later clauses to these nodes
+        for bindings in &mut bindings_list {
+            bindings.retain(|var, _| !var.starts_with('#'));
+        }
+
+        Ok(bindings_list)
+    }
+
+    /// Filter bindings by every pending conjunct whose variables are all
+    /// bound, removing those conjuncts from `pending`.
+    fn apply_ready_filters(
+        pending: &mut Vec<(&ast::Expression, HashSet<&str>)>,
+        is_bound: impl Fn(&str) -> bool,
+        bindings_list: &mut Vec<Bindings>,
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) {
+        let (ready, rest): (Vec<_>, Vec<_>) = pending
+            .drain(..)
+            .partition(|(_, vars)| vars.iter().all(|v| is_bound(v)));
+        *pending = rest;
+        if ready.is_empty() {
+            return;
+        }
+        bindings_list.retain(|bindings| {
+            ready
+                .iter()
+                .all(|(expr, _)| Self::evaluate_expression(expr, bindings, graph, options))
+        });
+    }
+
+    /// Split an expression into its top-level AND conjuncts.
+    fn conjuncts(expr: &ast::Expression) -> Vec<&ast::Expression> {
+        match expr {
+            ast::Expression::And(exprs) => exprs.iter().flat_map(Self::conjuncts).collect(),
+            _ => vec![expr],
+        }
+    }
+
+    /// Reject MATCH patterns that reuse a relationship variable.
+    ///
+    /// A relationship variable names a single edge per row, so binding it
+    /// twice is an error in Cypher. Node variables may repeat; repeated
+    /// occurrences constrain the match to the same node.
+    fn validate_pattern_variables(match_clause: &ast::MatchClause) -> Result<()> {
+        let mut node_vars = std::collections::HashSet::new();
+        let mut rel_vars = std::collections::HashSet::new();
+
+        for chain in match_clause.patterns.iter().flat_map(|p| &p.chains) {
+            let (rel_pat, node_pat) = match chain {
+                ast::PatternChain::Node(node_pat) => (None, node_pat),
+                ast::PatternChain::Relationship(rel_pat, node_pat) => (Some(rel_pat), node_pat),
+            };
+            if let Some(var) = rel_pat.and_then(|r| r.variable.as_ref()) {
+                if !rel_vars.insert(var) {
+                    return Err(EngineError::ExecutionError(format!(
+                        "Relationship variable {} cannot be used more than once in MATCH",
+                        var
+                    )));
+                }
+                if node_vars.contains(var) {
+                    return Err(EngineError::ExecutionError(format!(
+                        "Variable {} is used for both a node and a relationship",
+                        var
+                    )));
+                }
+            }
+            if let Some(var) = &node_pat.variable {
+                if rel_vars.contains(var) {
+                    return Err(EngineError::ExecutionError(format!(
+                        "Variable {} is used for both a node and a relationship",
+                        var
+                    )));
+                }
+                node_vars.insert(var);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject WITH and RETURN items that reference a variable no earlier
+    /// clause binds.
+    ///
+    /// After a WITH, only the names it projects (plus those bound by its own
+    /// MATCH) remain in scope.
+    fn validate_item_variables(query: &ast::Query) -> Result<()> {
+        fn check_items(
+            clause: &str,
+            items: &[ast::ReturnItem],
+            scope: &HashSet<&str>,
+        ) -> Result<()> {
+            for (i, item) in items.iter().enumerate() {
+                let mut vars = HashSet::new();
+                item.expression.collect_variables(&mut vars);
+                let mut unbound: Vec<_> = vars
+                    .into_iter()
+                    .filter(|var| *var != "*" && !scope.contains(var))
+                    .collect();
+                unbound.sort_unstable();
+                if let Some(var) = unbound.first() {
+                    return Err(EngineError::ExecutionError(format!(
+                        "Variable not bound: {}",
+                        var
+                    ))
+                    .context(format_args!("{} item {}", clause, i + 1)));
+                }
+            }
+            Ok(())
+        }
+
+        let mut scope: HashSet<&str> = query
+            .match_clause
+            .variables()
+            .chain(
+                query
+                    .optional_matches
+                    .iter()
+                    .flat_map(|o| o.match_clause.variables()),
+            )
+            .collect();
+        for with_clause in &query.with_clauses {
+            check_items("WITH", &with_clause.projection.items, &scope)?;
+            scope = with_clause
+                .projection
+                .items
+                .iter()
+                .map(|item| match (&item.alias, &item.expression) {
+                    (Some(alias), _) => alias.as_str(),
+                    (None, expr) => match expr {
+                        ast::Expression::Comparison(comp) => comp
+                            .left
+                            .as_property_or_variable()
+                            .map_or("", |pv| pv.variable.as_str()),
+                        _ => "",
+                    },
+                })
+                .chain(
+                    with_clause
+                        .match_clause
+                        .iter()
+                        .flat_map(ast::MatchClause::variables),
+                )
+                .collect();
+        }
+        check_items("RETURN", &query.return_clause.items, &scope)
+    }
 
-                            if let Some(ref v) = node_pat.variable {
-                                last_node_variable = Some(v.clone());
+    /// Reject calls to unknown scalar functions, or with the wrong number of
+    /// arguments, anywhere in WHERE, WITH, RETURN or SET.
+    ///
+    /// Calls whose arguments are all literals are evaluated here too, so
+    /// that e.g. `range(1, 5, 0)` fails instead of yielding `null` per row.
+    fn validate_functions(query: &ast::Query, functions: &FunctionRegistry) -> Result<()> {
+        fn check_value(expr: &ast::ValueExpression, functions: &FunctionRegistry) -> Result<()> {
+            match expr {
+                ast::ValueExpression::Function(func) => {
+                    functions
+                        .validate(&func.name, func.args.len())
+                        .map_err(|e| EngineError::ExecutionError(e.to_string()))?;
+                    let literals: Option<Vec<Value>> = func
+                        .args
+                        .iter()
+                        .map(|arg| match arg {
+                            ast::ValueExpression::Literal(lit) => {
+                                Some(super::mutation::literal_value(lit))
                             }
-                        }
+                            _ => None,
+                        })
+                        .collect();
+                    if let Some(args) = literals {
+                        functions
+                            .call(&func.name, &args)
+                            .map_err(|e| EngineError::ExecutionError(e.to_string()))?;
                     }
+                    func.args
+                        .iter()
+                        .try_for_each(|arg| check_value(arg, functions))
                 }
+                ast::ValueExpression::Add(left, right)
+                | ast::ValueExpression::Multiply(left, right) => {
+                    check_value(left, functions)?;
+                    check_value(right, functions)
+                }
+                _ => Ok(()),
+            }
+        }
+        fn check_expression(expr: &ast::Expression, functions: &FunctionRegistry) -> Result<()> {
+            match expr {
+                ast::Expression::And(exprs) | ast::Expression::Or(exprs) => exprs
+                    .iter()
+                    .try_for_each(|e| check_expression(e, functions)),
+                ast::Expression::Comparison(comp) => {
+                    check_value(&comp.left, functions)?;
+                    comp.right
+                        .iter()
+                        .try_for_each(|right| check_value(right, functions))
+                }
+                ast::Expression::Aggregate(_) | ast::Expression::HasLabels(_) => Ok(()),
             }
         }
 
-        // 2. Filter with WHERE
-        if let Some(where_clause) = &query.where_clause {
-            bindings_list.retain(|bindings| {
-                Self::evaluate_expression(&where_clause.expression, bindings, graph)
-            });
+        for where_clause in query
+            .where_clause
+            .iter()
+            .chain(query.optional_matches.iter().flat_map(|o| &o.where_clause))
+        {
+            check_expression(&where_clause.expression, functions)
+                .map_err(|e| e.context("WHERE"))?;
+        }
+        for with_clause in &query.with_clauses {
+            for (i, item) in with_clause.projection.items.iter().enumerate() {
+                check_expression(&item.expression, functions)
+                    .map_err(|e| e.context(format_args!("WITH item {}", i + 1)))?;
+            }
+            for where_clause in [&with_clause.where_clause, &with_clause.match_where]
+                .into_iter()
+                .flatten()
+            {
+                check_expression(&where_clause.expression, functions)
+                    .map_err(|e| e.context("WHERE"))?;
+            }
+        }
+        for (i, item) in query.return_clause.items.iter().enumerate() {
+            check_expression(&item.expression, functions)
+                .map_err(|e| e.context(format_args!("RETURN item {}", i + 1)))?;
+        }
+        if let Some(order_by) = &query.order_by_clause {
+            // Items naming a returned column (such as `COUNT(u)`) aren't calls
+            let columns = Self::return_columns(&query.return_clause);
+            for (i, item) in order_by.items.iter().enumerate() {
+                if !columns.contains(&item.expression.to_string()) {
+                    check_value(&item.expression, functions)
+                        .map_err(|e| e.context(format_args!("ORDER BY item {}", i + 1)))?;
+                }
+            }
+        }
+        if let Some(set_clause) = &query.set_clause {
+            for (i, item) in set_clause.items.iter().enumerate() {
+                check_value(&item.value, functions)
+                    .map_err(|e| e.context(format_args!("SET item {}", i + 1)))?;
+            }
         }
+        Ok(())
+    }
 
+    /// Project matched bindings through the RETURN clause.
+    fn project(
+        query: &ast::Query,
+        bindings_list: Vec<Bindings>,
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> Result<QueryResult> {
         // 3. Project with RETURN
         let has_aggregate = query
             .return_clause
@@ -75,321 +654,1021 @@ impl QueryExecutor {
             .iter()
             .any(|item| matches!(&item.expression, ast::Expression::Aggregate(_)));
 
-        if has_aggregate {
-            Self::execute_aggregate_return(&query.return_clause, bindings_list, graph)
+        // ORDER BY items that aren't returned columns are evaluated per row,
+        // before the bindings are consumed by the projection
+        let sort_keys = match &query.order_by_clause {
+            Some(order_by) => Self::sort_keys(
+                query,
+                order_by,
+                has_aggregate,
+                &bindings_list,
+                graph,
+                options,
+            )?,
+            None => None,
+        };
+
+        let mut result = if has_aggregate {
+            Self::execute_aggregate_return(&query.return_clause, bindings_list, graph, options)?
         } else {
-            let mut result =
-                Self::execute_normal_return(&query.return_clause, bindings_list, graph)?;
-            if query.return_clause.distinct {
-                super::result_processor::deduplicate_rows(&mut result);
-            }
-            if let Some(order_by) = &query.order_by_clause {
-                super::result_processor::sort_rows(&mut result, order_by);
+            Self::execute_normal_return(&query.return_clause, bindings_list, graph, options)?
+        };
+
+        // Post-process in Cypher order: distinct -> sort -> skip -> limit
+        if query.return_clause.distinct {
+            super::result_processor::deduplicate_rows(&mut result);
+        }
+        if let Some(order_by) = &query.order_by_clause {
+            match sort_keys {
+                Some(keys) => {
+                    super::result_processor::sort_rows_by_keys(&mut result, keys, order_by)
+                }
+                None => super::result_processor::sort_rows(&mut result, order_by),
             }
-            Ok(result)
         }
+        if options.total_count {
+            result.total_count = Some(result.rows.len());
+        }
+        super::result_processor::skip_and_limit(&mut result, query.skip, query.limit);
+        Ok(result)
+    }
+
+    /// The RETURN column names, as aliases or rendered expressions.
+    fn return_columns(return_clause: &ast::ReturnClause) -> Vec<String> {
+        return_clause
+            .items
+            .iter()
+            .map(|item| {
+                item.alias
+                    .clone()
+                    .unwrap_or_else(|| Self::expression_column_name(&item.expression))
+            })
+            .collect()
+    }
+
+    /// Sort keys for every matched row, or `None` when each ORDER BY item
+    /// names a returned column and the projected rows can be sorted as is.
+    ///
+    /// Computed keys need one row per binding, so with DISTINCT or
+    /// aggregates every item must name a returned column.
+    fn sort_keys(
+        query: &ast::Query,
+        order_by: &ast::OrderByClause,
+        has_aggregate: bool,
+        bindings_list: &[Bindings],
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> Result<Option<Vec<Vec<Value>>>> {
+        let columns = Self::return_columns(&query.return_clause);
+        let Some((i, item)) = order_by
+            .items
+            .iter()
+            .enumerate()
+            .find(|(_, item)| !columns.contains(&item.expression.to_string()))
+        else {
+            return Ok(None);
+        };
+        if has_aggregate || query.return_clause.distinct {
+            return Err(EngineError::ExecutionError(format!(
+                "ORDER BY item {}: {} is not returned; with DISTINCT or aggregates, \
+                 sort by a returned column or alias",
+                i + 1,
+                item.expression
+            )));
+        }
+
+        // Items naming a column take the value of that RETURN item
+        let returned: Vec<Option<&ast::ReturnItem>> = order_by
+            .items
+            .iter()
+            .map(|item| {
+                let name = item.expression.to_string();
+                let col = columns.iter().position(|c| *c == name)?;
+                query.return_clause.items.get(col)
+            })
+            .collect();
+        let keys = bindings_list
+            .iter()
+            .map(|bindings| {
+                order_by
+                    .items
+                    .iter()
+                    .zip(&returned)
+                    .map(|(item, returned)| match returned {
+                        Some(ret) => Self::evaluate_expression_value(
+                            &ret.expression,
+                            bindings,
+                            graph,
+                            options,
+                        ),
+                        None => super::mutation::evaluate_value(
+                            &item.expression,
+                            bindings,
+                            graph,
+                            options,
+                        ),
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Some(keys))
     }
 
+    /// Project aggregate RETURN items, grouping by the non-aggregate items.
+    ///
+    /// As in Cypher, every non-aggregate item is an implicit grouping key
+    /// and aggregates are computed per group over its matched rows. `COUNT`
+    /// therefore counts rows: with a relationship pattern that is one per
+    /// matched edge, so a neighbor reached over two edges counts twice.
+    /// Groups appear in the order their first row was matched.
     fn execute_aggregate_return(
         return_clause: &ast::ReturnClause,
         bindings_list: Vec<Bindings>,
         graph: &Graph,
+        options: &ExecutionOptions,
     ) -> Result<QueryResult> {
         use crate::engine::functions::AggregateEvaluator;
 
-        let mut columns = Vec::new();
-        let mut values = serde_json::Map::new();
-
-        for item in &return_clause.items {
-            let column_name = item.alias.clone().unwrap_or_else(|| {
-                if let ast::Expression::Aggregate(agg) = &item.expression {
-                    AggregateEvaluator::column_name(agg)
-                } else {
-                    Self::expression_column_name(&item.expression)
-                }
-            });
-
-            let value = match &item.expression {
-                ast::Expression::Aggregate(agg) => {
-                    // Convert bindings to EvalContexts
-                    let contexts: Vec<EvalContext> = bindings_list
-                        .iter()
-                        .map(|bindings| {
-                            let mut ctx = EvalContext::new();
-                            for (var, entity) in bindings {
-                                if let EntityId::Node(idx) = entity {
-                                    ctx.bind(var.clone(), *idx);
-                                }
-                            }
-                            ctx
-                        })
-                        .collect();
-
-                    AggregateEvaluator::evaluate(agg, &contexts, graph)
-                        .map_err(|e| EngineError::ExecutionError(e.to_string()))?
-                }
-                _ => {
-                    return Err(EngineError::ExecutionError(
-                        "Mixed aggregate and non-aggregate in RETURN".to_string(),
-                    ));
-                }
-            };
+        let columns: Vec<String> = return_clause
+            .items
+            .iter()
+            .map(|item| {
+                item.alias.clone().unwrap_or_else(|| {
+                    if let ast::Expression::Aggregate(agg) = &item.expression {
+                        AggregateEvaluator::column_name(agg)
+                    } else {
+                        Self::expression_column_name(&item.expression)
+                    }
+                })
+            })
+            .collect();
 
-            columns.push(column_name.clone());
-            values.insert(column_name, value);
+        let mut rows = Vec::new();
+        for (key, group) in Self::group_rows(&return_clause.items, bindings_list, graph, options) {
+            let mut key_values = key.into_iter();
+            let mut values = serde_json::Map::new();
+            for (i, (item, column_name)) in return_clause.items.iter().zip(&columns).enumerate() {
+                let value = match &item.expression {
+                    ast::Expression::Aggregate(agg) => {
+                        Self::aggregate_value(agg, &group, graph, options)
+                            .map_err(|e| e.context(format_args!("RETURN item {}", i + 1)))?
+                    }
+                    _ => key_values.next().unwrap_or(Value::Null),
+                };
+                values.insert(column_name.clone(), value);
+            }
+            rows.push(Value::Object(values));
         }
 
         Ok(QueryResult {
             columns,
-            rows: vec![Value::Object(values)],
+            rows,
+            aggregate: true,
+            total_count: None,
         })
     }
 
-    fn execute_normal_return(
-        return_clause: &ast::ReturnClause,
+    /// Partition rows by the values of the non-aggregate items, returning
+    /// each group's key values with its rows in order of first appearance.
+    ///
+    /// Without grouping keys there is exactly one group, even for no matches.
+    fn group_rows(
+        items: &[ast::ReturnItem],
         bindings_list: Vec<Bindings>,
         graph: &Graph,
-    ) -> Result<QueryResult> {
-        let mut columns = Vec::new();
-        let mut rows = Vec::new();
-
-        for item in &return_clause.items {
-            let column_name = item
-                .alias
-                .clone()
-                .unwrap_or_else(|| Self::expression_column_name(&item.expression));
-            columns.push(column_name);
+        options: &ExecutionOptions,
+    ) -> Vec<(Vec<Value>, Vec<Bindings>)> {
+        let has_grouping_keys = items
+            .iter()
+            .any(|item| !matches!(item.expression, ast::Expression::Aggregate(_)));
+        if !has_grouping_keys {
+            return vec![(Vec::new(), bindings_list)];
         }
 
+        let mut groups: Vec<(Vec<Value>, Vec<Bindings>)> = Vec::new();
+        let mut group_index: HashMap<String, usize> = HashMap::new();
         for bindings in bindings_list {
-            let mut row = serde_json::Map::new();
-
-            for (i, item) in return_clause.items.iter().enumerate() {
-                let column_name = &columns[i];
-                let value = Self::evaluate_expression_value(&item.expression, &bindings, graph);
-                row.insert(column_name.clone(), value);
+            let key: Vec<Value> = items
+                .iter()
+                .filter(|item| !matches!(item.expression, ast::Expression::Aggregate(_)))
+                .map(|item| {
+                    Self::evaluate_expression_value(&item.expression, &bindings, graph, options)
+                })
+                .collect();
+            let serialized = serde_json::to_string(&key).unwrap_or_default();
+            match group_index.get(&serialized) {
+                Some(&idx) => groups[idx].1.push(bindings),
+                None => {
+                    group_index.insert(serialized, groups.len());
+                    groups.push((key, vec![bindings]));
+                }
             }
-
-            rows.push(Value::Object(row));
         }
-
-        Ok(QueryResult { columns, rows })
+        groups
     }
 
-    fn expression_column_name(expr: &ast::Expression) -> String {
-        match expr {
-            ast::Expression::Comparison(comp)
-                if comp.operator.is_none() && comp.right.is_none() =>
-            {
-                if let Some(ref prop) = comp.left.property {
-                    format!("{}.{}", comp.left.variable, prop)
-                } else {
-                    comp.left.variable.clone()
-                }
-            }
-            ast::Expression::Aggregate(agg) => {
-                let func_name = match agg.func {
-                    ast::AggregateFunction::Count => "COUNT",
-                    ast::AggregateFunction::Sum => "SUM",
-                };
+    /// Compute an aggregate over the rows of one group.
+    fn aggregate_value(
+        agg: &ast::AggregateExpression,
+        group: &[Bindings],
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> Result<Value> {
+        use crate::engine::functions::AggregateEvaluator;
 
-                if let Some(ref prop) = agg.property {
-                    format!("{}({}.{})", func_name, agg.variable, prop)
-                } else {
-                    format!("{}({})", func_name, agg.variable)
-                }
-            }
-            _ => "expression".to_string(),
-        }
+        AggregateEvaluator::evaluate(agg, group, graph, options)
+            .map_err(|e| EngineError::ExecutionError(e.to_string()))
     }
 
-    fn match_node_pattern(
-        node_pat: &ast::NodePattern,
+    /// Project rows through a WITH clause into the bindings of the next stage.
+    ///
+    /// Bare variables keep their node or relationship; every other item is
+    /// bound to its value under its alias. Aggregates group rows exactly as
+    /// in RETURN, and DISTINCT drops repeated rows.
+    fn project_with(
+        projection: &ast::ReturnClause,
+        bindings_list: Vec<Bindings>,
         graph: &Graph,
-        current_bindings: Vec<Bindings>,
-    ) -> Vec<Bindings> {
-        let mut next_bindings = Vec::new();
+        options: &ExecutionOptions,
+    ) -> Result<Vec<Bindings>> {
+        let has_aggregate = projection
+            .items
+            .iter()
+            .any(|item| matches!(item.expression, ast::Expression::Aggregate(_)));
+        let groups: Vec<Vec<Bindings>> = if has_aggregate {
+            Self::group_rows(&projection.items, bindings_list, graph, options)
+                .into_iter()
+                .map(|(_, group)| group)
+                .collect()
+        } else {
+            bindings_list.into_iter().map(|b| vec![b]).collect()
+        };
 
-        for bindings in current_bindings {
-            for (i, node) in graph.nodes.iter().enumerate() {
-                // Check labels
-                let label_match = if node_pat.labels.is_empty() {
-                    true
-                } else {
-                    node_pat
-                        .labels
-                        .iter()
-                        .any(|l| node.label.as_ref() == Some(l))
+        let mut projected = Vec::with_capacity(groups.len());
+        let mut seen = HashSet::new();
+        for group in groups {
+            let mut next = Bindings::new();
+            for (i, item) in projection.items.iter().enumerate() {
+                let bare_variable = match &item.expression {
+                    ast::Expression::Comparison(comp) if comp.operator.is_none() => comp
+                        .left
+                        .as_property_or_variable()
+                        .filter(|pv| pv.property.is_none())
+                        .map(|pv| pv.variable.as_str()),
+                    _ => None,
                 };
+                let name = item
+                    .alias
+                    .clone()
+                    .or_else(|| bare_variable.map(str::to_string))
+                    .unwrap_or_else(|| Self::expression_column_name(&item.expression));
 
-                if !label_match {
-                    continue;
-                }
-
-                // Bind variable
-                if let Some(ref var) = node_pat.variable {
-                    if let Some(entity) = bindings.get(var) {
-                        if let EntityId::Node(prev_idx) = entity
-                            && *prev_idx == i
-                        {
-                            next_bindings.push(bindings.clone());
+                let entity = match &item.expression {
+                    ast::Expression::Aggregate(agg) => EntityId::Value(
+                        Self::aggregate_value(agg, &group, graph, options)
+                            .map_err(|e| e.context(format_args!("WITH item {}", i + 1)))?,
+                    ),
+                    expr => {
+                        // Every row of a group shares the grouping keys
+                        let Some(first) = group.first() else {
+                            continue;
+                        };
+                        match bare_variable.and_then(|var| first.get(var)) {
+                            Some(entity) => entity.clone(),
+                            None => EntityId::Value(Self::evaluate_expression_value(
+                                expr, first, graph, options,
+                            )),
                         }
-                    } else {
-                        let mut new_bindings = bindings.clone();
-                        new_bindings.insert(var.clone(), EntityId::Node(i));
-                        next_bindings.push(new_bindings);
                     }
-                } else {
-                    next_bindings.push(bindings.clone());
+                };
+                next.insert(name, entity);
+            }
+
+            if projection.distinct {
+                let mut entries: Vec<_> = next.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                if !seen.insert(format!("{:?}", entries)) {
+                    continue;
                 }
             }
+            projected.push(next);
         }
-        next_bindings
+        Ok(projected)
     }
 
-    fn match_relationship_pattern(
-        start_node_var: &str,
-        rel_pat: &ast::RelationshipPattern,
-        end_node_pat: &ast::NodePattern,
+    fn execute_normal_return(
+        return_clause: &ast::ReturnClause,
+        bindings_list: Vec<Bindings>,
         graph: &Graph,
-        current_bindings: Vec<Bindings>,
-    ) -> Vec<Bindings> {
-        let mut next_bindings = Vec::new();
+        options: &ExecutionOptions,
+    ) -> Result<QueryResult> {
+        let columns = Self::return_columns(return_clause);
+        let rows = bindings_list
+            .iter()
+            .map(|bindings| Self::project_row(return_clause, &columns, bindings, graph, options))
+            .collect();
 
-        // Build adjacency maps
-        let mut forward_adj: HashMap<usize, Vec<(usize, String)>> = HashMap::new();
-        let mut backward_adj: HashMap<usize, Vec<(usize, String)>> = HashMap::new();
+        Ok(QueryResult::new(columns, rows))
+    }
 
-        for edge in &graph.edges {
-            forward_adj
-                .entry(edge.from)
-                .or_default()
-                .push((edge.to, edge.rel_type.clone()));
-            backward_adj
-                .entry(edge.to)
-                .or_default()
-                .push((edge.from, edge.rel_type.clone()));
+    /// Evaluate the RETURN items for one row of bindings.
+    fn project_row(
+        return_clause: &ast::ReturnClause,
+        columns: &[String],
+        bindings: &Bindings,
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> Value {
+        let mut row = serde_json::Map::new();
+        for (column_name, item) in columns.iter().zip(&return_clause.items) {
+            let value = Self::evaluate_expression_value(&item.expression, bindings, graph, options);
+            row.insert(column_name.clone(), value);
         }
+        Value::Object(row)
+    }
 
-        for bindings in current_bindings {
-            if let Some(EntityId::Node(start_idx)) = bindings.get(start_node_var) {
-                let start_idx = *start_idx;
+    fn expression_column_name(expr: &ast::Expression) -> String {
+        match expr {
+            ast::Expression::Comparison(comp)
+                if comp.operator.is_none() && comp.right.is_none() =>
+            {
+                comp.left.to_string()
+            }
+            // Computed columns such as `u.age > 18` are named by their text
+            _ => expr.to_string(),
+        }
+    }
 
-                // Single hop matching
-                let neighbors = match rel_pat.direction {
-                    ast::Direction::Right => {
-                        forward_adj.get(&start_idx).cloned().unwrap_or_default()
-                    }
-                    ast::Direction::Left => {
-                        backward_adj.get(&start_idx).cloned().unwrap_or_default()
-                    }
-                    ast::Direction::Both => {
-                        let mut neighbors =
-                            forward_adj.get(&start_idx).cloned().unwrap_or_default();
-                        neighbors.extend(backward_adj.get(&start_idx).cloned().unwrap_or_default());
-                        neighbors
+    /// Move top-level `WHERE n:label` conjuncts into the pattern's node for
+    /// `n`, so that nodes without the label are never bound and the label
+    /// counts towards [`Self::plan_pattern_part`]'s choice of start node.
+    ///
+    /// The conjuncts are still evaluated as filters, so this only narrows
+    /// the candidates earlier. Nodes with label alternatives (`:a|b`) are
+    /// left alone.
+    fn push_down_labels<'a>(
+        pattern_part: &'a ast::PatternPart,
+        where_clause: Option<&ast::WhereClause>,
+    ) -> Cow<'a, ast::PatternPart> {
+        let predicates: Vec<&ast::LabelPredicate> = where_clause
+            .iter()
+            .flat_map(|w| Self::conjuncts(&w.expression))
+            .filter_map(|expr| match expr {
+                ast::Expression::HasLabels(predicate) => Some(predicate),
+                _ => None,
+            })
+            .collect();
+        if predicates.is_empty() {
+            return Cow::Borrowed(pattern_part);
+        }
+
+        let mut pattern_part = pattern_part.clone();
+        for chain in &mut pattern_part.chains {
+            let node_pat = match chain {
+                ast::PatternChain::Node(node_pat) => node_pat,
+                ast::PatternChain::Relationship(_, node_pat) => node_pat,
+            };
+            if node_pat.any_label {
+                continue;
+            }
+            let Some(var) = &node_pat.variable else {
+                continue;
+            };
+            for predicate in predicates.iter().filter(|p| p.variable == *var) {
+                for label in &predicate.labels {
+                    if !node_pat.labels.contains(label) {
+                        node_pat.labels.push(label.clone());
                     }
+                }
+            }
+        }
+        Cow::Owned(pattern_part)
+    }
+
+    /// Choose the traversal direction for a pattern part.
+    ///
+    /// Patterns are expanded from their first node. When the last node is
+    /// more selective (already bound, pinned by a WHERE equality, or
+    /// labeled while the first node is not), the pattern is reversed so
+    /// that expansion starts from the smaller candidate set.
+    fn plan_pattern_part<'a>(
+        pattern_part: &'a ast::PatternPart,
+        bindings_list: &[Bindings],
+        where_clause: Option<&ast::WhereClause>,
+    ) -> Cow<'a, ast::PatternPart> {
+        let first = match pattern_part.chains.first() {
+            Some(ast::PatternChain::Node(node_pat)) => node_pat,
+            _ => return Cow::Borrowed(pattern_part),
+        };
+        let last = match pattern_part.chains.last() {
+            Some(ast::PatternChain::Relationship(_, node_pat)) => node_pat,
+            _ => return Cow::Borrowed(pattern_part),
+        };
+
+        if last.variable.is_none() {
+            return Cow::Borrowed(pattern_part);
+        }
+
+        let bound = bindings_list.first();
+        if Self::node_selectivity(last, bound, where_clause)
+            > Self::node_selectivity(first, bound, where_clause)
+        {
+            Cow::Owned(Self::reverse_pattern_part(pattern_part))
+        } else {
+            Cow::Borrowed(pattern_part)
+        }
+    }
+
+    /// Rough selectivity score for a node pattern; higher means fewer candidates.
+    fn node_selectivity(
+        node_pat: &ast::NodePattern,
+        bound: Option<&Bindings>,
+        where_clause: Option<&ast::WhereClause>,
+    ) -> u8 {
+        if let Some(ref var) = node_pat.variable {
+            if bound.is_some_and(|b| b.contains_key(var)) {
+                return 3;
+            }
+            if where_clause.is_some_and(|w| Self::has_equality_on(&w.expression, var)) {
+                return 2;
+            }
+        }
+        if !node_pat.properties.is_empty() {
+            return 2;
+        }
+        if node_pat.labels.is_empty() { 0 } else { 1 }
+    }
+
+    /// Whether a top-level conjunct pins `variable` to a literal value.
+    fn has_equality_on(expr: &ast::Expression, variable: &str) -> bool {
+        match expr {
+            ast::Expression::And(exprs) => exprs.iter().any(|e| Self::has_equality_on(e, variable)),
+            ast::Expression::Comparison(comp) => {
+                comp.left
+                    .as_property_or_variable()
+                    .is_some_and(|pv| pv.variable == variable)
+                    && matches!(comp.operator, Some(ast::ComparisonOperator::Eq))
+                    && matches!(comp.right, Some(ast::ValueExpression::Literal(_)))
+            }
+            _ => false,
+        }
+    }
+
+    /// Reverse a pattern part so it is expanded from its last node.
+    fn reverse_pattern_part(pattern_part: &ast::PatternPart) -> ast::PatternPart {
+        let mut nodes = Vec::new();
+        let mut rels = Vec::new();
+
+        for chain in &pattern_part.chains {
+            match chain {
+                ast::PatternChain::Node(node_pat) => nodes.push(node_pat.clone()),
+                ast::PatternChain::Relationship(rel_pat, node_pat) => {
+                    rels.push(rel_pat.clone());
+                    nodes.push(node_pat.clone());
+                }
+            }
+        }
+
+        nodes.reverse();
+        rels.reverse();
+
+        let mut nodes = nodes.into_iter();
+        let mut chains = Vec::new();
+        if let Some(first) = nodes.next() {
+            chains.push(ast::PatternChain::Node(first));
+        }
+        for (mut rel_pat, node_pat) in rels.into_iter().zip(nodes) {
+            rel_pat.direction = match rel_pat.direction {
+                ast::Direction::Left => ast::Direction::Right,
+                ast::Direction::Right => ast::Direction::Left,
+                ast::Direction::Both => ast::Direction::Both,
+            };
+            chains.push(ast::PatternChain::Relationship(rel_pat, node_pat));
+        }
+
+        ast::PatternPart { chains }
+    }
+
+    fn match_node_pattern(
+        node_pat: &ast::NodePattern,
+        graph: &Graph,
+        current_bindings: Vec<Bindings>,
+        options: &ExecutionOptions,
+    ) -> Result<Vec<Bindings>> {
+        let mut next_bindings = Vec::new();
+
+        for bindings in current_bindings {
+            // A variable bound by an earlier clause pins the pattern to its
+            // node, so only that node needs checking
+            if let Some(entity) = node_pat.variable.as_ref().and_then(|var| bindings.get(var)) {
+                if let EntityId::Node(idx) = entity
+                    && Self::node_matches_pattern(node_pat, &graph.nodes[*idx])
+                {
+                    next_bindings.push(bindings.clone());
+                    Self::check_row_limit(&next_bindings, options)?;
+                }
+                continue;
+            }
+
+            for (i, node) in graph.nodes.iter().enumerate() {
+                Self::check_row_limit(&next_bindings, options)?;
+
+                if !Self::node_matches_pattern(node_pat, node) {
+                    continue;
+                }
+
+                // Bind variable
+                if let Some(ref var) = node_pat.variable {
+                    let mut new_bindings = bindings.clone();
+                    new_bindings.insert(var.clone(), EntityId::Node(i));
+                    next_bindings.push(new_bindings);
+                } else {
+                    next_bindings.push(bindings.clone());
+                }
+            }
+        }
+        Self::check_row_limit(&next_bindings, options)?;
+        Ok(next_bindings)
+    }
+
+    /// Give an anonymous node pattern an internal variable so the next hop
+    /// of the chain can continue from the node it matched.
+    ///
+    /// The name contains `#`, which the grammar never accepts in an
+    /// identifier, so it cannot collide with a user variable.
+    fn bind_anonymous(
+        node_pat: &ast::NodePattern,
+        part_idx: usize,
+        chain_idx: usize,
+    ) -> Cow<'_, ast::NodePattern> {
+        if node_pat.variable.is_some() {
+            return Cow::Borrowed(node_pat);
+        }
+        let mut node_pat = node_pat.clone();
+        node_pat.variable = Some(format!("#anon{}_{}", part_idx, chain_idx));
+        Cow::Owned(node_pat)
+    }
+
+    /// Check a node against a pattern's labels and inline properties.
+    fn node_matches_pattern(node_pat: &ast::NodePattern, node: &crate::graph::Node) -> bool {
+        let has_label = |l: &String| node.has_label(l);
+        let label_match = if node_pat.any_label {
+            node_pat.labels.iter().any(has_label)
+        } else {
+            node_pat.labels.iter().all(has_label)
+        };
+
+        label_match
+            && node_pat.properties.iter().all(|(key, lit)| {
+                let expected = match lit {
+                    ast::Literal::String(s) => s.clone(),
+                    ast::Literal::Number(n) => n.to_string(),
                 };
+                node.get_property_as_string(key).as_deref() == Some(expected.as_str())
+            })
+    }
 
-                for (next_idx, rel) in neighbors {
-                    // Check rel_type if specified
-                    let rel_match = if let Some(ref target_rel_type) = rel_pat.rel_type {
-                        &rel == target_rel_type
-                    } else {
-                        true
-                    };
+    /// Abort matching once the intermediate bindings exceed `max_rows`.
+    fn check_row_limit(bindings_list: &[Bindings], options: &ExecutionOptions) -> Result<()> {
+        match options.max_rows {
+            Some(max_rows) if bindings_list.len() > max_rows => Err(EngineError::ExecutionError(
+                "result set exceeded max_rows".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Expand each binding by one hop (or a variable-length path) from
+    /// `start_node_var`. `reversed` marks a pattern the planner reversed, so
+    /// bound relationships report their direction as originally written.
+    #[allow(clippy::too_many_arguments)]
+    fn match_relationship_pattern(
+        start_node_var: &str,
+        rel_pat: &ast::RelationshipPattern,
+        end_node_pat: &ast::NodePattern,
+        reversed: bool,
+        graph: &Graph,
+        adjacency: &Adjacency,
+        current_bindings: Vec<Bindings>,
+        options: &ExecutionOptions,
+    ) -> Result<Vec<Bindings>> {
+        let mut next_bindings = Vec::new();
+
+        let neighbors_of = |idx: usize| -> Vec<(usize, usize, bool)> {
+            let forward = adjacency
+                .forward
+                .get(&idx)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let backward = adjacency
+                .backward
+                .get(&idx)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let neighbors = match rel_pat.direction {
+                ast::Direction::Right => forward.to_vec(),
+                ast::Direction::Left => backward.to_vec(),
+                ast::Direction::Both => [forward, backward].concat(),
+            };
+            neighbors
+                .into_iter()
+                .filter(|(_, edge_idx, _)| {
+                    rel_pat
+                        .rel_type
+                        .as_ref()
+                        .is_none_or(|t| &graph.edges[*edge_idx].rel_type == t)
+                })
+                .collect()
+        };
 
-                    if !rel_match {
-                        continue;
+        for bindings in current_bindings {
+            let Some(EntityId::Node(start_idx)) = bindings.get(start_node_var) else {
+                continue;
+            };
+            let start_idx = *start_idx;
+
+            if let Some(range) = &rel_pat.range {
+                // Variable-length matching: one row per path
+                if rel_pat.variable.is_some() {
+                    return Err(EngineError::ExecutionError(
+                        "Binding a variable-length relationship to a variable is not supported"
+                            .to_string(),
+                    ));
+                }
+                let min_hops = range.start.unwrap_or(1);
+                let depth_cap = options
+                    .max_path_depth
+                    .unwrap_or(ExecutionOptions::DEFAULT_MAX_PATH_DEPTH);
+                let max_hops = range.end.map_or(depth_cap, |end| end.min(depth_cap));
+                let capped = range.end.is_none_or(|end| end > depth_cap);
+
+                let mut ends = Vec::new();
+                Self::expand_paths(
+                    start_idx,
+                    0,
+                    (min_hops, max_hops),
+                    capped,
+                    &neighbors_of,
+                    &mut Vec::new(),
+                    &mut ends,
+                    options,
+                )?;
+
+                for end_idx in ends {
+                    Self::check_row_limit(&next_bindings, options)?;
+                    if Self::node_matches_pattern(end_node_pat, &graph.nodes[end_idx]) {
+                        Self::bind_end_node(
+                            end_node_pat,
+                            end_idx,
+                            bindings.clone(),
+                            &mut next_bindings,
+                        );
                     }
+                }
+                continue;
+            }
 
-                    // Check if current node matches end_node_pat
-                    let node = &graph.nodes[next_idx];
-                    let label_match = if end_node_pat.labels.is_empty() {
-                        true
-                    } else {
-                        end_node_pat
-                            .labels
-                            .iter()
-                            .any(|l| node.label.as_ref() == Some(l))
-                    };
+            // Single hop matching
+            for (next_idx, edge_idx, forward) in neighbors_of(start_idx) {
+                Self::check_row_limit(&next_bindings, options)?;
 
-                    if label_match {
-                        let mut new_bindings = bindings.clone();
-
-                        // Bind relationship variable if present
-                        if let Some(ref r_var) = rel_pat.variable {
-                            new_bindings.insert(
-                                r_var.clone(),
-                                EntityId::Relationship {
-                                    from_idx: start_idx,
-                                    to_idx: next_idx,
-                                    rel: rel.clone(),
-                                },
-                            );
-                        }
+                // Check if current node matches end_node_pat
+                let node = &graph.nodes[next_idx];
+                if Self::node_matches_pattern(end_node_pat, node) {
+                    let mut new_bindings = bindings.clone();
 
-                        // Bind end variable
-                        if let Some(ref var) = end_node_pat.variable {
-                            if let Some(EntityId::Node(prev_idx)) = bindings.get(var) {
-                                if *prev_idx == next_idx {
-                                    next_bindings.push(new_bindings);
-                                }
-                            } else {
-                                new_bindings.insert(var.clone(), EntityId::Node(next_idx));
-                                next_bindings.push(new_bindings);
-                            }
+                    // Bind relationship variable if present, keeping the
+                    // edge's stored endpoints regardless of traversal direction
+                    if let Some(ref r_var) = rel_pat.variable {
+                        let (from_idx, to_idx) = if forward {
+                            (start_idx, next_idx)
                         } else {
-                            next_bindings.push(new_bindings);
-                        }
+                            (next_idx, start_idx)
+                        };
+                        new_bindings.insert(
+                            r_var.clone(),
+                            EntityId::Relationship {
+                                edge_idx,
+                                from_idx,
+                                to_idx,
+                                rel: graph.edges[edge_idx].rel_type.clone(),
+                                forward: forward != reversed,
+                            },
+                        );
                     }
+
+                    Self::bind_end_node(end_node_pat, next_idx, new_bindings, &mut next_bindings);
                 }
             }
         }
 
-        next_bindings
+        Self::check_row_limit(&next_bindings, options)?;
+        Ok(next_bindings)
     }
 
-    fn evaluate_expression(expr: &ast::Expression, bindings: &Bindings, graph: &Graph) -> bool {
+    /// Bind the end node of a hop, or check it against an existing binding.
+    fn bind_end_node(
+        end_node_pat: &ast::NodePattern,
+        end_idx: usize,
+        mut new_bindings: Bindings,
+        next_bindings: &mut Vec<Bindings>,
+    ) {
+        if let Some(ref var) = end_node_pat.variable {
+            match new_bindings.get(var) {
+                Some(EntityId::Node(prev_idx)) => {
+                    if *prev_idx == end_idx {
+                        next_bindings.push(new_bindings);
+                    }
+                }
+                // A value bound by WITH is never a node
+                Some(EntityId::Value(_)) => {}
+                _ => {
+                    new_bindings.insert(var.clone(), EntityId::Node(end_idx));
+                    next_bindings.push(new_bindings);
+                }
+            }
+        } else {
+            next_bindings.push(new_bindings);
+        }
+    }
+
+    /// Walk every path of `min..=max` hops from `node`, recording the node
+    /// each path ends at.
+    ///
+    /// A path never reuses an edge, so cycles are walked at most once and
+    /// unbounded ranges terminate even without the depth cap. `capped`
+    /// says that `max` is the depth cap rather than the pattern's own bound;
+    /// with [`ExecutionOptions::strict_path_depth`], a path that could
+    /// continue past it is then an error.
+    #[allow(clippy::too_many_arguments)]
+    fn expand_paths(
+        node: usize,
+        depth: usize,
+        (min_hops, max_hops): (usize, usize),
+        capped: bool,
+        neighbors_of: &impl Fn(usize) -> Vec<(usize, usize, bool)>,
+        path_edges: &mut Vec<usize>,
+        ends: &mut Vec<usize>,
+        options: &ExecutionOptions,
+    ) -> Result<()> {
+        if depth >= min_hops {
+            ends.push(node);
+            if options
+                .max_rows
+                .is_some_and(|max_rows| ends.len() > max_rows)
+            {
+                return Err(EngineError::ExecutionError(
+                    "result set exceeded max_rows".to_string(),
+                ));
+            }
+        }
+        if depth == max_hops {
+            if capped
+                && options.strict_path_depth
+                && neighbors_of(node)
+                    .iter()
+                    .any(|(_, edge_idx, _)| !path_edges.contains(edge_idx))
+            {
+                return Err(EngineError::ExecutionError(format!(
+                    "variable-length path exceeded max_path_depth ({})",
+                    max_hops
+                )));
+            }
+            return Ok(());
+        }
+
+        for (next_idx, edge_idx, _) in neighbors_of(node) {
+            if path_edges.contains(&edge_idx) {
+                continue;
+            }
+            path_edges.push(edge_idx);
+            Self::expand_paths(
+                next_idx,
+                depth + 1,
+                (min_hops, max_hops),
+                capped,
+                neighbors_of,
+                path_edges,
+                ends,
+                options,
+            )?;
+            path_edges.pop();
+        }
+        Ok(())
+    }
+
+    fn evaluate_expression(
+        expr: &ast::Expression,
+        bindings: &Bindings,
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> bool {
         match expr {
             ast::Expression::And(exprs) => exprs
                 .iter()
-                .all(|e| Self::evaluate_expression(e, bindings, graph)),
+                .all(|e| Self::evaluate_expression(e, bindings, graph, options)),
             ast::Expression::Or(exprs) => exprs
                 .iter()
-                .any(|e| Self::evaluate_expression(e, bindings, graph)),
+                .any(|e| Self::evaluate_expression(e, bindings, graph, options)),
             ast::Expression::Comparison(comp) => {
-                let left_val = Self::evaluate_property_or_variable(&comp.left, bindings, graph);
-
-                if let Some(right_term) = &comp.right {
-                    let right_val = match right_term {
-                        ast::Term::Literal(lit) => match lit {
-                            ast::Literal::String(s) => s.clone(),
-                            ast::Literal::Number(n) => n.to_string(),
-                        },
-                        ast::Term::PropertyOrVariable(pv) => {
-                            Self::evaluate_property_or_variable(pv, bindings, graph)
-                        }
+                if let (Some(op), Some(right)) = (&comp.operator, &comp.right) {
+                    if matches!(op, ast::ComparisonOperator::In) {
+                        return Self::is_member(&comp.left, right, bindings, graph, options);
+                    }
+                    // CONTAINS on a list tests for an element, not a substring
+                    if matches!(op, ast::ComparisonOperator::Contains)
+                        && let Value::Array(items) =
+                            super::mutation::evaluate_value(&comp.left, bindings, graph, options)
+                    {
+                        let value =
+                            super::mutation::evaluate_value(right, bindings, graph, options);
+                        return Self::list_contains(&items, &value, options);
+                    }
+                    let left_val = Self::evaluate_operand(&comp.left, bindings, graph, options);
+                    let right_val = Self::evaluate_operand(right, bindings, graph, options);
+                    // Ordering against a missing property or a null is never true
+                    if matches!(
+                        op,
+                        ast::ComparisonOperator::Lt
+                            | ast::ComparisonOperator::Gt
+                            | ast::ComparisonOperator::LtEq
+                            | ast::ComparisonOperator::GtEq
+                    ) && (left_val.is_none() || right_val.is_none())
+                    {
+                        return false;
+                    }
+                    // Otherwise a missing property compares as "null", while a
+                    // computed null (e.g. an unparseable date()) never matches
+                    let as_text = |val: Option<String>, operand: &ast::ValueExpression| {
+                        val.or_else(|| {
+                            operand
+                                .as_property_or_variable()
+                                .map(|_| "null".to_string())
+                        })
                     };
-
-                    if let Some(op) = &comp.operator {
-                        match op {
-                            ast::ComparisonOperator::Eq => left_val == right_val,
-                            ast::ComparisonOperator::NotEq => left_val != right_val,
-                            ast::ComparisonOperator::Contains => left_val.contains(&right_val),
-                            ast::ComparisonOperator::Lt => left_val < right_val,
-                            ast::ComparisonOperator::Gt => left_val > right_val,
-                            ast::ComparisonOperator::LtEq => left_val <= right_val,
-                            ast::ComparisonOperator::GtEq => left_val >= right_val,
+                    let (Some(left_val), Some(right_val)) =
+                        (as_text(left_val, &comp.left), as_text(right_val, right))
+                    else {
+                        return false;
+                    };
+                    match op {
+                        ast::ComparisonOperator::Eq => left_val == right_val,
+                        ast::ComparisonOperator::NotEq => left_val != right_val,
+                        ast::ComparisonOperator::Contains
+                        | ast::ComparisonOperator::StartsWith
+                        | ast::ComparisonOperator::EndsWith => {
+                            Self::match_text(op, &left_val, &right_val, options)
                         }
-                    } else {
-                        !left_val.is_empty() && left_val != "null"
+                        ast::ComparisonOperator::Lt => {
+                            Self::compare_operands(&left_val, &right_val).is_lt()
+                        }
+                        ast::ComparisonOperator::Gt => {
+                            Self::compare_operands(&left_val, &right_val).is_gt()
+                        }
+                        ast::ComparisonOperator::LtEq => {
+                            Self::compare_operands(&left_val, &right_val).is_le()
+                        }
+                        ast::ComparisonOperator::GtEq => {
+                            Self::compare_operands(&left_val, &right_val).is_ge()
+                        }
+                        ast::ComparisonOperator::In => unreachable!(),
                     }
                 } else {
-                    !left_val.is_empty() && left_val != "null"
+                    Self::is_truthy(&comp.left, bindings, graph, options)
                 }
             }
             ast::Expression::Aggregate(_) => true,
+            ast::Expression::HasLabels(predicate) => match bindings.get(&predicate.variable) {
+                Some(EntityId::Node(idx)) => predicate
+                    .labels
+                    .iter()
+                    .all(|label| graph.nodes[*idx].has_label(label)),
+                _ => false,
+            },
+        }
+    }
+
+    /// Evaluate `left IN right`.
+    ///
+    /// `right` must evaluate to a list; anything else never matches. Scalars
+    /// are compared by their string form, so `"2"` matches `2`, consistent
+    /// with the other WHERE comparisons.
+    fn is_member(
+        left: &ast::ValueExpression,
+        right: &ast::ValueExpression,
+        bindings: &Bindings,
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> bool {
+        let Value::Array(items) = super::mutation::evaluate_value(right, bindings, graph, options)
+        else {
+            return false;
+        };
+        let value = super::mutation::evaluate_value(left, bindings, graph, options);
+        Self::list_contains(&items, &value, &ExecutionOptions::default())
+    }
+
+    /// Whether `items` holds a scalar equal to `value`, comparing numbers
+    /// and strings by value (`2` matches `"2"`). With
+    /// `case_insensitive_text`, strings also match ignoring case.
+    fn list_contains(items: &[Value], value: &Value, options: &ExecutionOptions) -> bool {
+        let scalar_key = |value: &Value| {
+            let key = match value {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            Some(if options.case_insensitive_text {
+                key.to_lowercase()
+            } else {
+                key
+            })
+        };
+
+        let Some(key) = scalar_key(value) else {
+            return false;
+        };
+        items
+            .iter()
+            .any(|item| scalar_key(item).as_ref() == Some(&key))
+    }
+
+    /// Evaluate one side of a comparison to the string form compared in WHERE.
+    ///
+    /// `None` stands for a missing property or a `null`, whether looked up
+    /// or computed by a function call; the caller decides how it compares.
+    fn evaluate_operand(
+        expr: &ast::ValueExpression,
+        bindings: &Bindings,
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> Option<String> {
+        if let Some(pv) = expr.as_property_or_variable() {
+            return Self::evaluate_property_or_variable(pv, bindings, graph);
+        }
+        match super::mutation::evaluate_value(expr, bindings, graph, options) {
+            Value::String(s) => Some(s),
+            Value::Null => None,
+            other => Some(other.to_string()),
+        }
+    }
+
+    /// Whether a bare WHERE operand passes the filter.
+    ///
+    /// Boolean properties pass when `true`; other properties pass when
+    /// present and non-empty. Computed values such as `toBoolean(n.flag)`
+    /// pass unless they are `false`, `null` or `""`.
+    fn is_truthy(
+        expr: &ast::ValueExpression,
+        bindings: &Bindings,
+        graph: &Graph,
+        options: &ExecutionOptions,
+    ) -> bool {
+        if let Some(pv) = expr.as_property_or_variable() {
+            if let Value::Bool(b) = super::mutation::evaluate_value(expr, bindings, graph, options)
+            {
+                return b;
+            }
+            return Self::evaluate_property_or_variable(pv, bindings, graph)
+                .is_some_and(|val| !val.is_empty() && val != "null");
+        }
+        match super::mutation::evaluate_value(expr, bindings, graph, options) {
+            Value::Bool(b) => b,
+            Value::Null => false,
+            Value::String(s) => !s.is_empty(),
+            _ => true,
+        }
+    }
+
+    /// Order two WHERE operands: numerically when both parse as numbers (so
+    /// a count of `10` is greater than `3`), otherwise as strings.
+    fn compare_operands(left: &str, right: &str) -> std::cmp::Ordering {
+        match (left.parse::<f64>(), right.parse::<f64>()) {
+            (Ok(l), Ok(r)) if l.is_finite() && r.is_finite() => l.total_cmp(&r),
+            _ => left.cmp(right),
+        }
+    }
+
+    /// Evaluate the substring operators, optionally ignoring case.
+    fn match_text(
+        op: &ast::ComparisonOperator,
+        left: &str,
+        right: &str,
+        options: &ExecutionOptions,
+    ) -> bool {
+        let (left, right) = if options.case_insensitive_text {
+            (
+                Cow::Owned(left.to_lowercase()),
+                Cow::Owned(right.to_lowercase()),
+            )
+        } else {
+            (Cow::Borrowed(left), Cow::Borrowed(right))
+        };
+        match op {
+            ast::ComparisonOperator::StartsWith => left.starts_with(right.as_ref()),
+            ast::ComparisonOperator::EndsWith => left.ends_with(right.as_ref()),
+            _ => left.contains(right.as_ref()),
         }
     }
 
@@ -397,11 +1676,58 @@ impl QueryExecutor {
         expr: &ast::Expression,
         bindings: &Bindings,
         graph: &Graph,
+        options: &ExecutionOptions,
     ) -> Value {
         match expr {
             ast::Expression::Comparison(comp) => {
                 if comp.operator.is_none() && comp.right.is_none() {
-                    let val = Self::evaluate_property_or_variable(&comp.left, bindings, graph);
+                    // Computed values (function calls, literals) are returned as is
+                    let Some(pv) = comp.left.as_property_or_variable() else {
+                        return super::mutation::evaluate_value(
+                            &comp.left, bindings, graph, options,
+                        );
+                    };
+                    if let Some(EntityId::Value(_)) = bindings.get(&pv.variable) {
+                        return super::mutation::evaluate_value(
+                            &comp.left, bindings, graph, options,
+                        );
+                    }
+
+                    if pv.property.is_none()
+                        && let Some(EntityId::Relationship {
+                            from_idx,
+                            to_idx,
+                            rel,
+                            forward,
+                            ..
+                        }) = bindings.get(&pv.variable)
+                    {
+                        return Self::relationship_value(*from_idx, *to_idx, rel, *forward, graph);
+                    }
+
+                    // Properties are returned with their original JSON type
+                    if let Some(prop) = &pv.property {
+                        match bindings.get(&pv.variable) {
+                            Some(EntityId::Node(idx)) => {
+                                return graph.nodes[*idx]
+                                    .get_property(prop)
+                                    .cloned()
+                                    .unwrap_or(Value::Null);
+                            }
+                            Some(EntityId::Relationship { edge_idx, .. }) if prop != "type" => {
+                                return graph
+                                    .edges
+                                    .get(*edge_idx)
+                                    .and_then(|edge| edge.get_property(prop))
+                                    .cloned()
+                                    .unwrap_or(Value::Null);
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    let val = Self::evaluate_property_or_variable(pv, bindings, graph)
+                        .unwrap_or_else(|| "null".to_string());
                     // Try to parse as number first
                     if let Ok(n) = val.parse::<i64>() {
                         Value::Number(n.into())
@@ -409,44 +1735,86 @@ impl QueryExecutor {
                         Value::String(val)
                     }
                 } else {
-                    Value::Bool(Self::evaluate_expression(expr, bindings, graph))
+                    Value::Bool(Self::evaluate_expression(expr, bindings, graph, options))
                 }
             }
             ast::Expression::Aggregate(_) => Value::Null,
-            _ => Value::Null,
+            ast::Expression::And(_) | ast::Expression::Or(_) | ast::Expression::HasLabels(_) => {
+                Value::Bool(Self::evaluate_expression(expr, bindings, graph, options))
+            }
         }
     }
 
+    /// Build the JSON object returned for a bound relationship variable.
+    ///
+    /// `start` and `end` are the edge's stored endpoints; `direction` is
+    /// `forward` when the edge runs left to right in the matched pattern
+    /// and `backward` otherwise.
+    pub(crate) fn relationship_value(
+        from_idx: usize,
+        to_idx: usize,
+        rel: &str,
+        forward: bool,
+        graph: &Graph,
+    ) -> Value {
+        let mut obj = serde_json::Map::new();
+        obj.insert("type".to_string(), Value::String(rel.to_string()));
+        obj.insert(
+            "start".to_string(),
+            Value::String(graph.nodes[from_idx].id.clone()),
+        );
+        obj.insert(
+            "end".to_string(),
+            Value::String(graph.nodes[to_idx].id.clone()),
+        );
+        let direction = if forward { "forward" } else { "backward" };
+        obj.insert(
+            "direction".to_string(),
+            Value::String(direction.to_string()),
+        );
+        Value::Object(obj)
+    }
+
+    /// The text form of a property or variable, or `None` when it is
+    /// unbound, missing or `null` (or a node or relationship property that
+    /// holds a list or map).
     fn evaluate_property_or_variable(
         pv: &ast::PropertyOrVariable,
         bindings: &Bindings,
         graph: &Graph,
-    ) -> String {
-        if let Some(entity) = bindings.get(&pv.variable) {
-            match entity {
-                EntityId::Node(idx) => {
-                    let node = &graph.nodes[*idx];
-                    if let Some(ref prop) = pv.property {
-                        node.get_property_as_string(prop)
-                            .unwrap_or_else(|| "null".to_string())
-                    } else {
-                        node.id.clone()
-                    }
+    ) -> Option<String> {
+        match bindings.get(&pv.variable)? {
+            EntityId::Node(idx) => {
+                let node = &graph.nodes[*idx];
+                match &pv.property {
+                    Some(prop) => node.get_property_as_string(prop),
+                    None => Some(node.id.clone()),
                 }
-                EntityId::Relationship { rel, .. } => {
-                    if let Some(ref prop) = pv.property {
-                        if prop == "type" {
-                            rel.clone()
-                        } else {
-                            "null".to_string()
-                        }
-                    } else {
-                        rel.clone()
-                    }
+            }
+            EntityId::Relationship { edge_idx, rel, .. } => match pv.property.as_deref() {
+                None | Some("type") => Some(rel.clone()),
+                Some(prop) => graph
+                    .edges
+                    .get(*edge_idx)
+                    .and_then(|edge| edge.get_property(prop))
+                    .and_then(|v| match v {
+                        Value::String(s) => Some(s.clone()),
+                        Value::Number(n) => Some(n.to_string()),
+                        Value::Bool(b) => Some(b.to_string()),
+                        _ => None,
+                    }),
+            },
+            EntityId::Value(value) => {
+                let value = match &pv.property {
+                    Some(prop) => value.get(prop).unwrap_or(&Value::Null),
+                    None => value,
+                };
+                match value {
+                    Value::Null => None,
+                    Value::String(s) => Some(s.clone()),
+                    other => Some(other.to_string()),
                 }
             }
-        } else {
-            "null".to_string()
         }
     }
 }
@@ -515,6 +1883,944 @@ mod tests {
         assert_eq!(result.get_single_value().unwrap().as_i64(), Some(90));
     }
 
+    #[test]
+    fn test_plan_reverses_toward_selective_end() {
+        let parsed =
+            parser::parse_query("MATCH (a)-[:knows]->(b) WHERE b.id = \"3\" RETURN a.id").unwrap();
+        let pattern_part = &parsed.match_clause.patterns[0];
+        let planned = QueryExecutor::plan_pattern_part(
+            pattern_part,
+            &[HashMap::new()],
+            parsed.where_clause.as_ref(),
+        );
+
+        match &planned.chains[0] {
+            ast::PatternChain::Node(node_pat) => {
+                assert_eq!(node_pat.variable.as_deref(), Some("b"))
+            }
+            _ => panic!("expected node pattern"),
+        }
+        match &planned.chains[1] {
+            ast::PatternChain::Relationship(rel_pat, node_pat) => {
+                assert_eq!(rel_pat.direction, ast::Direction::Left);
+                assert_eq!(node_pat.variable.as_deref(), Some("a"));
+            }
+            _ => panic!("expected relationship pattern"),
+        }
+    }
+
+    #[test]
+    fn test_plan_keeps_order_without_selective_end() {
+        let parsed = parser::parse_query("MATCH (a:admin)-[:knows]->(b) RETURN b.id").unwrap();
+        let pattern_part = &parsed.match_clause.patterns[0];
+        let planned = QueryExecutor::plan_pattern_part(pattern_part, &[HashMap::new()], None);
+        assert!(matches!(planned, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_execute_reverse_traversal() {
+        let graph = create_test_graph();
+        let parsed =
+            parser::parse_query("MATCH (a)-[r:knows]->(b) WHERE b.id = \"3\" RETURN a.id, r")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0]["a.id"], "2");
+        assert_eq!(
+            result.rows[0]["r"],
+            json!({"type": "knows", "start": "2", "end": "3", "direction": "forward"})
+        );
+    }
+
+    #[test]
+    fn test_execute_return_relationship_object() {
+        let graph = create_test_graph();
+        let parsed = parser::parse_query("MATCH (a)-[r]->(b) RETURN r").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(
+            result.rows[0]["r"],
+            json!({"type": "knows", "start": "1", "end": "2", "direction": "forward"})
+        );
+
+        // Incoming traversal still reports the edge's stored direction
+        let parsed = parser::parse_query("MATCH (b)<-[r]-(a) WHERE b.id = \"2\" RETURN r").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(
+            result.rows[0]["r"],
+            json!({"type": "knows", "start": "1", "end": "2", "direction": "backward"})
+        );
+    }
+
+    #[test]
+    fn test_execute_ordering_against_null() {
+        let mut graph = create_test_graph();
+        graph.add_node(Node::new("4", None, json!({"id": "4", "score": null})));
+        let ids = |query: &str| -> Vec<Value> {
+            let parsed = parser::parse_query(query).unwrap();
+            let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+            result.rows.iter().map(|row| row["n.id"].clone()).collect()
+        };
+
+        // Node 4 has no age, so it is neither above nor below any value
+        assert_eq!(
+            ids("MATCH (n) WHERE n.age > 26 RETURN n.id"),
+            vec!["1", "3"]
+        );
+        assert_eq!(ids("MATCH (n) WHERE n.age <= 26 RETURN n.id"), vec!["2"]);
+        assert!(ids("MATCH (n) WHERE n.score >= 0 OR n.score < 0 RETURN n.id").is_empty());
+        // Equality still compares a missing property as "null"
+        assert_eq!(
+            ids("MATCH (n) WHERE n.age <> 30 RETURN n.id"),
+            vec!["2", "3", "4"]
+        );
+    }
+
+    #[test]
+    fn test_execute_where_relationship_property() {
+        let mut graph = create_test_graph();
+        graph.edges[0].data = json!({"since": 2018});
+        graph.edges[1].data = json!({"since": 2021});
+
+        let parsed = parser::parse_query(
+            "MATCH (a)-[r:knows]->(b) WHERE r.since > 2019 RETURN b.id, r.since",
+        )
+        .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0]["b.id"], "3");
+        assert_eq!(result.rows[0]["r.since"], 2021);
+    }
+
+    #[test]
+    fn test_execute_parallel_edge_properties() {
+        let mut graph = create_test_graph();
+        graph.edges[0].data = json!({"w": 1});
+        graph.add_edge(crate::graph::Edge::new(0, 1, "knows").with_data(json!({"w": 2})));
+
+        let parsed =
+            parser::parse_query("MATCH (a)-[r:knows]->(b) WHERE b.id = \"2\" RETURN r.w").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        let weights: Vec<_> = result.rows.iter().map(|row| row["r.w"].clone()).collect();
+        assert_eq!(weights, vec![json!(1), json!(2)]);
+
+        let parsed =
+            parser::parse_query("MATCH (a)-[r:knows]->(b) WHERE r.w = 2 RETURN b.id").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 1);
+    }
+
+    fn create_chain_graph() -> Graph {
+        let mut graph = Graph::new();
+        for id in ["a", "b", "c", "d", "x"] {
+            graph.add_node(Node::new(id, Some("n".to_string()), json!({ "id": id })));
+        }
+        graph.add_edge(crate::graph::Edge::new(0, 1, "next"));
+        graph.add_edge(crate::graph::Edge::new(1, 2, "next"));
+        graph.add_edge(crate::graph::Edge::new(2, 3, "next"));
+        graph.add_edge(crate::graph::Edge::new(4, 1, "other"));
+        graph
+    }
+
+    #[test]
+    fn test_execute_three_hop_chain_named() {
+        let graph = create_chain_graph();
+        let parsed = parser::parse_query(
+            "MATCH (a)-[:next]->(b)-[:next]->(c)-[:next]->(d) RETURN a.id, b.id, c.id, d.id",
+        )
+        .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(
+            result.rows[0],
+            json!({"a.id": "a", "b.id": "b", "c.id": "c", "d.id": "d"})
+        );
+    }
+
+    #[test]
+    fn test_execute_three_hop_chain_anonymous_middle() {
+        let graph = create_chain_graph();
+        let parsed = parser::parse_query(
+            "MATCH (a {id: \"a\"})-[:next]->()-[:next]->()-[:next]->(d) RETURN d.id",
+        )
+        .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0]["d.id"], "d");
+
+        // An anonymous start node still anchors the first hop
+        let parsed = parser::parse_query("MATCH ()-[:other]->()-[:next]->(c) RETURN c.id").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0]["c.id"], "c");
+    }
+
+    #[test]
+    fn test_execute_anonymous_start_relationship() {
+        let graph = create_test_graph();
+        let ids = |q: &str| {
+            let parsed = parser::parse_query(q).unwrap();
+            QueryExecutor::execute(&parsed, &graph)
+                .unwrap()
+                .rows
+                .into_iter()
+                .map(|row| row["b.id"].clone())
+                .collect::<Vec<_>>()
+        };
+
+        // The anonymous start ranges over every node
+        assert_eq!(ids("MATCH ()-[:knows]->(b) RETURN b.id"), vec!["2", "3"]);
+        assert_eq!(ids("MATCH ()<-[:knows]-(b) RETURN b.id"), vec!["1", "2"]);
+        assert_eq!(ids("MATCH (:admin)-[:knows]->(b) RETURN b.id"), vec!["2"]);
+        assert!(ids("MATCH ()-[:likes]->(b) RETURN b.id").is_empty());
+    }
+
+    #[test]
+    fn test_execute_variable_length_any() {
+        let mut graph = create_chain_graph();
+        // Close a cycle d -> a so unbounded traversal must terminate
+        graph.add_edge(crate::graph::Edge::new(3, 0, "next"));
+
+        let parsed =
+            parser::parse_query("MATCH (a {id: \"a\"})-[:next*]->(b) RETURN DISTINCT b.id")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        let mut ids: Vec<&str> = result
+            .rows
+            .iter()
+            .map(|r| r["b.id"].as_str().unwrap())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b", "c", "d"]);
+
+        let parsed =
+            parser::parse_query("MATCH (a {id: \"a\"})-[:next*2]->(b) RETURN b.id").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0]["b.id"], "c");
+
+        let parsed =
+            parser::parse_query("MATCH (a {id: \"a\"})-[:next*]->(b) RETURN b.id").unwrap();
+        let options = ExecutionOptions {
+            max_path_depth: Some(2),
+            ..Default::default()
+        };
+        let result = QueryExecutor::execute_with_options(&parsed, &graph, &options).unwrap();
+        assert_eq!(result.rows.len(), 2);
+    }
+
+    #[test]
+    fn test_execute_text_operators_case_sensitivity() {
+        let mut graph = Graph::new();
+        for name in ["Alice", "alfred", "Bob"] {
+            graph.add_node(Node::new(name, None, json!({ "name": name })));
+        }
+        let count = |query: &str, options: &ExecutionOptions| {
+            let parsed = parser::parse_query(query).unwrap();
+            QueryExecutor::execute_with_options(&parsed, &graph, options)
+                .unwrap()
+                .rows
+                .len()
+        };
+
+        let sensitive = ExecutionOptions::default();
+        let insensitive = ExecutionOptions {
+            case_insensitive_text: true,
+            ..Default::default()
+        };
+
+        let starts = "MATCH (n) WHERE n.name STARTS WITH \"al\" RETURN n.name";
+        assert_eq!(count(starts, &sensitive), 1);
+        assert_eq!(count(starts, &insensitive), 2);
+
+        let ends = "MATCH (n) WHERE n.name ENDS WITH \"B\" RETURN n.name";
+        assert_eq!(count(ends, &sensitive), 0);
+        assert_eq!(count(ends, &insensitive), 1);
+
+        let contains = "MATCH (n) WHERE n.name CONTAINS \"LI\" RETURN n.name";
+        assert_eq!(count(contains, &sensitive), 0);
+        assert_eq!(count(contains, &insensitive), 1);
+    }
+
+    #[test]
+    fn test_execute_contains_on_lists() {
+        let mut graph = Graph::new();
+        for (id, friends, name) in [
+            ("1", json!(["21", "3"]), "Ann"),
+            ("2", json!(["2"]), "Bob"),
+            ("3", json!([2, "Ann"]), "Cy"),
+        ] {
+            graph.add_node(Node::new(
+                id,
+                None,
+                json!({"id": id, "friends": friends, "name": name}),
+            ));
+        }
+        let ids = |query: &str, options: &ExecutionOptions| -> Vec<String> {
+            let parsed = parser::parse_query(query).unwrap();
+            QueryExecutor::execute_with_options(&parsed, &graph, options)
+                .unwrap()
+                .rows
+                .iter()
+                .map(|r| r["n.id"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let options = ExecutionOptions::default();
+
+        // Elements match whole, by value: "21" doesn't contain "2", but 2 does
+        assert_eq!(
+            ids(
+                "MATCH (n) WHERE n.friends CONTAINS \"2\" RETURN n.id",
+                &options
+            ),
+            ["2", "3"]
+        );
+        assert_eq!(
+            ids(
+                "MATCH (n) WHERE n.friends CONTAINS 21 RETURN n.id",
+                &options
+            ),
+            ["1"]
+        );
+        assert!(
+            ids(
+                "MATCH (n) WHERE n.friends CONTAINS \"[\" RETURN n.id",
+                &options
+            )
+            .is_empty()
+        );
+        // Strings keep substring semantics
+        assert_eq!(
+            ids(
+                "MATCH (n) WHERE n.name CONTAINS \"n\" RETURN n.id",
+                &options
+            ),
+            ["1"]
+        );
+
+        let insensitive = ExecutionOptions {
+            case_insensitive_text: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(
+                "MATCH (n) WHERE n.friends CONTAINS \"ann\" RETURN n.id",
+                &insensitive
+            ),
+            ["3"]
+        );
+        assert!(
+            ids(
+                "MATCH (n) WHERE n.friends CONTAINS \"ann\" RETURN n.id",
+                &options
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_execute_grouped_count_counts_edges() {
+        let mut graph = create_test_graph();
+        // A second, differently typed edge to the same neighbor
+        graph.add_edge(crate::graph::Edge::new(0, 1, "likes"));
+
+        let parsed = parser::parse_query("MATCH (a)-->(b) RETURN a.id, COUNT(b)").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows[0], json!({"a.id": "1", "COUNT(b)": 2}));
+        assert_eq!(result.rows[1], json!({"a.id": "2", "COUNT(b)": 1}));
+    }
+
+    #[test]
+    fn test_execute_rejects_reused_relationship_variable() {
+        let graph = create_test_graph();
+        let parsed = parser::parse_query("MATCH (a)-[r]->(b)-[r]->(c) RETURN c.id").unwrap();
+        let err = QueryExecutor::execute(&parsed, &graph).unwrap_err();
+        assert!(err.to_string().contains("cannot be used more than once"));
+
+        let parsed = parser::parse_query("MATCH (a)-[r]->(b), (r) RETURN b.id").unwrap();
+        let err = QueryExecutor::execute(&parsed, &graph).unwrap_err();
+        assert!(err.to_string().contains("both a node and a relationship"));
+    }
+
+    #[test]
+    fn test_execute_aggregate_relationship_variable() {
+        let mut graph = create_test_graph();
+        graph.add_edge(crate::graph::Edge::new(0, 2, "likes".to_string()));
+        let run = |q: &str| {
+            let parsed = parser::parse_query(q).unwrap();
+            QueryExecutor::execute(&parsed, &graph).unwrap().rows
+        };
+
+        assert_eq!(
+            run("MATCH (a)-[r:knows]->(b) RETURN COUNT(r)"),
+            vec![json!({"COUNT(r)": 2})]
+        );
+        // A bare relationship collects as the same object RETURN r gives
+        assert_eq!(
+            run("MATCH (a {id: \"1\"})-[r]->(b) RETURN a.id, COUNT(r) AS n, COLLECT(r) AS rels"),
+            vec![json!({"a.id": "1", "n": 2, "rels": [
+                {"type": "knows", "start": "1", "end": "2", "direction": "forward"},
+                {"type": "likes", "start": "1", "end": "3", "direction": "forward"},
+            ]})]
+        );
+        assert_eq!(
+            run("MATCH (a {id: \"2\"})-[r]->(b) WITH a, COLLECT(r) AS rs RETURN rs"),
+            vec![json!({"rs": [
+                {"type": "knows", "start": "2", "end": "3", "direction": "forward"},
+            ]})]
+        );
+        // COUNT skips nulls; COUNT(*) counts every row
+        assert_eq!(
+            run("MATCH (n) RETURN COUNT(n.missing) AS some, COUNT(*) AS all"),
+            vec![json!({"some": 0, "all": 3})]
+        );
+    }
+
+    #[test]
+    fn test_execute_count_by_relationship_type() {
+        let query = parser::parse_query("MATCH ()-[r]->() RETURN type(r), COUNT(*)").unwrap();
+
+        let mut graph = create_test_graph();
+        let result = QueryExecutor::execute(&query, &graph).unwrap();
+        assert_eq!(result.columns, vec!["type(r)", "COUNT(*)"]);
+        assert_eq!(
+            result.rows,
+            vec![json!({"type(r)": "knows", "COUNT(*)": 2})]
+        );
+
+        graph.add_edge(crate::graph::Edge::new(0, 2, "likes".to_string()));
+        graph.add_edge(crate::graph::Edge::new(2, 0, "knows".to_string()));
+        graph.add_edge(crate::graph::Edge::new(1, 0, "likes".to_string()));
+        graph.add_edge(crate::graph::Edge::new(2, 1, "blocks".to_string()));
+        let result = QueryExecutor::execute(&query, &graph).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                json!({"type(r)": "knows", "COUNT(*)": 3}),
+                json!({"type(r)": "likes", "COUNT(*)": 2}),
+                json!({"type(r)": "blocks", "COUNT(*)": 1})
+            ]
+        );
+
+        // type() of anything but a relationship is null
+        let parsed = parser::parse_query("MATCH (n {id: \"1\"}) RETURN type(n) AS t").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows, vec![json!({"t": null})]);
+    }
+
+    #[test]
+    fn test_execution_errors_name_the_clause() {
+        let mut graph = create_test_graph();
+        let error = |q: &str, graph: &mut Graph| {
+            let parsed = parser::parse_query(q).unwrap();
+            QueryExecutor::execute_mut(&parsed, graph, &ExecutionOptions::default())
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            error("MATCH (n) RETURN n.id, COUNT(x)", &mut graph),
+            "Execution error: RETURN item 2: Variable not bound: x"
+        );
+        assert!(
+            error("MATCH (n) WHERE nope(n.id) RETURN n", &mut graph)
+                .starts_with("Execution error: WHERE: ")
+        );
+        assert!(
+            error("MATCH (n) WITH n, nope(n.id) AS x RETURN n", &mut graph)
+                .starts_with("Execution error: WITH item 2: ")
+        );
+        assert!(
+            error("MATCH (a)-[r]->(b)-[r]->(c) RETURN c", &mut graph)
+                .starts_with("Execution error: MATCH: ")
+        );
+        assert!(
+            error("MATCH (n) SET n.a = 1, x.b = 2", &mut graph)
+                .starts_with("Execution error: SET item 2: Variable not bound: x")
+        );
+        assert!(
+            error("MATCH (n:admin) DELETE n", &mut graph).starts_with("Execution error: DELETE: ")
+        );
+    }
+
+    #[test]
+    fn test_execute_reused_node_variable_constrains_match() {
+        let mut graph = create_test_graph();
+        let parsed =
+            parser::parse_query("MATCH (a)-[:knows]->(b)-[:knows]->(c)-[:knows]->(a) RETURN a.id")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 0);
+
+        // Closing the triangle yields one row per rotation
+        graph.add_edge(crate::graph::Edge::new(2, 0, "knows"));
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 3);
+    }
+
+    #[test]
+    fn test_execute_aggregates_over_different_variables() {
+        let graph = create_test_graph();
+        let parsed =
+            parser::parse_query("MATCH (u)-[r:knows]->(v) RETURN COUNT(r), SUM(v.age), COUNT(u)")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.columns, vec!["COUNT(r)", "SUM(v.age)", "COUNT(u)"]);
+        assert_eq!(result.rows.len(), 1);
+        // Edges 1->2 and 2->3 end at ages 25 and 35
+        assert_eq!(
+            result.rows[0],
+            json!({"COUNT(r)": 2, "SUM(v.age)": 60, "COUNT(u)": 2})
+        );
+    }
+
+    #[test]
+    fn test_execute_max_rows_exceeded() {
+        let graph = create_test_graph();
+        let parsed = parser::parse_query("MATCH (a), (b) RETURN a.id, b.id").unwrap();
+        let options = ExecutionOptions {
+            max_rows: Some(5),
+            ..Default::default()
+        };
+        let err = QueryExecutor::execute_with_options(&parsed, &graph, &options).unwrap_err();
+        assert!(err.to_string().contains("result set exceeded max_rows"));
+    }
+
+    #[test]
+    fn test_execute_max_rows_within_limit() {
+        let graph = create_test_graph();
+        let parsed = parser::parse_query("MATCH (a), (b) RETURN a.id, b.id").unwrap();
+        let options = ExecutionOptions {
+            max_rows: Some(9),
+            ..Default::default()
+        };
+        let result = QueryExecutor::execute_with_options(&parsed, &graph, &options).unwrap();
+        assert_eq!(result.rows.len(), 9);
+    }
+
+    #[test]
+    fn test_execute_default_order_is_stable() {
+        let build = || {
+            let mut graph = Graph::new();
+            for id in ["c", "a", "b"] {
+                graph.add_node(Node::new(id, None, json!({ "id": id })));
+            }
+            // Edges from "c" are inserted out of target index order
+            graph.add_edge(crate::graph::Edge::new(0, 2, "knows"));
+            graph.add_edge(crate::graph::Edge::new(1, 2, "knows"));
+            graph.add_edge(crate::graph::Edge::new(0, 1, "knows"));
+            graph
+        };
+
+        let parsed = parser::parse_query("MATCH (n) RETURN n.id").unwrap();
+        let result = QueryExecutor::execute(&parsed, &build()).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                json!({"n.id": "c"}),
+                json!({"n.id": "a"}),
+                json!({"n.id": "b"})
+            ]
+        );
+
+        // By start node index, then edge order
+        let query = "MATCH (x)-[:knows]->(y) RETURN x.id, y.id";
+        let parsed = parser::parse_query(query).unwrap();
+        let expected = vec![
+            json!({"x.id": "c", "y.id": "b"}),
+            json!({"x.id": "c", "y.id": "a"}),
+            json!({"x.id": "a", "y.id": "b"}),
+        ];
+        for _ in 0..3 {
+            let result = QueryExecutor::execute(&parsed, &build()).unwrap();
+            assert_eq!(result.rows, expected);
+        }
+
+        // Pages without ORDER BY tile the full result
+        let pages: Vec<Value> = (0..3)
+            .flat_map(|skip| {
+                let page = format!("{} SKIP {} LIMIT 1", query, skip);
+                let parsed = parser::parse_query(&page).unwrap();
+                QueryExecutor::execute(&parsed, &build()).unwrap().rows
+            })
+            .collect();
+        assert_eq!(pages, expected);
+    }
+
+    #[test]
+    fn test_execute_where_pushdown_prunes_before_expansion() {
+        let graph = create_test_graph();
+        // Without pushdown the cartesian product (9 rows) would exceed max_rows
+        let parsed = parser::parse_query(
+            "MATCH (a), (b) WHERE a.id = \"1\" AND b.id <> a.id AND b.missing = a.missing RETURN b.id",
+        )
+        .unwrap();
+        let options = ExecutionOptions {
+            max_rows: Some(5),
+            ..Default::default()
+        };
+        let result = QueryExecutor::execute_with_options(&parsed, &graph, &options).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![json!({"b.id": "2"}), json!({"b.id": "3"})]
+        );
+
+        // Conjuncts on variables MATCH never binds still apply
+        let parsed =
+            parser::parse_query("MATCH (a) WHERE a.id = \"1\" AND z.id = \"1\" RETURN a.id")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert!(result.rows.is_empty());
+    }
+
+    #[test]
+    fn test_execute_where_label_predicate() {
+        let graph = create_test_graph();
+        // The label narrows candidates as they are bound: 3 nodes would exceed max_rows
+        let parsed =
+            parser::parse_query("MATCH (n) WHERE n:admin AND n.age > 30 RETURN n.id").unwrap();
+        let options = ExecutionOptions {
+            max_rows: Some(2),
+            ..Default::default()
+        };
+        let result = QueryExecutor::execute_with_options(&parsed, &graph, &options).unwrap();
+        assert_eq!(result.rows, vec![json!({"n.id": "3"})]);
+
+        let parsed =
+            parser::parse_query("MATCH (n) WHERE n:user OR n.age > 32 RETURN n.id").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![json!({"n.id": "2"}), json!({"n.id": "3"})]
+        );
+
+        let parsed =
+            parser::parse_query("MATCH (a)-[:knows]->(b) WHERE b:admin RETURN a.id").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows, vec![json!({"a.id": "2"})]);
+    }
+
+    #[test]
+    fn test_execute_anonymous_labeled_count_star() {
+        let graph = create_test_graph();
+        let parsed = parser::parse_query("MATCH (:admin) RETURN COUNT(*)").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.columns[0], "COUNT(*)");
+        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+    }
+
+    #[test]
+    fn test_execute_anonymous_inline_properties() {
+        let graph = create_test_graph();
+        let parsed = parser::parse_query("MATCH ({role: \"admin\"}) RETURN COUNT(*)").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+
+        let parsed = parser::parse_query("MATCH (n:admin {age: 35}) RETURN n.id").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0]["n.id"], "3");
+    }
+
+    #[test]
+    fn test_execute_return_boolean_property() {
+        let mut graph = Graph::new();
+        graph.add_node(Node::new(
+            "1".to_string(),
+            Some("user".to_string()),
+            json!({"id": "1", "active": true}),
+        ));
+        let parsed = parser::parse_query("MATCH (u:user) RETURN u.active").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows[0]["u.active"], Value::Bool(true));
+    }
+
+    #[test]
+    fn test_execute_return_literal_alias() {
+        let graph = create_test_graph();
+        let parsed =
+            parser::parse_query("MATCH (u) RETURN u.id, \"active\" AS status, 1 AS one, \"x\"")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.columns, vec!["u.id", "status", "one", "\"x\""]);
+        assert_eq!(result.rows.len(), 3);
+        for row in &result.rows {
+            assert_eq!(row["status"], json!("active"));
+            assert_eq!(row["one"], json!(1));
+            assert_eq!(row["\"x\""], json!("x"));
+        }
+
+        // A constant is a single grouping key
+        let parsed = parser::parse_query("MATCH (u) RETURN \"all\" AS tag, COUNT(u) AS c").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows, vec![json!({"tag": "all", "c": 3})]);
+    }
+
+    #[test]
+    fn test_execute_return_preserves_json_types() {
+        let mut graph = Graph::new();
+        graph.add_node(Node::new(
+            "1".to_string(),
+            Some("user".to_string()),
+            json!({"id": "1", "score": 4.5, "tags": ["a", "b"], "meta": {"k": 1}, "gone": null}),
+        ));
+        let parsed = parser::parse_query(
+            "MATCH (u:user) RETURN u.id, u.score, u.tags, u.meta, u.gone, u.missing",
+        )
+        .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        let row = &result.rows[0];
+        assert_eq!(row["u.id"], json!("1"));
+        assert_eq!(row["u.score"], json!(4.5));
+        assert_eq!(row["u.tags"], json!(["a", "b"]));
+        assert_eq!(row["u.meta"], json!({"k": 1}));
+        assert_eq!(row["u.gone"], Value::Null);
+        assert_eq!(row["u.missing"], Value::Null);
+    }
+
+    #[test]
+    fn test_execute_bare_boolean_property() {
+        let mut graph = Graph::new();
+        for (id, active) in [("1", json!(true)), ("2", json!(false)), ("3", json!("no"))] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                Some("user".to_string()),
+                json!({"id": id, "active": active}),
+            ));
+        }
+        graph.add_node(Node::new(
+            "4".to_string(),
+            Some("user".to_string()),
+            json!({"id": "4"}),
+        ));
+
+        let parsed = parser::parse_query("MATCH (u:user) WHERE u.active RETURN u.id").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![json!({"u.id": "1"}), json!({"u.id": "3"})]
+        );
+    }
+
+    #[test]
+    fn test_execute_to_boolean() {
+        let mut graph = Graph::new();
+        for (id, flag) in [("1", json!("true")), ("2", json!("FALSE")), ("3", json!(1))] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                Some("user".to_string()),
+                json!({"id": id, "flag": flag}),
+            ));
+        }
+
+        let parsed =
+            parser::parse_query("MATCH (u:user) WHERE toBoolean(u.flag) RETURN u.id").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![json!({"u.id": "1"}), json!({"u.id": "3"})]
+        );
+
+        let parsed =
+            parser::parse_query("MATCH (u:user) WHERE u.id = \"2\" RETURN toBoolean(u.flag)")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.columns, vec!["toBoolean(u.flag)"]);
+        assert_eq!(result.rows[0]["toBoolean(u.flag)"], json!(false));
+
+        let parsed = parser::parse_query("MATCH (u) RETURN toBool(u.flag)").unwrap();
+        let err = QueryExecutor::execute(&parsed, &graph).unwrap_err();
+        assert!(err.to_string().contains("toBool"));
+    }
+
+    #[test]
+    fn test_execute_reverse() {
+        let mut graph = Graph::new();
+        graph.add_node(Node::new(
+            "1".to_string(),
+            Some("user".to_string()),
+            json!({"id": "1", "name": "Alice", "tags": ["a", "b", "c"]}),
+        ));
+        let parsed =
+            parser::parse_query("MATCH (u:user) RETURN reverse(u.name) AS n, reverse(u.tags) AS t")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![json!({"n": "ecilA", "t": ["c", "b", "a"]})]
+        );
+    }
+
+    #[test]
+    fn test_execute_range() {
+        let graph = create_test_graph();
+        let parsed =
+            parser::parse_query("MATCH (n) WHERE n.id = \"1\" RETURN range(3, -3, -3) AS r")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows, vec![json!({"r": [3, 0, -3]})]);
+
+        let parsed = parser::parse_query("MATCH (n) RETURN range(1, 5, 0)").unwrap();
+        let err = QueryExecutor::execute(&parsed, &graph).unwrap_err();
+        assert!(err.to_string().contains("step must not be zero"));
+
+        let parsed = parser::parse_query("MATCH (n) RETURN range(0, 9223372036854775807)").unwrap();
+        let err = QueryExecutor::execute(&parsed, &graph).unwrap_err();
+        assert!(err.to_string().contains("exceeds the limit"), "{}", err);
+    }
+
+    #[test]
+    fn test_execute_list_functions() {
+        let mut graph = Graph::new();
+        graph.add_node(Node::new(
+            "1".to_string(),
+            Some("user".to_string()),
+            json!({"id": "1", "friends": ["2", "3", "4"]}),
+        ));
+        let parsed = parser::parse_query(
+            "MATCH (u:user) WHERE head(u.friends) = \"2\" RETURN last(u.friends) AS l, tail(u.friends) AS t",
+        )
+        .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows, vec![json!({"l": "4", "t": ["3", "4"]})]);
+    }
+
+    #[test]
+    fn test_execute_in() {
+        let mut graph = Graph::new();
+        for (id, friends) in [
+            ("1", json!(["2", "3"])),
+            ("2", json!([1, 3])),
+            ("3", json!("2")),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                Some("user".to_string()),
+                json!({"id": id, "friends": friends}),
+            ));
+        }
+        let ids = |q: &str| -> Vec<Value> {
+            let parsed = parser::parse_query(q).unwrap();
+            let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+            result.rows.iter().map(|r| r["u.id"].clone()).collect()
+        };
+
+        // Array-valued property; a non-array property never matches
+        assert_eq!(
+            ids("MATCH (u) WHERE \"3\" IN u.friends RETURN u.id"),
+            vec![json!("1"), json!("2")]
+        );
+        // Numeric and string elements compare consistently
+        assert_eq!(
+            ids("MATCH (u) WHERE \"1\" IN u.friends RETURN u.id"),
+            vec![json!("2")]
+        );
+        assert_eq!(
+            ids("MATCH (u) WHERE u.id IN [\"1\", 3] RETURN u.id"),
+            vec![json!("1"), json!("3")]
+        );
+        assert!(ids("MATCH (u) WHERE u.missing IN u.friends RETURN u.id").is_empty());
+    }
+
+    #[test]
+    fn test_execute_with() {
+        let graph = create_test_graph();
+        let run = |q: &str| {
+            let parsed = parser::parse_query(q).unwrap();
+            QueryExecutor::execute(&parsed, &graph).unwrap().rows
+        };
+
+        // Nodes pass through; computed items are bound under their alias
+        assert_eq!(
+            run("MATCH (n:admin) WITH n, n.age AS age WHERE age > 30 RETURN n.id, age"),
+            vec![json!({"n.id": "3", "age": 35})]
+        );
+        // Aggregates group by the other items; later clauses see only projected variables
+        assert_eq!(
+            run("MATCH (n) WITH n.role AS role, COUNT(n) AS c WHERE c > 1 RETURN role, c"),
+            vec![json!({"role": "admin", "c": 2})]
+        );
+        assert_eq!(
+            run("MATCH (n) WITH DISTINCT n.role AS role RETURN role"),
+            vec![json!({"role": "admin"}), json!({"role": "user"})]
+        );
+        // A following MATCH continues from the projected node
+        assert_eq!(
+            run("MATCH (a:admin) WITH a WHERE a.age < 31 MATCH (a)-[:knows]->(b) RETURN b.id"),
+            vec![json!({"b.id": "2"})]
+        );
+        assert_eq!(
+            run("MATCH (n) RETURN COLLECT(n.id) AS ids, COLLECT(n.missing) AS none"),
+            vec![json!({"ids": ["1", "2", "3"], "none": []})]
+        );
+
+        assert!(parser::parse_query("MATCH (n) WITH n.id RETURN n").is_err());
+    }
+
+    #[test]
+    fn test_execute_label_alternatives() {
+        let graph = create_test_graph();
+        let ids = |q: &str| {
+            let parsed = parser::parse_query(q).unwrap();
+            QueryExecutor::execute(&parsed, &graph).unwrap().rows
+        };
+
+        // `|` accepts any of the labels
+        assert_eq!(
+            ids("MATCH (n:admin|user) RETURN n.id"),
+            vec![
+                json!({"n.id": "1"}),
+                json!({"n.id": "2"}),
+                json!({"n.id": "3"})
+            ]
+        );
+        assert_eq!(
+            ids("MATCH (n:user|:guest) RETURN n.id"),
+            vec![json!({"n.id": "2"})]
+        );
+        // `:a:b` requires all of them, which no single-label node satisfies
+        assert!(ids("MATCH (n:admin:user) RETURN n.id").is_empty());
+        assert_eq!(ids("MATCH (n:admin:admin) RETURN n.id").len(), 2);
+    }
+
+    #[test]
+    fn test_execute_date_comparison() {
+        let mut graph = Graph::new();
+        for (id, created) in [
+            ("1", "2019-12-31"),
+            ("2", "2020-01-01T00:30:00+01:00"),
+            ("3", "2020-01-01T00:30:00Z"),
+            ("4", "soon"),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                Some("post".to_string()),
+                json!({"id": id, "created": created}),
+            ));
+        }
+        let ids = |q: &str| -> Vec<Value> {
+            let parsed = parser::parse_query(q).unwrap();
+            let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+            result.rows.iter().map(|r| r["p.id"].clone()).collect()
+        };
+
+        assert_eq!(
+            ids("MATCH (p) WHERE date(p.created) < date(\"2020-01-01\") RETURN p.id"),
+            vec![json!("1")]
+        );
+        // 00:30 at +01:00 is still 2019 in UTC
+        assert_eq!(
+            ids("MATCH (p) WHERE datetime(p.created) < datetime(\"2020-01-01\") RETURN p.id"),
+            vec![json!("1"), json!("2")]
+        );
+        // Unparseable dates match neither side
+        assert_eq!(
+            ids("MATCH (p) WHERE date(p.created) >= date(\"2020-01-01\") RETURN p.id"),
+            vec![json!("2"), json!("3")]
+        );
+    }
+
     #[test]
     fn test_execute_distinct() {
         let graph = create_test_graph();
@@ -1,8 +1,11 @@
+use crate::engine::executor::{Bindings, EntityId, ExecutionOptions};
+use crate::engine::mutation::evaluate_value;
 use crate::graph::Graph;
 use crate::parser::ast;
 use serde_json::Value;
+use std::collections::HashSet;
 
-use super::{EvalContext, FunctionError, FunctionResult};
+use super::{FunctionError, FunctionResult};
 
 /// Aggregate function evaluator.
 ///
@@ -10,94 +13,107 @@ use super::{EvalContext, FunctionError, FunctionResult};
 pub struct AggregateEvaluator;
 
 impl AggregateEvaluator {
-    /// Evaluate an aggregate expression over a set of bindings.
+    /// Evaluate an aggregate expression over the rows of a group, evaluating
+    /// its argument in each row.
     pub fn evaluate(
         agg: &ast::AggregateExpression,
-        contexts: &[EvalContext],
+        rows: &[Bindings],
         graph: &Graph,
+        options: &ExecutionOptions,
     ) -> FunctionResult<Value> {
-        match agg.func {
-            ast::AggregateFunction::Count => Self::count(contexts),
-            ast::AggregateFunction::Sum => Self::sum(agg, contexts, graph),
+        let Some(argument) = &agg.argument else {
+            // COUNT(*)
+            return Ok(Value::Number(rows.len().into()));
+        };
+        let mut values: Vec<Value> = Vec::new();
+        let mut seen: HashSet<DistinctKey> = HashSet::new();
+        for bindings in rows {
+            let value = evaluate_value(argument, bindings, graph, options);
+            if value.is_null() {
+                continue;
+            }
+            if agg.distinct && !seen.insert(DistinctKey::of(argument, &value, bindings)) {
+                continue;
+            }
+            values.push(value);
         }
-    }
 
-    /// COUNT function - counts the number of matched entities.
-    fn count(contexts: &[EvalContext]) -> FunctionResult<Value> {
-        let count = contexts.len();
-        Ok(Value::Number(count.into()))
+        match agg.func {
+            ast::AggregateFunction::Count => Ok(Value::Number(values.len().into())),
+            ast::AggregateFunction::Sum => Ok(Self::sum(&values)),
+            ast::AggregateFunction::Collect => Ok(Value::Array(values)),
+        }
     }
 
-    /// SUM function - sums numeric property values.
-    fn sum(
-        agg: &ast::AggregateExpression,
-        contexts: &[EvalContext],
-        graph: &Graph,
-    ) -> FunctionResult<Value> {
-        let mut sum: i64 = 0;
-
-        for context in contexts {
-            if let Some(node_idx) = context.get_binding(&agg.variable) {
-                let node = &graph.nodes[node_idx];
-
-                let value = if let Some(ref prop) = agg.property {
-                    node.get_property_as_i64(prop)
-                } else {
-                    None
-                };
-
-                if let Some(v) = value {
-                    sum += v;
-                }
-            }
+    /// SUM function - sums the numeric values, ignoring anything else.
+    ///
+    /// The sum is an integer while every value is one and the total fits in
+    /// an `i64`, and a float otherwise.
+    fn sum(values: &[Value]) -> Value {
+        let numbers: Vec<&serde_json::Number> = values
+            .iter()
+            .filter_map(|value| match value {
+                Value::Number(n) => Some(n),
+                _ => None,
+            })
+            .collect();
+        let integer_sum = numbers
+            .iter()
+            .try_fold(0i64, |sum, n| n.as_i64().and_then(|x| sum.checked_add(x)));
+        if let Some(sum) = integer_sum {
+            return Value::Number(sum.into());
         }
-
-        Ok(Value::Number(sum.into()))
+        let sum: f64 = numbers.iter().filter_map(|n| n.as_f64()).sum();
+        serde_json::Number::from_f64(sum)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
     }
 
     /// Get the column name for an aggregate expression.
     pub fn column_name(agg: &ast::AggregateExpression) -> String {
-        let func_name = match agg.func {
-            ast::AggregateFunction::Count => "COUNT",
-            ast::AggregateFunction::Sum => "SUM",
-        };
+        agg.to_string()
+    }
+}
+
+/// What makes an aggregated value distinct under `DISTINCT`.
+///
+/// A relationship is identified by its edge, since parallel edges of one
+/// type evaluate to equal objects; any other value by its serialized form.
+#[derive(PartialEq, Eq, Hash)]
+enum DistinctKey {
+    Edge(usize),
+    Value(String),
+}
 
-        if let Some(ref prop) = agg.property {
-            format!("{}({}.{})", func_name, agg.variable, prop)
-        } else {
-            format!("{}({})", func_name, agg.variable)
+impl DistinctKey {
+    fn of(argument: &ast::ValueExpression, value: &Value, bindings: &Bindings) -> Self {
+        let relationship = argument
+            .as_property_or_variable()
+            .filter(|pv| pv.property.is_none())
+            .and_then(|pv| bindings.get(&pv.variable));
+        match relationship {
+            Some(EntityId::Relationship { edge_idx, .. }) => DistinctKey::Edge(*edge_idx),
+            _ => DistinctKey::Value(serde_json::to_string(value).unwrap_or_default()),
         }
     }
 }
 
 /// Extension to add more aggregate functions.
 impl AggregateEvaluator {
-    /// AVG function - calculates the average of numeric property values.
-    pub fn avg(
-        _agg: &ast::AggregateExpression,
-        _contexts: &[EvalContext],
-        _graph: &Graph,
-    ) -> FunctionResult<Value> {
+    /// AVG function - calculates the average of numeric values.
+    pub fn avg(_values: &[Value]) -> FunctionResult<Value> {
         // TODO: Implement AVG
         Err(FunctionError::NotImplemented("AVG".to_string()))
     }
 
     /// MIN function - finds the minimum value.
-    pub fn min(
-        _agg: &ast::AggregateExpression,
-        _contexts: &[EvalContext],
-        _graph: &Graph,
-    ) -> FunctionResult<Value> {
+    pub fn min(_values: &[Value]) -> FunctionResult<Value> {
         // TODO: Implement MIN
         Err(FunctionError::NotImplemented("MIN".to_string()))
     }
 
     /// MAX function - finds the maximum value.
-    pub fn max(
-        _agg: &ast::AggregateExpression,
-        _contexts: &[EvalContext],
-        _graph: &Graph,
-    ) -> FunctionResult<Value> {
+    pub fn max(_values: &[Value]) -> FunctionResult<Value> {
         // TODO: Implement MAX
         Err(FunctionError::NotImplemented("MAX".to_string()))
     }
@@ -130,60 +146,106 @@ mod tests {
         graph
     }
 
-    fn create_test_contexts(graph: &Graph) -> Vec<EvalContext> {
-        let mut contexts = Vec::new();
-        for i in 0..graph.nodes.len() {
-            let mut ctx = EvalContext::new();
-            ctx.bind("n".to_string(), i);
-            contexts.push(ctx);
+    fn create_test_rows(graph: &Graph) -> Vec<Bindings> {
+        (0..graph.nodes.len())
+            .map(|i| Bindings::from([("n".to_string(), EntityId::Node(i))]))
+            .collect()
+    }
+
+    fn aggregate(func: AggregateFunction, property: Option<&str>) -> ast::AggregateExpression {
+        ast::AggregateExpression {
+            func,
+            distinct: false,
+            argument: Some(ast::ValueExpression::PropertyOrVariable(
+                ast::PropertyOrVariable {
+                    variable: "n".to_string(),
+                    property: property.map(str::to_string),
+                },
+            )),
         }
-        contexts
     }
 
     #[test]
     fn test_count() {
         let graph = create_test_graph();
-        let contexts = create_test_contexts(&graph);
-
-        let agg = ast::AggregateExpression {
-            func: AggregateFunction::Count,
-            variable: "n".to_string(),
-            property: None,
-        };
+        let rows = create_test_rows(&graph);
+        let options = ExecutionOptions::default();
 
-        let result = AggregateEvaluator::evaluate(&agg, &contexts, &graph).unwrap();
+        let agg = aggregate(AggregateFunction::Count, None);
+        let result = AggregateEvaluator::evaluate(&agg, &rows, &graph, &options).unwrap();
         assert_eq!(result.as_i64(), Some(3));
     }
 
     #[test]
     fn test_sum() {
         let graph = create_test_graph();
-        let contexts = create_test_contexts(&graph);
-
-        let agg = ast::AggregateExpression {
-            func: AggregateFunction::Sum,
-            variable: "n".to_string(),
-            property: Some("value".to_string()),
-        };
+        let rows = create_test_rows(&graph);
+        let options = ExecutionOptions::default();
 
-        let result = AggregateEvaluator::evaluate(&agg, &contexts, &graph).unwrap();
+        let agg = aggregate(AggregateFunction::Sum, Some("value"));
+        let result = AggregateEvaluator::evaluate(&agg, &rows, &graph, &options).unwrap();
         assert_eq!(result.as_i64(), Some(60)); // 10 + 20 + 30 = 60
+
+        // Floats make the sum a float; non-numbers are ignored
+        assert_eq!(
+            AggregateEvaluator::sum(&[json!(1), json!(0.5), json!("2"), json!(2)]),
+            json!(3.5)
+        );
+    }
+
+    #[test]
+    fn test_sum_overflow_falls_back_to_float() {
+        let big = 9_000_000_000_000_000_000i64;
+        assert_eq!(
+            AggregateEvaluator::sum(&[json!(big), json!(big)]),
+            json!(big as f64 * 2.0)
+        );
+        assert_eq!(
+            AggregateEvaluator::sum(&[json!(i64::MAX), json!(-1)]),
+            json!(i64::MAX - 1)
+        );
+    }
+
+    #[test]
+    fn test_count_distinct_relationships() {
+        let mut graph = create_test_graph();
+        // Parallel edges of one type evaluate to equal objects
+        for _ in 0..3 {
+            graph.add_edge(crate::graph::Edge::new(0, 1, "knows"));
+        }
+        let rows: Vec<Bindings> = (0..graph.edges.len())
+            .map(|edge_idx| {
+                Bindings::from([(
+                    "n".to_string(),
+                    EntityId::Relationship {
+                        edge_idx,
+                        from_idx: 0,
+                        to_idx: 1,
+                        rel: "knows".to_string(),
+                        forward: true,
+                    },
+                )])
+            })
+            .collect();
+        let options = ExecutionOptions::default();
+
+        let mut agg = aggregate(AggregateFunction::Count, None);
+        agg.distinct = true;
+        let result = AggregateEvaluator::evaluate(&agg, &rows, &graph, &options).unwrap();
+        assert_eq!(result, json!(3));
+
+        // The same edge reached twice counts once
+        let twice = [rows[0].clone(), rows[0].clone()];
+        let result = AggregateEvaluator::evaluate(&agg, &twice, &graph, &options).unwrap();
+        assert_eq!(result, json!(1));
     }
 
     #[test]
     fn test_column_name() {
-        let agg_count = ast::AggregateExpression {
-            func: AggregateFunction::Count,
-            variable: "n".to_string(),
-            property: None,
-        };
+        let agg_count = aggregate(AggregateFunction::Count, None);
         assert_eq!(AggregateEvaluator::column_name(&agg_count), "COUNT(n)");
 
-        let agg_sum = ast::AggregateExpression {
-            func: AggregateFunction::Sum,
-            variable: "n".to_string(),
-            property: Some("value".to_string()),
-        };
+        let agg_sum = aggregate(AggregateFunction::Sum, Some("value"));
         assert_eq!(AggregateEvaluator::column_name(&agg_sum), "SUM(n.value)");
     }
 }
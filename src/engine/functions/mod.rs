@@ -4,6 +4,9 @@
 //! including aggregate functions, string functions, and mathematical functions.
 
 pub mod aggregate;
+pub mod registry;
+pub mod scalar;
+pub mod temporal;
 
 use std::collections::HashMap;
 
@@ -91,6 +94,7 @@ pub struct ExpressionContext<'a> {
 
 // Re-export aggregate evaluator
 pub use aggregate::AggregateEvaluator;
+pub use registry::{CustomFunction, FunctionRegistry};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,110 @@
+//! User-registered scalar functions.
+
+use super::{FunctionError, FunctionResult, scalar};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A user-supplied scalar function, called with its evaluated arguments.
+pub type CustomFunction = Arc<dyn Fn(&[Value]) -> FunctionResult<Value> + Send + Sync>;
+
+/// Scalar functions available to queries: the built-ins, plus any
+/// registered with [`FunctionRegistry::register`].
+///
+/// Names are case-insensitive. Built-ins are looked up first, so a custom
+/// function can't replace one.
+#[derive(Clone, Default)]
+pub struct FunctionRegistry {
+    custom: HashMap<String, CustomFunction>,
+}
+
+impl FunctionRegistry {
+    /// Create a registry holding only the built-in functions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `function` under `name`, replacing any custom function
+    /// already registered under that name.
+    pub fn register<F>(&mut self, name: &str, function: F)
+    where
+        F: Fn(&[Value]) -> FunctionResult<Value> + Send + Sync + 'static,
+    {
+        self.custom
+            .insert(name.to_ascii_lowercase(), Arc::new(function));
+    }
+
+    /// Whether a custom function is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.custom.contains_key(&name.to_ascii_lowercase())
+    }
+
+    /// Check that `name` is a known function. Built-ins also check
+    /// `arg_count`; custom functions check their own arguments when called.
+    pub fn validate(&self, name: &str, arg_count: usize) -> FunctionResult<()> {
+        match scalar::validate(name, arg_count) {
+            Err(FunctionError::NotImplemented(_)) if self.contains(name) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Call the function `name` with evaluated arguments.
+    pub fn call(&self, name: &str, args: &[Value]) -> FunctionResult<Value> {
+        match scalar::call(name, args) {
+            Err(FunctionError::NotImplemented(_)) => {
+                match self.custom.get(&name.to_ascii_lowercase()) {
+                    Some(function) => function(args),
+                    None => Err(FunctionError::NotImplemented(name.to_string())),
+                }
+            }
+            result => result,
+        }
+    }
+}
+
+impl fmt::Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&str> = self.custom.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        f.debug_struct("FunctionRegistry")
+            .field("custom", &names)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_functions_after_builtins() {
+        let mut registry = FunctionRegistry::new();
+        registry.register("double", |args| {
+            match args.first().and_then(Value::as_f64) {
+                Some(n) => Ok(Value::from(n * 2.0)),
+                None => Ok(Value::Null),
+            }
+        });
+        // Built-ins win over a custom function of the same name
+        registry.register("size", |_| Ok(Value::from(-1)));
+
+        assert!(registry.validate("DOUBLE", 1).is_ok());
+        assert_eq!(
+            registry.call("Double", &[Value::from(2)]).unwrap(),
+            Value::from(4.0)
+        );
+        assert_eq!(
+            registry.call("size", &[Value::from("abc")]).unwrap(),
+            Value::from(3)
+        );
+        assert!(matches!(
+            registry.validate("triple", 1),
+            Err(FunctionError::NotImplemented(_))
+        ));
+        assert_eq!(
+            format!("{:?}", registry),
+            r#"FunctionRegistry { custom: ["double", "size"] }"#
+        );
+    }
+}
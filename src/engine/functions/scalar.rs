@@ -0,0 +1,287 @@
+//! Scalar functions such as `toBoolean()`, `size()`, `head()` and `range()`.
+//!
+//! Scalar functions take already-evaluated arguments and return a single
+//! value per row. Names are matched case-insensitively, as in Cypher.
+
+use serde_json::Value;
+
+use super::{FunctionError, FunctionResult, temporal};
+
+/// Check that `name` is a known scalar function taking `arg_count` arguments.
+///
+/// Run before execution so that typos fail loudly instead of silently
+/// producing `null` on every row.
+pub fn validate(name: &str, arg_count: usize) -> FunctionResult<()> {
+    let (min, max) = match name.to_ascii_lowercase().as_str() {
+        "toboolean" | "size" | "reverse" | "head" | "last" | "tail" | "date" | "datetime"
+        | "type" => (1, 1),
+        "range" => (2, 3),
+        _ => return Err(FunctionError::NotImplemented(name.to_string())),
+    };
+
+    if arg_count < min || arg_count > max {
+        let expected = if min == max {
+            min.to_string()
+        } else {
+            format!("{} to {}", min, max)
+        };
+        return Err(FunctionError::InvalidArguments(
+            name.to_string(),
+            format!("expected {} argument(s), got {}", expected, arg_count),
+        ));
+    }
+    Ok(())
+}
+
+/// Call the scalar function `name` with evaluated arguments.
+pub fn call(name: &str, args: &[Value]) -> FunctionResult<Value> {
+    validate(name, args.len())?;
+    match name.to_ascii_lowercase().as_str() {
+        "toboolean" => Ok(to_boolean(&args[0])),
+        "size" => Ok(size(&args[0])),
+        "reverse" => Ok(reverse(&args[0])),
+        "head" => Ok(head(&args[0])),
+        "last" => Ok(last(&args[0])),
+        "tail" => Ok(tail(&args[0])),
+        "range" => range(&args[0], &args[1], args.get(2)),
+        "date" => Ok(temporal::date(&args[0])),
+        "datetime" => Ok(temporal::datetime(&args[0])),
+        // Relationship arguments are resolved before their values are taken
+        "type" => Ok(Value::Null),
+        _ => Err(FunctionError::NotImplemented(name.to_string())),
+    }
+}
+
+/// `toBoolean()`: booleans pass through, `"true"`/`"false"` (any case) and
+/// `1`/`0` (as numbers or strings) convert; anything else is `null`.
+pub fn to_boolean(value: &Value) -> Value {
+    let parsed = match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        },
+        Value::Number(n) => match n.as_i64() {
+            Some(1) => Some(true),
+            Some(0) => Some(false),
+            _ => None,
+        },
+        _ => None,
+    };
+    parsed.map(Value::Bool).unwrap_or(Value::Null)
+}
+
+/// `size()`: the number of elements in a list or characters in a string;
+/// anything else is `null`.
+pub fn size(value: &Value) -> Value {
+    match value {
+        Value::String(s) => Value::from(s.chars().count()),
+        Value::Array(items) => Value::from(items.len()),
+        _ => Value::Null,
+    }
+}
+
+/// `reverse()`: strings reverse by character, arrays by element; anything
+/// else is `null`.
+pub fn reverse(value: &Value) -> Value {
+    match value {
+        Value::String(s) => Value::String(s.chars().rev().collect()),
+        Value::Array(items) => Value::Array(items.iter().rev().cloned().collect()),
+        _ => Value::Null,
+    }
+}
+
+/// `head()`: the first element of a list, or `null` if it is empty or not
+/// a list.
+pub fn head(value: &Value) -> Value {
+    match value {
+        Value::Array(items) => items.first().cloned().unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+/// `last()`: the last element of a list, or `null` if it is empty or not
+/// a list.
+pub fn last(value: &Value) -> Value {
+    match value {
+        Value::Array(items) => items.last().cloned().unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+/// `tail()`: every element of a list but the first (an empty list stays
+/// empty); `null` if the value is not a list.
+pub fn tail(value: &Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.iter().skip(1).cloned().collect()),
+        _ => Value::Null,
+    }
+}
+
+/// Largest list `range()` will build.
+const MAX_RANGE_LEN: i128 = 1_000_000;
+
+/// `range()`: the integers from `start` to `end` inclusive, counting by
+/// `step` (default 1). A negative step counts down; a zero step, or a
+/// list longer than 1,000,000 elements, is an error. A `null` argument
+/// yields `null`.
+pub fn range(start: &Value, end: &Value, step: Option<&Value>) -> FunctionResult<Value> {
+    let args = [Some(start), Some(end), step];
+    if args.iter().flatten().any(|v| v.is_null()) {
+        return Ok(Value::Null);
+    }
+    let int_arg = |v: &Value| {
+        v.as_i64().ok_or_else(|| {
+            FunctionError::TypeError(
+                "range".to_string(),
+                format!("expected an integer, got {}", v),
+            )
+        })
+    };
+    let (start, end) = (int_arg(start)?, int_arg(end)?);
+    let step = step.map(int_arg).transpose()?.unwrap_or(1);
+    if step == 0 {
+        return Err(FunctionError::InvalidArguments(
+            "range".to_string(),
+            "step must not be zero".to_string(),
+        ));
+    }
+
+    let len = if (step > 0 && start <= end) || (step < 0 && start >= end) {
+        (i128::from(end) - i128::from(start)) / i128::from(step) + 1
+    } else {
+        0
+    };
+    if len > MAX_RANGE_LEN {
+        return Err(FunctionError::InvalidArguments(
+            "range".to_string(),
+            format!(
+                "range of {} elements exceeds the limit of {}",
+                len, MAX_RANGE_LEN
+            ),
+        ));
+    }
+
+    let mut items = Vec::with_capacity(len as usize);
+    let mut i = start;
+    while (step > 0 && i <= end) || (step < 0 && i >= end) {
+        items.push(Value::from(i));
+        match i.checked_add(step) {
+            Some(next) => i = next,
+            None => break,
+        }
+    }
+    Ok(Value::Array(items))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_boolean() {
+        assert_eq!(to_boolean(&json!(true)), json!(true));
+        assert_eq!(to_boolean(&json!("TRUE")), json!(true));
+        assert_eq!(to_boolean(&json!("false")), json!(false));
+        assert_eq!(to_boolean(&json!(1)), json!(true));
+        assert_eq!(to_boolean(&json!("0")), json!(false));
+        assert_eq!(to_boolean(&json!("yes")), Value::Null);
+        assert_eq!(to_boolean(&json!(2)), Value::Null);
+        assert_eq!(to_boolean(&Value::Null), Value::Null);
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(size(&json!(["a", null, 3])), json!(3));
+        assert_eq!(size(&json!([])), json!(0));
+        assert_eq!(size(&json!("héllo")), json!(5));
+        assert_eq!(size(&json!(12)), Value::Null);
+        assert_eq!(size(&Value::Null), Value::Null);
+    }
+
+    #[test]
+    fn test_reverse() {
+        assert_eq!(reverse(&json!("héllo")), json!("olléh"));
+        assert_eq!(reverse(&json!(["a", 1, null])), json!([null, 1, "a"]));
+        assert_eq!(reverse(&json!([])), json!([]));
+        assert_eq!(reverse(&json!(12)), Value::Null);
+        assert_eq!(reverse(&Value::Null), Value::Null);
+    }
+
+    #[test]
+    fn test_head_last_tail() {
+        let list = json!(["1", "2", "3"]);
+        assert_eq!(head(&list), json!("1"));
+        assert_eq!(last(&list), json!("3"));
+        assert_eq!(tail(&list), json!(["2", "3"]));
+
+        let empty = json!([]);
+        assert_eq!(head(&empty), Value::Null);
+        assert_eq!(last(&empty), Value::Null);
+        assert_eq!(tail(&empty), json!([]));
+
+        for not_list in [json!("abc"), json!(1), Value::Null] {
+            assert_eq!(head(&not_list), Value::Null);
+            assert_eq!(last(&not_list), Value::Null);
+            assert_eq!(tail(&not_list), Value::Null);
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        assert_eq!(
+            range(&json!(1), &json!(5), None).unwrap(),
+            json!([1, 2, 3, 4, 5])
+        );
+        assert_eq!(
+            range(&json!(0), &json!(10), Some(&json!(3))).unwrap(),
+            json!([0, 3, 6, 9])
+        );
+        assert_eq!(
+            range(&json!(5), &json!(1), Some(&json!(-2))).unwrap(),
+            json!([5, 3, 1])
+        );
+        assert_eq!(range(&json!(5), &json!(1), None).unwrap(), json!([]));
+        assert_eq!(range(&json!(1), &Value::Null, None).unwrap(), Value::Null);
+        assert!(matches!(
+            range(&json!(1), &json!(5), Some(&json!(0))),
+            Err(FunctionError::InvalidArguments(_, _))
+        ));
+        assert!(matches!(
+            range(&json!(0), &json!(i64::MAX), None),
+            Err(FunctionError::InvalidArguments(_, _))
+        ));
+        assert!(matches!(
+            range(&json!(i64::MAX), &json!(i64::MIN), Some(&json!(-1))),
+            Err(FunctionError::InvalidArguments(_, _))
+        ));
+        assert_eq!(
+            range(&json!(1), &json!(i64::MAX), Some(&json!(i64::MAX)))
+                .unwrap()
+                .as_array()
+                .map(Vec::len),
+            Some(1)
+        );
+        assert!(matches!(
+            range(&json!("1"), &json!(5), None),
+            Err(FunctionError::TypeError(_, _))
+        ));
+        assert!(validate("range", 1).is_err());
+        assert!(validate("range", 3).is_ok());
+    }
+
+    #[test]
+    fn test_call_is_case_insensitive() {
+        assert_eq!(call("TOBOOLEAN", &[json!("true")]).unwrap(), json!(true));
+        assert!(matches!(
+            call("toBool", &[json!("true")]),
+            Err(FunctionError::NotImplemented(_))
+        ));
+        assert!(matches!(
+            validate("toBoolean", 2),
+            Err(FunctionError::InvalidArguments(_, _))
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+//! Temporal functions: `date()` and `datetime()`.
+//!
+//! Values are normalized to fixed-width ISO-8601 strings (`YYYY-MM-DD` and
+//! `YYYY-MM-DDTHH:MM:SS.sssZ` in UTC), so the string comparisons used by
+//! WHERE order them chronologically regardless of the input format.
+
+use serde_json::Value;
+
+/// A parsed timestamp: calendar date, time of day and UTC offset.
+struct Timestamp {
+    year: i64,
+    month: u32,
+    day: u32,
+    /// Milliseconds since local midnight
+    millis: i64,
+    /// Offset from UTC in minutes
+    offset_minutes: i64,
+}
+
+/// `date()`: the calendar date of a `YYYY-MM-DD` string or ISO timestamp,
+/// as `YYYY-MM-DD`. Anything unparseable is `null`.
+pub fn date(value: &Value) -> Value {
+    match value.as_str().and_then(parse) {
+        Some(ts) => Value::String(format!("{:04}-{:02}-{:02}", ts.year, ts.month, ts.day)),
+        None => Value::Null,
+    }
+}
+
+/// `datetime()`: an ISO timestamp (or bare date, taken as midnight UTC)
+/// converted to UTC as `YYYY-MM-DDTHH:MM:SS.sssZ`. Anything unparseable is
+/// `null`.
+pub fn datetime(value: &Value) -> Value {
+    let Some(ts) = value.as_str().and_then(parse) else {
+        return Value::Null;
+    };
+
+    let total = days_from_civil(ts.year, ts.month, ts.day) * MILLIS_PER_DAY + ts.millis
+        - ts.offset_minutes * 60_000;
+    let (year, month, day) = civil_from_days(total.div_euclid(MILLIS_PER_DAY));
+    if !(0..=9999).contains(&year) {
+        return Value::Null;
+    }
+    let ms = total.rem_euclid(MILLIS_PER_DAY);
+    Value::String(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    ))
+}
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// Parse `YYYY-MM-DD`, optionally followed by `T` (or a space) and
+/// `HH:MM[:SS[.fff]]` with an optional `Z`, `±HH:MM`, `±HHMM` or `±HH`
+/// offset.
+fn parse(s: &str) -> Option<Timestamp> {
+    let s = s.trim();
+    let (date_part, time_part) = match s.find(['T', 't', ' ']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+
+    let mut fields = date_part.split('-');
+    let year = digits(fields.next()?, 4)?;
+    let month = digits(fields.next()?, 2)? as u32;
+    let day = digits(fields.next()?, 2)? as u32;
+    if fields.next().is_some() || !(1..=12).contains(&month) {
+        return None;
+    }
+    if day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let (millis, offset_minutes) = match time_part {
+        Some(t) => parse_time(t)?,
+        None => (0, 0),
+    };
+    Some(Timestamp {
+        year,
+        month,
+        day,
+        millis,
+        offset_minutes,
+    })
+}
+
+/// Parse the time-of-day and offset part of a timestamp.
+fn parse_time(s: &str) -> Option<(i64, i64)> {
+    let (clock, offset) = match s.find(['Z', 'z', '+', '-']) {
+        Some(i) => (&s[..i], Some(&s[i..])),
+        None => (s, None),
+    };
+
+    let (clock, fraction) = match clock.split_once('.') {
+        Some((clock, fraction)) => (clock, Some(fraction)),
+        None => (clock, None),
+    };
+    let mut fields = clock.split(':');
+    let hour = digits(fields.next()?, 2)?;
+    let minute = digits(fields.next()?, 2)?;
+    let second = fields.next().map_or(Some(0), |f| digits(f, 2))?;
+    if fields.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+    let millis = match fraction {
+        Some(f) if !f.is_empty() && f.len() <= 9 && f.bytes().all(|b| b.is_ascii_digit()) => {
+            format!("{:0<3}", &f[..f.len().min(3)])
+                .parse::<i64>()
+                .ok()?
+        }
+        Some(_) => return None,
+        None => 0,
+    };
+
+    let offset_minutes = match offset {
+        None | Some("Z") | Some("z") => 0,
+        Some(o) => {
+            let sign = if o.starts_with('-') { -1 } else { 1 };
+            let o = &o[1..];
+            let (hours, minutes) = match (o.get(..2), o.get(2..)) {
+                (Some(h), Some("")) => (digits(h, 2)?, 0),
+                (Some(h), Some(m)) => (digits(h, 2)?, digits(m.strip_prefix(':').unwrap_or(m), 2)?),
+                _ => return None,
+            };
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            sign * (hours * 60 + minutes)
+        }
+    };
+
+    Some((
+        ((hour * 60 + minute) * 60 + second) * 1000 + millis,
+        offset_minutes,
+    ))
+}
+
+/// Parse exactly `len` ASCII digits.
+fn digits(s: &str, len: usize) -> Option<i64> {
+    if s.len() != len || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_date() {
+        assert_eq!(date(&json!("2020-01-31")), json!("2020-01-31"));
+        assert_eq!(
+            date(&json!("2020-02-29T23:30:00+05:00")),
+            json!("2020-02-29")
+        );
+        assert_eq!(date(&json!("2019-02-29")), Value::Null);
+        assert_eq!(date(&json!("2020-1-5")), Value::Null);
+        assert_eq!(date(&json!("yesterday")), Value::Null);
+        assert_eq!(date(&json!(20200101)), Value::Null);
+    }
+
+    #[test]
+    fn test_datetime_normalizes_to_utc() {
+        assert_eq!(
+            datetime(&json!("2020-01-01")),
+            json!("2020-01-01T00:00:00.000Z")
+        );
+        assert_eq!(
+            datetime(&json!("2020-01-01T01:30:00+02:00")),
+            json!("2019-12-31T23:30:00.000Z")
+        );
+        assert_eq!(
+            datetime(&json!("2020-03-01 12:00:05.25-0130")),
+            json!("2020-03-01T13:30:05.250Z")
+        );
+        assert_eq!(
+            datetime(&json!("2020-01-01T10:00Z")),
+            json!("2020-01-01T10:00:00.000Z")
+        );
+        assert_eq!(datetime(&json!("2020-01-01T25:00:00Z")), Value::Null);
+        assert_eq!(datetime(&json!("2020-01-01T10:00:00+2")), Value::Null);
+        assert_eq!(datetime(&json!("2020-01-01T10:00:00+aé0")), Value::Null);
+    }
+
+    #[test]
+    fn test_civil_round_trip() {
+        for days in [-719_468, -1, 0, 59, 10_957, 2_932_896] {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+    }
+}
@@ -3,16 +3,22 @@
 //! This module provides the core query execution functionality for the Cypher-RS library.
 //! It is organized into submodules for better separation of concerns:
 
+pub mod cache;
 pub mod executor;
 pub mod functions;
+pub mod mutation;
 pub mod result_processor;
 pub mod storage;
+pub mod transaction;
 
 use crate::graph::Graph;
 use crate::parser;
-pub use executor::{EntityId, QueryExecutor};
+pub use cache::QueryCache;
+pub use executor::{EntityId, ExecutionOptions, QueryExecutor};
+pub use transaction::Transaction;
 
 use serde_json::Value;
+use std::collections::HashSet;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -25,6 +31,22 @@ pub enum EngineError {
 
     #[error("Invalid JSON structure: {0}")]
     InvalidJson(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl EngineError {
+    /// Prefix an execution error with the clause (and item) that raised it,
+    /// e.g. `RETURN item 2: Variable not bound: x`. Other errors pass through.
+    pub(crate) fn context(self, context: impl std::fmt::Display) -> Self {
+        match self {
+            EngineError::ExecutionError(msg) => {
+                EngineError::ExecutionError(format!("{}: {}", context, msg))
+            }
+            other => other,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, EngineError>;
@@ -34,31 +56,152 @@ pub type Result<T> = std::result::Result<T, EngineError>;
 pub struct QueryResult {
     pub columns: Vec<String>,
     pub rows: Vec<Value>,
+    /// Whether the rows come from an aggregating RETURN (`COUNT`, `SUM`).
+    pub aggregate: bool,
+    /// Number of rows before `SKIP` and `LIMIT` were applied.
+    ///
+    /// This counts rows after WHERE filtering, aggregation and `DISTINCT`,
+    /// so it is the total to paginate over. Only populated when requested
+    /// through [`ExecutionOptions::total_count`]; `None` otherwise.
+    pub total_count: Option<usize>,
 }
 
 impl QueryResult {
     pub fn new(columns: Vec<String>, rows: Vec<Value>) -> Self {
-        Self { columns, rows }
+        Self {
+            columns,
+            rows,
+            aggregate: false,
+            total_count: None,
+        }
+    }
+
+    /// Number of rows in the result.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Number of columns in the result.
+    pub fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Whether the query produced no meaningful results.
+    ///
+    /// For row-returning queries this means zero rows. An aggregate without
+    /// grouping keys always returns one row, so it counts as empty when
+    /// every value in that row is `0` — e.g. `COUNT(u)` over no matches.
+    /// Note that a `SUM` of zero over matched rows also reads as empty when
+    /// it is the only column.
+    pub fn is_empty(&self) -> bool {
+        match self.rows.as_slice() {
+            [] => true,
+            [row] if self.aggregate => row
+                .as_object()
+                .is_some_and(|obj| obj.values().all(|v| v.as_i64() == Some(0))),
+            _ => false,
+        }
     }
 
     /// Get the result as a JSON array of objects.
     pub fn as_json_array(&self) -> Value {
-        let arr: Vec<Value> = self
-            .rows
+        Value::Array(self.rows.iter().map(|row| self.row_object(row)).collect())
+    }
+
+    /// Write each row as one line of JSON (NDJSON), returning the number of
+    /// rows written.
+    ///
+    /// Rows hold the same columns as [`QueryResult::as_json_array`], but
+    /// are serialized one at a time instead of through a combined array.
+    pub fn write_ndjson(&self, writer: &mut impl std::io::Write) -> std::io::Result<usize> {
+        for row in &self.rows {
+            serde_json::to_writer(&mut *writer, &self.row_object(row))?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(self.rows.len())
+    }
+
+    /// A row restricted to the result's columns.
+    pub(crate) fn row_object(&self, row: &Value) -> Value {
+        let mut obj = serde_json::Map::new();
+        for col in &self.columns {
+            if let Some(row_obj) = row.as_object()
+                && let Some(val) = row_obj.get(col)
+            {
+                obj.insert(col.clone(), val.clone());
+            }
+        }
+        Value::Object(obj)
+    }
+
+    /// Rename aggregate columns such as `COUNT(u)` to identifier-safe names.
+    ///
+    /// A column of the form `FUNC(args)` becomes the lowercased function
+    /// name followed by `_` and the arguments, where `*` is written as
+    /// `star` and every other run of non-alphanumeric characters becomes a
+    /// single `_`: `COUNT(u)` → `count_u`, `SUM(u.age)` → `sum_u_age`,
+    /// `COUNT(*)` → `count_star`. Only aggregates (`COUNT`, `SUM`,
+    /// `COLLECT`) are renamed; other columns, including scalar function
+    /// calls such as `size(u.friends)` and explicit `AS` aliases, are left
+    /// unchanged. A name already taken by an earlier column gets a `_2`,
+    /// `_3`, … suffix, so `COUNT(u.a_b)` and `COUNT(u.a.b)` become
+    /// `count_u_a_b` and `count_u_a_b_2`. Row keys are renamed to match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::CypherEngine;
+    /// use serde_json::json;
+    ///
+    /// let data = json!({"users": [{"id": "1", "age": 30}]});
+    /// let engine = CypherEngine::from_json_auto(&data).unwrap();
+    /// let result = engine
+    ///     .execute("MATCH (u:users) RETURN COUNT(u), SUM(u.age) AS total")
+    ///     .unwrap()
+    ///     .with_sanitized_columns();
+    /// assert_eq!(result.columns, vec!["count_u", "total"]);
+    /// ```
+    pub fn with_sanitized_columns(mut self) -> Self {
+        let mut renames: Vec<(String, String)> = self
+            .columns
             .iter()
-            .map(|row| {
-                let mut obj = serde_json::Map::new();
-                for col in &self.columns {
-                    if let Some(row_obj) = row.as_object()
-                        && let Some(val) = row_obj.get(col)
-                    {
-                        obj.insert(col.clone(), val.clone());
+            .filter_map(|col| sanitize_aggregate_column(col).map(|new| (col.clone(), new)))
+            .collect();
+        if renames.is_empty() {
+            return self;
+        }
+
+        let mut taken: HashSet<String> = self
+            .columns
+            .iter()
+            .filter(|col| !renames.iter().any(|(old, _)| old == *col))
+            .cloned()
+            .collect();
+        for (_, new) in &mut renames {
+            let base = new.clone();
+            let mut n = 2;
+            while taken.contains(new.as_str()) {
+                *new = format!("{}_{}", base, n);
+                n += 1;
+            }
+            taken.insert(new.clone());
+        }
+
+        for col in &mut self.columns {
+            if let Some((_, new)) = renames.iter().find(|(old, _)| old == col) {
+                *col = new.clone();
+            }
+        }
+        for row in &mut self.rows {
+            if let Value::Object(obj) = row {
+                for (old, new) in &renames {
+                    if let Some(val) = obj.remove(old) {
+                        obj.insert(new.clone(), val);
                     }
                 }
-                Value::Object(obj)
-            })
-            .collect();
-        Value::Array(arr)
+            }
+        }
+        self
     }
 
     /// Get a single aggregate result (for queries like COUNT, SUM).
@@ -69,6 +212,131 @@ impl QueryResult {
             None
         }
     }
+
+    /// Build a graph with one node per row, to query the result further.
+    ///
+    /// Each node's id is taken from the `id_field` column and its label from
+    /// the `label_field` column, if given. Every other non-null column
+    /// becomes a property, named without its variable prefix: `u.name`
+    /// becomes `name`. Return the properties you want to keep, e.g.
+    /// `MATCH (u:admin) RETURN u.id, u.name`. The graph has no edges, since
+    /// rows don't carry relationships.
+    ///
+    /// Fails with [`EngineError::InvalidJson`] when a row has no usable id
+    /// or repeats one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::CypherEngine;
+    /// use serde_json::json;
+    ///
+    /// let data = json!({"users": [{"id": "1", "role": "admin"}, {"id": "2", "role": "user"}]});
+    /// let engine = CypherEngine::from_json_auto(&data).unwrap();
+    /// let admins = engine
+    ///     .execute("MATCH (u:users) WHERE u.role = \"admin\" RETURN u.id, u.role")
+    ///     .unwrap()
+    ///     .to_graph("u.id", Some("u.role"))
+    ///     .unwrap();
+    /// assert_eq!(admins.get_node("1").unwrap().label.as_deref(), Some("admin"));
+    /// ```
+    pub fn to_graph(&self, id_field: &str, label_field: Option<&str>) -> Result<Graph> {
+        let mut graph = Graph::new();
+        for (i, row) in self.rows.iter().enumerate() {
+            let id = row
+                .get(id_field)
+                .and_then(storage::json::id_to_string)
+                .ok_or_else(|| {
+                    EngineError::InvalidJson(format!("Row {} has no id in {}", i + 1, id_field))
+                })?;
+            if graph.get_node_index(&id).is_some() {
+                return Err(EngineError::InvalidJson(format!(
+                    "Row {} repeats id {}",
+                    i + 1,
+                    id
+                )));
+            }
+            let label = label_field
+                .and_then(|field| row.get(field))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+
+            let mut data = serde_json::Map::new();
+            for col in &self.columns {
+                match row.get(col) {
+                    Some(Value::Null) | None => {}
+                    Some(value) => {
+                        let name = col.split_once('.').map_or(col.as_str(), |(_, prop)| prop);
+                        data.insert(name.to_string(), value.clone());
+                    }
+                }
+            }
+            graph.add_node(crate::graph::Node::new(id, label, Value::Object(data)));
+        }
+        Ok(graph)
+    }
+}
+
+/// Identifier-safe name for an aggregate column, or `None` if `col` isn't
+/// of the form `FUNC(args)` for an aggregate function `FUNC`.
+fn sanitize_aggregate_column(col: &str) -> Option<String> {
+    let (func, rest) = col.split_once('(')?;
+    let args = rest.strip_suffix(')')?;
+    if !["COUNT", "SUM", "COLLECT"]
+        .iter()
+        .any(|agg| func.eq_ignore_ascii_case(agg))
+    {
+        return None;
+    }
+
+    let mut name = func.to_lowercase();
+    let mut pending_sep = true;
+    for c in args.replace('*', "star").chars() {
+        if c.is_alphanumeric() || c == '_' {
+            if pending_sep {
+                name.push('_');
+                pending_sep = false;
+            }
+            name.push(c);
+        } else {
+            pending_sep = true;
+        }
+    }
+    Some(name)
+}
+
+/// A parsed query that can be executed repeatedly without re-parsing.
+///
+/// Create one with [`CompiledQuery::new`] or `CypherEngine::prepare`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledQuery {
+    source: String,
+    query: parser::ast::Query,
+}
+
+impl CompiledQuery {
+    /// Parse `query` once for later execution.
+    pub fn new(query: &str) -> Result<Self> {
+        Ok(Self {
+            source: query.to_string(),
+            query: parser::parse_query(query)?,
+        })
+    }
+
+    /// The query text this was compiled from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The parsed query.
+    pub fn query(&self) -> &parser::ast::Query {
+        &self.query
+    }
+
+    /// Execute against a graph with the given execution options.
+    pub fn execute(&self, graph: &Graph, options: &ExecutionOptions) -> Result<QueryResult> {
+        QueryExecutor::execute_with_options(&self.query, graph, options)
+    }
 }
 
 /// Execute a Cypher query against a graph.
@@ -85,15 +353,57 @@ impl QueryResult {
 /// let result = execute("MATCH (n) RETURN COUNT(n)", &graph).unwrap();
 /// ```
 pub fn execute(query: &str, graph: &Graph) -> Result<QueryResult> {
+    execute_with_options(query, graph, &ExecutionOptions::default())
+}
+
+/// Execute a Cypher query against a graph with the given execution options.
+pub fn execute_with_options(
+    query: &str,
+    graph: &Graph,
+    options: &ExecutionOptions,
+) -> Result<QueryResult> {
     let ast_query = parser::parse_query(query)?;
-    QueryExecutor::execute(&ast_query, graph)
+    QueryExecutor::execute_with_options(&ast_query, graph, options)
+}
+
+/// Execute a Cypher query and write its rows to `writer` as NDJSON, one
+/// JSON object per line, returning the number of rows written.
+///
+/// Rows are written as they are projected where the query allows; see
+/// [`QueryExecutor::execute_for_each`].
+pub fn execute_to_writer(
+    query: &str,
+    graph: &Graph,
+    options: &ExecutionOptions,
+    writer: &mut impl std::io::Write,
+) -> Result<usize> {
+    let ast_query = parser::parse_query(query)?;
+    QueryExecutor::execute_for_each(&ast_query, graph, options, |row| {
+        serde_json::to_writer(&mut *writer, &row).map_err(std::io::Error::from)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    })
+}
+
+/// Execute a Cypher query that may mutate the graph (`SET`, `DETACH DELETE`).
+pub fn execute_mut(
+    query: &str,
+    graph: &mut Graph,
+    options: &ExecutionOptions,
+) -> Result<QueryResult> {
+    let ast_query = parser::parse_query(query)?;
+    QueryExecutor::execute_mut(&ast_query, graph, options)
 }
 
 // Re-exports for convenience
 pub use functions::{
-    AggregateEvaluator, EvalContext, ExpressionContext, FunctionError, FunctionResult,
+    AggregateEvaluator, CustomFunction, EvalContext, ExpressionContext, FunctionError,
+    FunctionRegistry, FunctionResult,
+};
+pub use storage::{
+    CsvConfig, CsvStorage, GraphConfig, GraphConfigBuilder, JsonStorage, MemoryStorage,
+    MemoryStorageBuilder, Storage, SyncStorage,
 };
-pub use storage::{JsonStorage, MemoryStorage, MemoryStorageBuilder, Storage, SyncStorage};
 pub use storage::{StorageError, StorageFeature, StorageMetadata, StorageResult};
 
 #[cfg(test)]
@@ -126,6 +436,114 @@ mod tests {
         graph
     }
 
+    #[test]
+    fn test_sanitized_columns() {
+        let graph = create_test_graph();
+        let result = execute(
+            "MATCH (n) RETURN COUNT(*), SUM(n.age), COUNT(n) AS total",
+            &graph,
+        )
+        .unwrap()
+        .with_sanitized_columns();
+        assert_eq!(result.columns, vec!["count_star", "sum_n_age", "total"]);
+        assert_eq!(result.rows[0]["sum_n_age"], 90);
+        assert!(result.rows[0].get("SUM(n.age)").is_none());
+
+        assert_eq!(sanitize_aggregate_column("n.name"), None);
+        assert_eq!(sanitize_aggregate_column("size(n.friends)"), None);
+    }
+
+    #[test]
+    fn test_sanitized_columns_collisions() {
+        let result = QueryResult::new(
+            vec![
+                "count_u_a_b".to_string(),
+                "COUNT(u.a_b)".to_string(),
+                "COUNT(u.a.b)".to_string(),
+                "size(u.a)".to_string(),
+            ],
+            vec![json!({"count_u_a_b": 0, "COUNT(u.a_b)": 1, "COUNT(u.a.b)": 2, "size(u.a)": 3})],
+        )
+        .with_sanitized_columns();
+        assert_eq!(
+            result.columns,
+            vec!["count_u_a_b", "count_u_a_b_2", "count_u_a_b_3", "size(u.a)"]
+        );
+        assert_eq!(
+            result.rows[0],
+            json!({"count_u_a_b": 0, "count_u_a_b_2": 1, "count_u_a_b_3": 2, "size(u.a)": 3})
+        );
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let graph = create_test_graph();
+
+        let result = execute("MATCH (n:nobody) RETURN n.id", &graph).unwrap();
+        assert!(result.is_empty());
+        assert_eq!(result.row_count(), 0);
+        assert_eq!(result.column_count(), 1);
+
+        let result = execute("MATCH (n:nobody) RETURN COUNT(n)", &graph).unwrap();
+        assert_eq!(result.row_count(), 1);
+        assert!(result.is_empty());
+
+        let result = execute("MATCH (n:admin) RETURN COUNT(n), SUM(n.age)", &graph).unwrap();
+        assert!(!result.is_empty());
+
+        // A zero-valued property is a real row, not an empty aggregate
+        let result = QueryResult::new(vec!["n.x".to_string()], vec![json!({"n.x": 0})]);
+        assert!(!result.is_empty());
+    }
+
+    #[test]
+    fn test_to_graph() {
+        let data = serde_json::json!({
+            "users": [
+                { "id": "1", "role": "admin", "name": "Alice" },
+                { "id": "2", "role": "user", "name": "Bob" },
+                { "id": "3", "role": "admin" }
+            ]
+        });
+        let graph = storage::json::build_graph_from_root_object(&data, "Root").unwrap();
+        let admins = execute(
+            "MATCH (u:users) WHERE u.role = \"admin\" RETURN u.id, u.role, u.name",
+            &graph,
+        )
+        .unwrap()
+        .to_graph("u.id", Some("u.role"))
+        .unwrap();
+
+        assert_eq!(admins.node_count(), 2);
+        assert_eq!(admins.edge_count(), 0);
+        let result = execute("MATCH (a:admin) RETURN COUNT(a)", &admins).unwrap();
+        assert_eq!(result.get_single_value(), Some(&Value::from(2)));
+        assert_eq!(
+            *admins.get_node("1").unwrap().data,
+            serde_json::json!({"id": "1", "role": "admin", "name": "Alice"})
+        );
+        // Null columns are left out
+        assert_eq!(admins.get_node("3").unwrap().get_property("name"), None);
+
+        let result = execute("MATCH (u:users) RETURN u.role", &graph).unwrap();
+        assert!(result.to_graph("u.role", None).is_err());
+        assert!(result.to_graph("u.id", None).is_err());
+    }
+
+    #[test]
+    fn test_compiled_query_reuse() {
+        let graph = create_test_graph();
+        let compiled = CompiledQuery::new("MATCH (n:admin) RETURN COUNT(n)").unwrap();
+        assert_eq!(compiled.source(), "MATCH (n:admin) RETURN COUNT(n)");
+        for _ in 0..2 {
+            let result = compiled
+                .execute(&graph, &ExecutionOptions::default())
+                .unwrap();
+            assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+        }
+        assert!(CompiledQuery::new("MATCH (n RETURN n").is_err());
+    }
+
     #[test]
     fn test_execute_convenience() {
         let graph = create_test_graph();
@@ -133,6 +551,21 @@ mod tests {
         assert_eq!(result.get_single_value().unwrap().as_i64(), Some(3));
     }
 
+    #[test]
+    fn test_graph_stats() {
+        let graph = create_test_graph();
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+
+        let stats = graph.stats();
+        assert_eq!(stats.node_count, 3);
+        assert_eq!(stats.edge_count, 2);
+        assert_eq!(stats.nodes_per_label.len(), 2);
+        assert_eq!(stats.nodes_per_label.get("admin"), Some(&2));
+        assert_eq!(stats.nodes_per_label.get("user"), Some(&1));
+        assert_eq!(stats.edges_per_type.get("knows"), Some(&2));
+    }
+
     #[test]
     fn test_storage_integration() {
         let data = json!({
@@ -171,6 +604,132 @@ mod tests {
         assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
     }
 
+    #[test]
+    fn test_detach_delete() {
+        let mut graph = create_test_graph();
+        graph.add_edge(crate::graph::Edge::new(1, 0, "knows".to_string()));
+
+        let result = execute_mut(
+            "MATCH (n) WHERE n.id = \"2\" DETACH DELETE n",
+            &mut graph,
+            &ExecutionOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.rows[0]["nodes_deleted"], 1);
+        assert_eq!(result.rows[0]["relationships_deleted"], 3);
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.edges.is_empty());
+        assert!(graph.get_node("2").is_none());
+        assert_eq!(graph.get_node_index("3"), Some(1));
+
+        let result = execute("MATCH (n) RETURN COUNT(n)", &graph).unwrap();
+        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+    }
+
+    #[test]
+    fn test_delete_connected_node_errors() {
+        let mut graph = create_test_graph();
+        let err = execute_mut(
+            "MATCH (n) WHERE n.id = \"2\" DELETE n",
+            &mut graph,
+            &ExecutionOptions::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("DETACH DELETE"));
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+    }
+
+    #[test]
+    fn test_delete_relationship_then_node() {
+        let mut graph = create_test_graph();
+        let result = execute_mut(
+            "MATCH (a)-[r]->(b) WHERE b.id = \"3\" DELETE r, b",
+            &mut graph,
+            &ExecutionOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.rows[0]["nodes_deleted"], 1);
+        assert_eq!(result.rows[0]["relationships_deleted"], 1);
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn test_delete_keeps_parallel_relationship() {
+        let mut graph = create_test_graph();
+        graph.edges[0].data = json!({"w": 1});
+        graph.add_edge(crate::graph::Edge::new(0, 1, "knows").with_data(json!({"w": 2})));
+
+        let result = execute_mut(
+            "MATCH (a)-[r:knows]->(b) WHERE r.w = 1 DELETE r",
+            &mut graph,
+            &ExecutionOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.rows[0]["relationships_deleted"], 1);
+        assert_eq!(graph.edges.len(), 2);
+        assert!(graph.edges.iter().any(|e| e.data == json!({"w": 2})));
+    }
+
+    #[test]
+    fn test_set_append_to_array() {
+        let mut graph = create_test_graph();
+        graph.nodes[0].data["friends"] = json!([]);
+
+        let result = execute_mut(
+            "MATCH (u) WHERE u.id=\"1\" SET u.friends = u.friends + [\"4\"]",
+            &mut graph,
+            &ExecutionOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.rows[0]["properties_set"], 1);
+        assert_eq!(graph.nodes[0].data["friends"], json!(["4"]));
+
+        execute_mut(
+            "MATCH (u) WHERE u.id=\"1\" SET u.friends = u.friends + [\"5\", \"6\"]",
+            &mut graph,
+            &ExecutionOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(graph.nodes[0].data["friends"], json!(["4", "5", "6"]));
+    }
+
+    #[test]
+    fn test_set_append_to_missing_property() {
+        let mut graph = create_test_graph();
+        execute_mut(
+            "MATCH (u) WHERE u.id = \"2\" SET u.tags = u.tags + [\"new\"], u.age = u.age + 1",
+            &mut graph,
+            &ExecutionOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(graph.nodes[1].data["tags"], json!(["new"]));
+        assert_eq!(graph.nodes[1].data["age"], json!(26));
+    }
+
+    #[test]
+    fn test_set_add_overflow_falls_back_to_float() {
+        let mut graph = create_test_graph();
+        graph.nodes[0].data["big"] = json!(i64::MAX);
+        execute_mut(
+            "MATCH (u) WHERE u.id = \"1\" SET u.x = u.big + 1, u.y = u.big + -1",
+            &mut graph,
+            &ExecutionOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(graph.nodes[0].data["x"], json!(i64::MAX as f64 + 1.0));
+        assert_eq!(graph.nodes[0].data["y"], json!(i64::MAX - 1));
+    }
+
+    #[test]
+    fn test_delete_requires_execute_mut() {
+        let graph = create_test_graph();
+        let err = execute("MATCH (n) DETACH DELETE n", &graph).unwrap_err();
+        assert!(err.to_string().contains("execute_mut"));
+    }
+
     #[test]
     fn test_query_result_as_json() {
         let graph = create_test_graph();
@@ -0,0 +1,295 @@
+//! Graph mutations applied after matching (SET, DELETE, DETACH DELETE).
+
+use crate::graph::Graph;
+use crate::parser::ast;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use super::executor::{Bindings, EntityId, ExecutionOptions, QueryExecutor};
+use super::{EngineError, QueryResult, Result};
+
+/// Apply a SET clause to every matched binding.
+///
+/// Values are evaluated against the graph as it stands when each item is
+/// applied, so repeated matches of the same node see earlier updates. The
+/// result has a single row reporting `properties_set`.
+pub fn apply_set(
+    set_clause: &ast::SetClause,
+    bindings_list: &[Bindings],
+    graph: &mut Graph,
+    options: &ExecutionOptions,
+) -> Result<QueryResult> {
+    let mut properties_set = 0usize;
+
+    for bindings in bindings_list {
+        for (item_idx, item) in set_clause.items.iter().enumerate() {
+            let idx = match bindings.get(&item.variable) {
+                Some(EntityId::Node(idx)) => *idx,
+                other => {
+                    let message = match other {
+                        Some(EntityId::Relationship { .. }) => format!(
+                            "SET on relationship variable {} is not supported",
+                            item.variable
+                        ),
+                        Some(EntityId::Value(_)) => {
+                            format!("SET requires a node, but {} is a value", item.variable)
+                        }
+                        _ => format!("Variable not bound: {}", item.variable),
+                    };
+                    return Err(EngineError::ExecutionError(message)
+                        .context(format_args!("SET item {}", item_idx + 1)));
+                }
+            };
+
+            let value = evaluate_value(&item.value, bindings, graph, options);
+            let node = &mut graph.nodes[idx];
+            if !node.data.is_object() {
+                *node.data = Value::Object(serde_json::Map::new());
+            }
+            if let Value::Object(obj) = &mut *node.data {
+                obj.insert(item.property.clone(), value);
+            }
+            properties_set += 1;
+        }
+    }
+
+    let mut row = serde_json::Map::new();
+    row.insert("properties_set".to_string(), properties_set.into());
+
+    Ok(QueryResult::new(
+        vec!["properties_set".to_string()],
+        vec![Value::Object(row)],
+    ))
+}
+
+/// Evaluate a value expression to a JSON value.
+///
+/// A property that is missing (or a variable that isn't bound) evaluates
+/// to `null`, as does a scalar function call that fails. Function calls
+/// are resolved through `options.functions`.
+pub fn evaluate_value(
+    expr: &ast::ValueExpression,
+    bindings: &Bindings,
+    graph: &Graph,
+    options: &ExecutionOptions,
+) -> Value {
+    match expr {
+        ast::ValueExpression::Literal(lit) => literal_value(lit),
+        ast::ValueExpression::List(items) => {
+            Value::Array(items.iter().map(literal_value).collect())
+        }
+        ast::ValueExpression::PropertyOrVariable(pv) => match bindings.get(&pv.variable) {
+            Some(EntityId::Node(idx)) => {
+                let node = &graph.nodes[*idx];
+                match &pv.property {
+                    Some(prop) => node.get_property(prop).cloned().unwrap_or(Value::Null),
+                    None => Value::String(node.id.clone()),
+                }
+            }
+            Some(EntityId::Relationship {
+                edge_idx,
+                from_idx,
+                to_idx,
+                rel,
+                forward,
+            }) => match pv.property.as_deref() {
+                // The full object, so COLLECT and WITH keep each edge's identity
+                None => QueryExecutor::relationship_value(*from_idx, *to_idx, rel, *forward, graph),
+                Some("type") => Value::String(rel.clone()),
+                Some(prop) => graph
+                    .edges
+                    .get(*edge_idx)
+                    .and_then(|edge| edge.get_property(prop))
+                    .cloned()
+                    .unwrap_or(Value::Null),
+            },
+            Some(EntityId::Value(value)) => match &pv.property {
+                Some(prop) => value.get(prop).cloned().unwrap_or(Value::Null),
+                None => value.clone(),
+            },
+            None => Value::Null,
+        },
+        ast::ValueExpression::Function(func) if func.name.eq_ignore_ascii_case("type") => {
+            // type() needs the relationship itself, not its evaluated value
+            match func
+                .args
+                .first()
+                .and_then(|arg| arg.as_property_or_variable())
+            {
+                Some(pv) if pv.property.is_none() => match bindings.get(&pv.variable) {
+                    Some(EntityId::Relationship { rel, .. }) => Value::String(rel.clone()),
+                    _ => Value::Null,
+                },
+                _ => Value::Null,
+            }
+        }
+        ast::ValueExpression::Function(func) => {
+            let args: Vec<Value> = func
+                .args
+                .iter()
+                .map(|arg| evaluate_value(arg, bindings, graph, options))
+                .collect();
+            options
+                .functions
+                .call(&func.name, &args)
+                .unwrap_or(Value::Null)
+        }
+        ast::ValueExpression::Add(left, right) => add_values(
+            evaluate_value(left, bindings, graph, options),
+            evaluate_value(right, bindings, graph, options),
+        ),
+        ast::ValueExpression::Multiply(left, right) => multiply_values(
+            evaluate_value(left, bindings, graph, options),
+            evaluate_value(right, bindings, graph, options),
+        ),
+    }
+}
+
+pub(crate) fn literal_value(lit: &ast::Literal) -> Value {
+    match lit {
+        ast::Literal::String(s) => Value::String(s.clone()),
+        ast::Literal::Number(n) => Value::Number((*n).into()),
+    }
+}
+
+/// Cypher `+`: lists concatenate (a null side counts as an empty list),
+/// numbers add, and strings concatenate. Anything else yields `null`.
+fn add_values(left: Value, right: Value) -> Value {
+    match (left, right) {
+        (Value::Array(mut a), Value::Array(b)) => {
+            a.extend(b);
+            Value::Array(a)
+        }
+        (Value::Null, Value::Array(b)) => Value::Array(b),
+        (Value::Array(a), Value::Null) => Value::Array(a),
+        (Value::Array(mut a), scalar) => {
+            a.push(scalar);
+            Value::Array(a)
+        }
+        (scalar, Value::Array(b)) => {
+            let mut items = vec![scalar];
+            items.extend(b);
+            Value::Array(items)
+        }
+        (Value::Number(a), Value::Number(b)) => {
+            if let (Some(x), Some(y)) = (a.as_i64(), b.as_i64())
+                && let Some(sum) = x.checked_add(y)
+            {
+                return Value::Number(sum.into());
+            }
+            match (a.as_f64(), b.as_f64()) {
+                (Some(x), Some(y)) => serde_json::Number::from_f64(x + y)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null),
+                _ => Value::Null,
+            }
+        }
+        (Value::String(a), Value::String(b)) => Value::String(a + &b),
+        _ => Value::Null,
+    }
+}
+
+/// Cypher `*`: numbers multiply, staying integers when both sides are.
+/// Anything else yields `null`.
+fn multiply_values(left: Value, right: Value) -> Value {
+    let (Value::Number(a), Value::Number(b)) = (left, right) else {
+        return Value::Null;
+    };
+    if let (Some(x), Some(y)) = (a.as_i64(), b.as_i64())
+        && let Some(product) = x.checked_mul(y)
+    {
+        return Value::Number(product.into());
+    }
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => serde_json::Number::from_f64(x * y)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+/// Apply a DELETE clause to every matched binding.
+///
+/// Relationship variables remove the matched edge. Node variables remove
+/// the node; without `DETACH`, a node that still has relationships is an
+/// error and the graph is left untouched. The result has a single row
+/// reporting `nodes_deleted` and `relationships_deleted`.
+pub fn apply_delete(
+    delete_clause: &ast::DeleteClause,
+    bindings_list: &[Bindings],
+    graph: &mut Graph,
+) -> Result<QueryResult> {
+    let mut node_indices = HashSet::new();
+    let mut relationships = HashSet::new();
+
+    for bindings in bindings_list {
+        for var in &delete_clause.variables {
+            match bindings.get(var) {
+                Some(EntityId::Node(idx)) => {
+                    node_indices.insert(*idx);
+                }
+                Some(EntityId::Relationship { edge_idx, .. }) => {
+                    relationships.insert(*edge_idx);
+                }
+                Some(EntityId::Value(_)) => {
+                    return Err(EngineError::ExecutionError(format!(
+                        "DELETE requires a node or relationship, but {} is a value",
+                        var
+                    )));
+                }
+                None => {
+                    return Err(EngineError::ExecutionError(format!(
+                        "Variable not bound: {}",
+                        var
+                    )));
+                }
+            }
+        }
+    }
+
+    if !delete_clause.detach {
+        // Edges deleted by this same clause don't count as remaining relationships
+        let connected = graph
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(edge_idx, _)| !relationships.contains(edge_idx))
+            .flat_map(|(_, e)| [e.from, e.to])
+            .filter(|idx| node_indices.contains(idx))
+            .min();
+        if let Some(idx) = connected {
+            return Err(EngineError::ExecutionError(format!(
+                "Cannot delete node {} because it still has relationships; use DETACH DELETE",
+                graph.nodes[idx].id
+            )));
+        }
+    }
+
+    let edge_count = graph.edges.len();
+    if !relationships.is_empty() {
+        let mut edge_idx = 0;
+        graph.edges.retain(|_| {
+            let keep = !relationships.contains(&edge_idx);
+            edge_idx += 1;
+            keep
+        });
+    }
+    let mut relationships_deleted = edge_count - graph.edges.len();
+
+    relationships_deleted += graph.detach_delete_nodes(&node_indices);
+
+    let mut row = serde_json::Map::new();
+    row.insert("nodes_deleted".to_string(), node_indices.len().into());
+    row.insert(
+        "relationships_deleted".to_string(),
+        relationships_deleted.into(),
+    );
+
+    Ok(QueryResult::new(
+        vec![
+            "nodes_deleted".to_string(),
+            "relationships_deleted".to_string(),
+        ],
+        vec![Value::Object(row)],
+    ))
+}
@@ -13,16 +13,45 @@ pub fn deduplicate_rows(result: &mut QueryResult) {
 }
 
 /// Sort rows in a query result according to an ORDER BY clause.
+///
+/// Each sort item names a column: its rendered expression, such as `u.age`
+/// or `size(u.friends)`, or a RETURN alias. Rows lacking the column keep
+/// their relative order for that item.
 pub fn sort_rows(result: &mut QueryResult, order_by: &ast::OrderByClause) {
-    result.rows.sort_by(|a, b| {
-        for item in &order_by.items {
-            let col_key = if let Some(ref prop) = item.expression.property {
-                format!("{}.{}", item.expression.variable, prop)
-            } else {
-                item.expression.variable.clone()
-            };
-            let a_val = a.get(&col_key);
-            let b_val = b.get(&col_key);
+    let columns: Vec<String> = order_by
+        .items
+        .iter()
+        .map(|item| item.expression.to_string())
+        .collect();
+    let keys = result
+        .rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|col| row.get(col).cloned().unwrap_or(Value::Null))
+                .collect()
+        })
+        .collect();
+    sort_rows_by_keys(result, keys, order_by);
+}
+
+/// Sort rows by precomputed sort keys, one per row with a value per ORDER
+/// BY item.
+///
+/// `null` sorts after every other value, so it comes last in ascending
+/// order and first in descending order. The sort is stable.
+pub fn sort_rows_by_keys(
+    result: &mut QueryResult,
+    keys: Vec<Vec<Value>>,
+    order_by: &ast::OrderByClause,
+) {
+    let mut keyed: Vec<(Vec<Value>, Value)> = keys
+        .into_iter()
+        .zip(std::mem::take(&mut result.rows))
+        .collect();
+    keyed.sort_by(|(a, _), (b, _)| {
+        for (item, (a_val, b_val)) in order_by.items.iter().zip(a.iter().zip(b)) {
             let ord = compare_values(a_val, b_val);
             let cmp = if item.direction == ast::SortDirection::Desc {
                 ord.reverse()
@@ -35,26 +64,57 @@ pub fn sort_rows(result: &mut QueryResult, order_by: &ast::OrderByClause) {
         }
         std::cmp::Ordering::Equal
     });
+    result.rows = keyed.into_iter().map(|(_, row)| row).collect();
+}
+
+/// Apply SKIP and LIMIT to an already sorted result.
+pub fn skip_and_limit(result: &mut QueryResult, skip: Option<usize>, limit: Option<usize>) {
+    if let Some(skip) = skip {
+        result.rows.drain(..skip.min(result.rows.len()));
+    }
+    if let Some(limit) = limit {
+        result.rows.truncate(limit);
+    }
 }
 
-fn compare_values(a: Option<&Value>, b: Option<&Value>) -> std::cmp::Ordering {
+/// Total order over JSON values used by ORDER BY.
+///
+/// Values rank by type first: booleans, numbers, strings, lists, maps, and
+/// `null` last. Numbers compare by value (integers exactly, ties between an
+/// integer and a float broken by kind); strings compare lexically, even
+/// when they look like numbers.
+fn compare_values(a: &Value, b: &Value) -> std::cmp::Ordering {
+    fn type_rank(value: &Value) -> u8 {
+        match value {
+            Value::Bool(_) => 0,
+            Value::Number(_) => 1,
+            Value::String(_) => 2,
+            Value::Array(_) => 3,
+            Value::Object(_) => 4,
+            Value::Null => 5,
+        }
+    }
+
+    fn number_key(n: &serde_json::Number) -> (f64, Option<i128>) {
+        let exact = n
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| n.as_u64().map(i128::from));
+        (n.as_f64().unwrap_or(0.0), exact)
+    }
+
     match (a, b) {
-        (None, None) => std::cmp::Ordering::Equal,
-        (None, Some(_)) => std::cmp::Ordering::Less,
-        (Some(_), None) => std::cmp::Ordering::Greater,
-        (Some(va), Some(vb)) => match (va.as_i64(), vb.as_i64()) {
-            (Some(na), Some(nb)) => na.cmp(&nb),
-            _ => {
-                let sa = va.as_str().unwrap_or_default();
-                let sb = vb.as_str().unwrap_or_default();
-                // Try numeric comparison for string-represented numbers
-                if let (Ok(na), Ok(nb)) = (sa.parse::<f64>(), sb.parse::<f64>()) {
-                    na.partial_cmp(&nb).unwrap_or(std::cmp::Ordering::Equal)
-                } else {
-                    sa.cmp(sb)
-                }
-            }
-        },
+        (Value::Number(na), Value::Number(nb)) => {
+            let (fa, ia) = number_key(na);
+            let (fb, ib) = number_key(nb);
+            fa.total_cmp(&fb).then(ia.cmp(&ib))
+        }
+        (Value::String(sa), Value::String(sb)) => sa.cmp(sb),
+        (Value::Bool(ba), Value::Bool(bb)) => ba.cmp(bb),
+        (Value::Null, Value::Null) => std::cmp::Ordering::Equal,
+        _ => type_rank(a)
+            .cmp(&type_rank(b))
+            .then_with(|| a.to_string().cmp(&b.to_string())),
     }
 }
 
@@ -214,6 +274,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_skip_and_limit_after_sort() {
+        let graph = create_test_graph();
+        let parsed =
+            parser::parse_query("MATCH (n) RETURN n.age ORDER BY n.age DESC SKIP 1 LIMIT 1")
+                .unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert_eq!(result.rows, vec![json!({"n.age": 30})]);
+
+        let parsed = parser::parse_query("MATCH (n) RETURN n.age SKIP 10").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        assert!(result.rows.is_empty());
+
+        for query in [
+            "MATCH (n) RETURN n.age LIMIT 0",
+            "MATCH (n) RETURN COUNT(n) LIMIT 0",
+            "MATCH (n) RETURN n.age SKIP 3 LIMIT 5",
+        ] {
+            let parsed = parser::parse_query(query).unwrap();
+            let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+            assert!(result.rows.is_empty(), "{}", query);
+        }
+    }
+
     #[test]
     fn test_sort_with_distinct() {
         let graph = create_test_graph();
@@ -228,4 +312,87 @@ mod tests {
             .collect();
         assert_eq!(roles, vec!["admin", "user"]);
     }
+
+    #[test]
+    fn test_sort_mixed_values() {
+        let mut graph = Graph::new();
+        for (id, v) in [
+            ("1", json!("10")),
+            ("2", json!("10x")),
+            ("3", json!("9")),
+            ("4", json!(2.5)),
+            ("5", json!(null)),
+            ("6", json!(1)),
+            ("7", json!("1e5")),
+            ("8", json!(true)),
+        ] {
+            graph.add_node(Node::new(id.to_string(), None, json!({ "v": v })));
+        }
+
+        let parsed = parser::parse_query("MATCH (u) RETURN u.v ORDER BY u.v").unwrap();
+        let result = QueryExecutor::execute(&parsed, &graph).unwrap();
+        let values: Vec<_> = result.rows.iter().map(|r| r["u.v"].clone()).collect();
+        assert_eq!(
+            values,
+            vec![
+                json!(true),
+                json!(1),
+                json!(2.5),
+                json!("10"),
+                json!("10x"),
+                json!("1e5"),
+                json!("9"),
+                json!(null)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_expression() {
+        let mut graph = Graph::new();
+        for data in [
+            json!({"id": "1", "first": "Ann", "last": "Lee", "friends": ["2"]}),
+            json!({"id": "2", "first": "Bob", "last": "Kim", "friends": ["1", "3", "4"]}),
+            json!({"id": "3", "first": "Al", "last": "Kim", "friends": []}),
+            json!({"id": "4", "first": "Cy", "last": "Lee"}),
+        ] {
+            let id = data["id"].as_str().unwrap().to_string();
+            graph.add_node(Node::new(id, None, data));
+        }
+        let ids = |q: &str| -> Vec<String> {
+            let parsed = parser::parse_query(q).unwrap();
+            QueryExecutor::execute(&parsed, &graph)
+                .unwrap()
+                .rows
+                .iter()
+                .map(|r| r["n.id"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        // The sort key need not be returned; null sizes sort last ascending
+        assert_eq!(
+            ids("MATCH (n) RETURN n.id ORDER BY size(n.friends) DESC"),
+            vec!["4", "2", "1", "3"]
+        );
+        assert_eq!(
+            ids("MATCH (n) RETURN n.id ORDER BY size(n.friends)"),
+            vec!["3", "1", "2", "4"]
+        );
+        assert_eq!(
+            ids("MATCH (n) RETURN n.id ORDER BY n.last + \" \" + n.first"),
+            vec!["3", "2", "1", "4"]
+        );
+        // A returned expression or alias is matched to its column
+        assert_eq!(
+            ids("MATCH (n) RETURN n.id, size(n.friends) AS c ORDER BY c DESC, n.id"),
+            vec!["4", "2", "1", "3"]
+        );
+
+        let parsed =
+            parser::parse_query("MATCH (n) RETURN DISTINCT n.last ORDER BY n.first").unwrap();
+        let err = QueryExecutor::execute(&parsed, &graph).unwrap_err();
+        assert!(err.to_string().contains("ORDER BY item 1"), "{}", err);
+        let parsed = parser::parse_query("MATCH (n) RETURN n.id ORDER BY nope(n.id)").unwrap();
+        assert!(QueryExecutor::execute(&parsed, &graph).is_err());
+    }
 }
@@ -0,0 +1,390 @@
+use super::storage_trait::{
+    StorageError, StorageFeature, StorageMetadata, StorageResult, SyncStorage,
+};
+use crate::graph::{Edge, Graph, Node};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Options mapping CSV columns to node ids, labels and relations.
+///
+/// Every row becomes one node. The remaining columns become properties:
+/// integers, floats and `true`/`false` are converted to JSON numbers and
+/// booleans, other cells stay strings, and empty cells are left out.
+/// Numbers written with leading zeros, such as zip codes like `02134`,
+/// stay strings so the zeros aren't lost.
+#[derive(Debug, Clone)]
+pub struct CsvConfig {
+    /// Column holding each row's node id. Defaults to `id`.
+    pub id_column: String,
+    /// Column holding each row's label; rows with an empty cell fall back
+    /// to [`CsvConfig::default_label`]. `None` by default.
+    pub label_column: Option<String>,
+    /// Label for rows without one of their own. Defaults to `rows`.
+    pub default_label: String,
+    /// Columns listing the ids of related rows, separated by
+    /// [`CsvConfig::list_separator`].
+    ///
+    /// Each listed id becomes an edge typed by the column name, and the
+    /// property holds the ids as a list. Ids that match no row are
+    /// skipped. Empty by default.
+    pub relation_columns: Vec<String>,
+    /// Field delimiter. Defaults to `,`.
+    pub delimiter: char,
+    /// Separator between ids in a relation column. Defaults to `;`.
+    pub list_separator: char,
+}
+
+impl Default for CsvConfig {
+    fn default() -> Self {
+        Self {
+            id_column: "id".to_string(),
+            label_column: None,
+            default_label: "rows".to_string(),
+            relation_columns: Vec::new(),
+            delimiter: ',',
+            list_separator: ';',
+        }
+    }
+}
+
+impl CsvConfig {
+    /// Read node ids from `column`.
+    pub fn with_id_column(mut self, column: impl Into<String>) -> Self {
+        self.id_column = column.into();
+        self
+    }
+
+    /// Read node labels from `column`.
+    pub fn with_label_column(mut self, column: impl Into<String>) -> Self {
+        self.label_column = Some(column.into());
+        self
+    }
+
+    /// Label rows that don't name their own with `label`.
+    pub fn with_default_label(mut self, label: impl Into<String>) -> Self {
+        self.default_label = label.into();
+        self
+    }
+
+    /// Treat `column` as a list of related row ids.
+    pub fn with_relation_column(mut self, column: impl Into<String>) -> Self {
+        self.relation_columns.push(column.into());
+        self
+    }
+
+    /// Split fields on `delimiter` instead of `,`.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Split relation columns on `separator` instead of `;`.
+    pub fn with_list_separator(mut self, separator: char) -> Self {
+        self.list_separator = separator;
+        self
+    }
+}
+
+/// CSV-based storage backend.
+///
+/// Loads one node per row from CSV text with a header line. Fields may be
+/// quoted with `"`, doubling quotes inside quoted fields.
+#[derive(Debug, Clone)]
+pub struct CsvStorage {
+    /// The CSV text, header included
+    text: String,
+    /// Storage metadata
+    metadata: StorageMetadata,
+    /// Column roles
+    config: CsvConfig,
+}
+
+impl CsvStorage {
+    /// Create a new CsvStorage from CSV text.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(csv: &str) -> Self {
+        let metadata = StorageMetadata::new("csv", "1.0.0")
+            .with_feature(StorageFeature::ConcurrentReads)
+            .with_property("data_type", "csv");
+
+        Self {
+            text: csv.to_string(),
+            metadata,
+            config: CsvConfig::default(),
+        }
+    }
+
+    /// Create a new CsvStorage from a CSV file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> StorageResult<Self> {
+        let text = fs::read_to_string(&path)?;
+        let mut storage = Self::from_str(&text);
+        storage.metadata = storage.metadata.with_feature(StorageFeature::Persistence);
+        if let Some(path_str) = path.as_ref().to_str() {
+            storage.metadata = storage.metadata.with_property("source_file", path_str);
+        }
+        Ok(storage)
+    }
+
+    /// Use the given column roles when loading.
+    pub fn with_config(mut self, config: CsvConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl SyncStorage for CsvStorage {
+    fn load_graph_sync(&self) -> StorageResult<Graph> {
+        build_graph_from_csv(&self.text, &self.config)
+    }
+
+    fn get_node_sync(&self, _id: &str) -> StorageResult<Option<Node>> {
+        Ok(None)
+    }
+
+    fn metadata(&self) -> StorageMetadata {
+        self.metadata.clone()
+    }
+
+    fn supports_feature(&self, feature: StorageFeature) -> bool {
+        self.metadata.features.contains(&feature)
+    }
+}
+
+/// Build a graph from CSV text, one node per row.
+///
+/// Rows whose field count differs from the header, that lack an id, or
+/// that repeat an earlier row's id, fail the load with
+/// [`StorageError::InvalidData`] naming the line.
+pub fn build_graph_from_csv(csv: &str, config: &CsvConfig) -> StorageResult<Graph> {
+    let mut records = parse_records(csv, config.delimiter)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        return Ok(Graph::new());
+    };
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| StorageError::ConfigError(format!("CSV has no column named {}", name)))
+    };
+    let id_col = column(&config.id_column)?;
+    let label_col = config.label_column.as_deref().map(column).transpose()?;
+    let relation_cols = config
+        .relation_columns
+        .iter()
+        .map(|name| column(name))
+        .collect::<StorageResult<Vec<_>>>()?;
+
+    let mut graph = Graph::new();
+    let mut relations = Vec::new();
+    for (line, fields) in records {
+        if fields.len() != header.len() {
+            return Err(StorageError::InvalidData(format!(
+                "CSV line {}: expected {} fields, got {}",
+                line,
+                header.len(),
+                fields.len()
+            )));
+        }
+        let id = fields[id_col].clone();
+        if id.is_empty() {
+            return Err(StorageError::InvalidData(format!(
+                "CSV line {}: missing {}",
+                line, config.id_column
+            )));
+        }
+        if graph.get_node_index(&id).is_some() {
+            return Err(StorageError::InvalidData(format!(
+                "CSV line {}: duplicate {} {}",
+                line, config.id_column, id
+            )));
+        }
+        let label = label_col
+            .map(|col| fields[col].clone())
+            .filter(|label| !label.is_empty())
+            .unwrap_or_else(|| config.default_label.clone());
+
+        let mut data = serde_json::Map::new();
+        for (col, (name, cell)) in header.iter().zip(&fields).enumerate() {
+            if relation_cols.contains(&col) {
+                let targets: Vec<String> = cell
+                    .split(config.list_separator)
+                    .map(str::trim)
+                    .filter(|target| !target.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                data.insert(
+                    name.clone(),
+                    Value::Array(targets.iter().cloned().map(Value::String).collect()),
+                );
+                relations.push((id.clone(), name.clone(), targets));
+            } else if col == id_col {
+                data.insert(name.clone(), Value::String(id.clone()));
+            } else if !cell.is_empty() {
+                data.insert(name.clone(), cell_value(cell));
+            }
+        }
+        graph.add_node(Node::new(id, Some(label), Value::Object(data)));
+    }
+
+    for (from_id, rel_type, targets) in relations {
+        let from = graph.id_map[&from_id];
+        for target in targets {
+            if let Some(to) = graph.get_node_index(&target) {
+                graph.add_edge(Edge::new(from, to, rel_type.clone()));
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// Convert a cell to a JSON number or boolean where it parses as one.
+///
+/// A number with a leading zero (`007`, `-01.5`) stays a string.
+fn cell_value(cell: &str) -> Value {
+    let digits = cell.strip_prefix('-').unwrap_or(cell).as_bytes();
+    if digits.len() > 1 && digits[0] == b'0' && digits[1].is_ascii_digit() {
+        return Value::String(cell.to_string());
+    }
+    if let Ok(i) = cell.parse::<i64>() {
+        return Value::from(i);
+    }
+    if let Some(n) = cell
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+    {
+        return Value::Number(n);
+    }
+    match cell {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::String(cell.to_string()),
+    }
+}
+
+/// Split CSV text into records with the 1-based line each starts on.
+///
+/// Quoted fields may contain delimiters, newlines and doubled quotes.
+/// Blank lines are skipped.
+fn parse_records(csv: &str, delimiter: char) -> StorageResult<Vec<(usize, Vec<String>)>> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = csv.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                let record = std::mem::take(&mut fields);
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push((record_line, record));
+                }
+                line += 1;
+                record_line = line;
+            }
+            c if c == delimiter => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(StorageError::InvalidData(format!(
+            "CSV line {}: unterminated quoted field",
+            record_line
+        )));
+    }
+    fields.push(field);
+    if fields.len() > 1 || !fields[0].is_empty() {
+        records.push((record_line, fields));
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_records_quoting() {
+        let records = parse_records(
+            "a,b\r\n\"x,1\",\"say \"\"hi\"\"\"\n\n\"multi\nline\",2",
+            ',',
+        )
+        .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                (1, vec!["a".to_string(), "b".to_string()]),
+                (2, vec!["x,1".to_string(), "say \"hi\"".to_string()]),
+                (4, vec!["multi\nline".to_string(), "2".to_string()]),
+            ]
+        );
+        assert!(parse_records("a\n\"open", ',').is_err());
+    }
+
+    #[test]
+    fn test_build_graph_from_csv() {
+        let csv = "id,kind,age,friends\n1,admin,30,2;3\n2,,25,1\n3,user,,9\n";
+        let config = CsvConfig::default()
+            .with_label_column("kind")
+            .with_relation_column("friends");
+        let graph = build_graph_from_csv(csv, &config).unwrap();
+
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.nodes[0].label.as_deref(), Some("admin"));
+        assert_eq!(graph.nodes[1].label.as_deref(), Some("rows"));
+        assert_eq!(
+            *graph.nodes[0].data,
+            json!({"id": "1", "kind": "admin", "age": 30, "friends": ["2", "3"]})
+        );
+        assert_eq!(graph.nodes[2].get_property("age"), None);
+        // The dangling id 9 is skipped
+        assert_eq!(graph.edges.len(), 3);
+
+        let err = build_graph_from_csv("id,age\n1,2,3\n", &CsvConfig::default()).unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+        assert!(build_graph_from_csv("name\nx\n", &CsvConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_build_graph_from_csv_duplicate_id() {
+        let err =
+            build_graph_from_csv("id,name\n1,a\n2,b\n1,c\n", &CsvConfig::default()).unwrap_err();
+        assert!(matches!(err, StorageError::InvalidData(_)));
+        assert!(err.to_string().contains("line 4"), "{}", err);
+    }
+
+    #[test]
+    fn test_cell_value_keeps_leading_zeros() {
+        assert_eq!(cell_value("02134"), json!("02134"));
+        assert_eq!(cell_value("-007"), json!("-007"));
+        assert_eq!(cell_value("00.5"), json!("00.5"));
+        assert_eq!(cell_value("0"), json!(0));
+        assert_eq!(cell_value("0.5"), json!(0.5));
+        assert_eq!(cell_value("-0.5"), json!(-0.5));
+        assert_eq!(cell_value("2134"), json!(2134));
+    }
+}
@@ -1,12 +1,409 @@
 use super::storage_trait::{
     StorageError, StorageFeature, StorageMetadata, StorageResult, SyncStorage,
 };
-use crate::graph::{Edge, Graph, Node};
+use crate::graph::{Edge, Graph, Node, NodeData};
+use crate::schema::SchemaAnalyzer;
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 use std::sync::Arc;
 
+/// Options controlling how a JSON document is turned into a graph.
+#[derive(Debug, Clone)]
+pub struct GraphConfig {
+    /// Assign a synthetic id to child objects that have no `id`/`_id` field.
+    ///
+    /// Array elements get `"{field}-{index}"` and single objects get the
+    /// field name. Synthetic ids are registered in [`Graph::id_map`] like any
+    /// other, but they are positional, so relation arrays in the source data
+    /// should not be expected to point at them. When disabled, a missing id
+    /// fails the load with [`StorageError::InvalidData`]. Defaults to `true`.
+    pub auto_id: bool,
+    /// Fields every array element must have to become a node.
+    ///
+    /// Elements missing any of these fields are skipped: they get no node,
+    /// are not registered in [`Graph::id_map`], and relations pointing at
+    /// them don't resolve. Empty by default.
+    pub require_fields: Vec<String>,
+    /// Label for child nodes without a `type`/`kind`/`label` string field.
+    ///
+    /// By default such nodes are labeled with the field they were loaded
+    /// from (e.g. `users`). Setting this gives them all one label instead,
+    /// so they can be matched uniformly with `MATCH (n:Label)`. Nodes that
+    /// carry their own label are unaffected. `None` by default.
+    pub default_label: Option<String>,
+    /// Always label child nodes with the field they were loaded from.
+    ///
+    /// Elements of `users` become `:users` even if they have a `type`,
+    /// `kind` or `label` field, matching the node types reported by
+    /// [`SchemaDetection::to_neo4j_schema`](crate::schema::SchemaDetection::to_neo4j_schema).
+    /// Takes precedence over [`GraphConfig::default_label`]. Defaults to `false`.
+    pub label_from_path: bool,
+    /// Reference child node data in the source document instead of copying it.
+    ///
+    /// Each child node then holds a [`NodeData::Ref`] into a shared copy of
+    /// the document, so the per-node clones of large datasets are avoided.
+    /// This pays off with [`build_graph_from_shared`] (used by
+    /// [`JsonStorage`]), which shares a document that is already held in
+    /// an `Arc`; [`build_graph_with_config`] has to copy the document once.
+    /// Nodes modified by `SET` switch to an owned copy. Defaults to `false`.
+    pub shared_data: bool,
+    /// Dot-separated path to each object's id, e.g. `meta.id`; write a dot
+    /// inside a key as `\.`.
+    ///
+    /// Replaces the default `id`/`_id` lookup. The resolved id is what
+    /// relation arrays must contain to point at the node. `None` by default.
+    pub id_field: Option<String>,
+    /// Dot-separated path to each child object's label, e.g. `meta.type`.
+    ///
+    /// Replaces the default `type`/`kind`/`label` lookup; the value must be
+    /// a string. `None` by default.
+    pub label_field: Option<String>,
+    /// Sort nodes by id once the graph is built, with
+    /// [`Graph::sort_nodes_by_id`].
+    ///
+    /// Results then no longer depend on the order of the source arrays.
+    /// Defaults to `false`.
+    pub sort_nodes_by_id: bool,
+    /// Fail the load when a relation field lists an id that matches no node.
+    ///
+    /// The fields checked are [`GraphConfig::relation_fields`] when set, and
+    /// otherwise the array fields [`SchemaAnalyzer`] detects as relations,
+    /// whether or not any of their entries resolve. Their `null`, empty or
+    /// unknown entries are reported in a [`StorageError::InvalidData`] as
+    /// `from -[field]-> target`, with the total count. Detection counts
+    /// tag lists too, so list the relation fields to leave those alone.
+    /// Defaults to `false`, silently skipping such entries.
+    pub strict_relations: bool,
+    /// Dot-separated path to the nodes to load, e.g. `data.users`.
+    ///
+    /// Replaces the scan of the root object's fields: only the objects at
+    /// this path (or the elements of the arrays there) become child nodes,
+    /// labeled and linked from the root by the path's last named segment.
+    /// A `*` segment stands for every element of an array or every value
+    /// of an object, so `departments.*.employees` collects the employees
+    /// of all departments into one set. A key containing a dot is written
+    /// with the dot escaped, e.g. `user\.profile.posts`. When the last
+    /// segment names an object rather than an array, the load fails with
+    /// an error listing the arrays of objects inside it. `None` by default.
+    pub node_path: Option<String>,
+    /// Fields loaded as relations.
+    ///
+    /// When empty, every array whose entries resolve to node ids becomes
+    /// relationships; otherwise only the listed fields do, and other
+    /// arrays stay plain properties. Empty by default.
+    pub relation_fields: Vec<String>,
+    /// Relationship types for relation fields, keyed by field name.
+    ///
+    /// Edges loaded from a listed field get the mapped type, e.g.
+    /// `reports_to` edges typed `MANAGED_BY`; other relation fields are
+    /// typed by their field name. The edges from the root to the nodes
+    /// keep the field name. Each type must identify one field, so that
+    /// [`graph_to_json`] can write edges back: loading fails with
+    /// [`StorageError::ConfigError`] if two fields map to one type, or a
+    /// type is the name of another relation field. Empty by default.
+    pub relation_types: HashMap<String, String>,
+}
+
+impl Default for GraphConfig {
+    fn default() -> Self {
+        Self {
+            auto_id: true,
+            require_fields: Vec::new(),
+            default_label: None,
+            label_from_path: false,
+            shared_data: false,
+            id_field: None,
+            label_field: None,
+            sort_nodes_by_id: false,
+            strict_relations: false,
+            node_path: None,
+            relation_fields: Vec::new(),
+            relation_types: HashMap::new(),
+        }
+    }
+}
+
+impl GraphConfig {
+    /// Start building a config with [`GraphConfigBuilder`].
+    pub fn builder() -> GraphConfigBuilder {
+        GraphConfigBuilder::new()
+    }
+
+    /// Set whether missing ids are auto-generated.
+    pub fn with_auto_id(mut self, auto_id: bool) -> Self {
+        self.auto_id = auto_id;
+        self
+    }
+
+    /// Skip array elements that lack any of the given fields.
+    pub fn with_required_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.require_fields = fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Label child nodes that don't name their own with `label`.
+    pub fn with_default_label(mut self, label: impl Into<String>) -> Self {
+        self.default_label = Some(label.into());
+        self
+    }
+
+    /// Label child nodes by their source field, ignoring label fields.
+    pub fn with_label_from_path(mut self, enabled: bool) -> Self {
+        self.label_from_path = enabled;
+        self
+    }
+
+    /// Reference node data in the source document instead of copying it.
+    pub fn with_shared_data(mut self, enabled: bool) -> Self {
+        self.shared_data = enabled;
+        self
+    }
+
+    /// Read ids from a dot-separated path instead of `id`/`_id`.
+    pub fn with_id_field(mut self, path: impl Into<String>) -> Self {
+        self.id_field = Some(path.into());
+        self
+    }
+
+    /// Read labels from a dot-separated path instead of `type`/`kind`/`label`.
+    pub fn with_label_field(mut self, path: impl Into<String>) -> Self {
+        self.label_field = Some(path.into());
+        self
+    }
+
+    /// Set whether nodes are sorted by id after loading.
+    pub fn with_sort_nodes_by_id(mut self, enabled: bool) -> Self {
+        self.sort_nodes_by_id = enabled;
+        self
+    }
+
+    /// Set whether unresolved relation targets fail the load.
+    pub fn with_strict_relations(mut self, enabled: bool) -> Self {
+        self.strict_relations = enabled;
+        self
+    }
+
+    /// Load nodes only from the given dot-separated path.
+    pub fn with_node_path(mut self, path: impl Into<String>) -> Self {
+        self.node_path = Some(path.into());
+        self
+    }
+
+    /// Load `field` as a relation, restricting relations to the listed fields.
+    pub fn with_relation_field(mut self, field: impl Into<String>) -> Self {
+        self.relation_fields.push(field.into());
+        self
+    }
+
+    /// Type edges loaded from the relation field `field` as `rel_type`.
+    pub fn with_relation_type(
+        mut self,
+        field: impl Into<String>,
+        rel_type: impl Into<String>,
+    ) -> Self {
+        self.relation_types.insert(field.into(), rel_type.into());
+        self
+    }
+
+    /// The relationship type for edges loaded from the relation field `field`.
+    fn relation_type<'a>(&'a self, field: &'a str) -> &'a str {
+        self.relation_types.get(field).map_or(field, String::as_str)
+    }
+
+    /// The relation field that edges of type `rel_type` were loaded from.
+    ///
+    /// Loading rejects ambiguous mappings; should several fields still map
+    /// to `rel_type`, the first by name is used.
+    fn relation_field<'a>(&'a self, rel_type: &'a str) -> &'a str {
+        self.relation_types
+            .iter()
+            .filter(|(_, mapped)| *mapped == rel_type)
+            .map(|(field, _)| field.as_str())
+            .min()
+            .unwrap_or(rel_type)
+    }
+
+    /// Whether `field` may be loaded as a relation.
+    fn is_relation_field(&self, field: &str) -> bool {
+        self.relation_fields.is_empty() || self.relation_fields.iter().any(|f| f == field)
+    }
+
+    /// Check that every relationship type maps back to one of `fields`,
+    /// the relation fields being loaded.
+    fn check_relation_types<'a>(
+        &self,
+        fields: impl IntoIterator<Item = &'a str>,
+    ) -> StorageResult<()> {
+        let mut by_type: Vec<(&str, &str)> = self
+            .relation_types
+            .iter()
+            .map(|(field, rel_type)| (rel_type.as_str(), field.as_str()))
+            .collect();
+        by_type.sort_unstable();
+        if let Some(pair) = by_type.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(StorageError::ConfigError(format!(
+                "relation fields {} and {} both map to type {}",
+                pair[0].1, pair[1].1, pair[0].0
+            )));
+        }
+
+        let mut fields: Vec<&str> = fields.into_iter().collect();
+        fields.sort_unstable();
+        for field in fields {
+            if let Some(mapped) = self.relation_types.iter().find_map(|(from, rel_type)| {
+                (rel_type == field && from != field && !self.relation_types.contains_key(field))
+                    .then_some(from)
+            }) {
+                return Err(StorageError::ConfigError(format!(
+                    "relation field {} maps to type {}, which is also the name of a relation field",
+                    mapped, field
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The id of an object, from [`GraphConfig::id_field`] or `id`/`_id`.
+    fn id_for(&self, obj: &serde_json::Map<String, Value>) -> Option<String> {
+        match &self.id_field {
+            Some(path) => value_at_path(obj, path).and_then(id_to_string),
+            None => object_id(obj),
+        }
+    }
+
+    /// The label for a child object loaded from `field_name`.
+    fn label_for(&self, obj: &serde_json::Map<String, Value>, field_name: &str) -> String {
+        if self.label_from_path {
+            return field_name.to_string();
+        }
+        let own_label = match &self.label_field {
+            Some(path) => value_at_path(obj, path),
+            None => obj
+                .get("type")
+                .or_else(|| obj.get("kind"))
+                .or_else(|| obj.get("label")),
+        };
+        own_label
+            .and_then(|v| v.as_str())
+            .or(self.default_label.as_deref())
+            .unwrap_or(field_name)
+            .to_string()
+    }
+
+    /// Whether an array element has every required field.
+    fn has_required_fields(&self, obj: &serde_json::Map<String, Value>) -> bool {
+        self.require_fields.iter().all(|f| obj.contains_key(f))
+    }
+}
+
+/// A builder for GraphConfig.
+///
+/// Provides a fluent interface for every loading option, each matching a
+/// [`GraphConfig`] field and its `with_*` method.
+#[derive(Debug, Default, Clone)]
+pub struct GraphConfigBuilder {
+    config: GraphConfig,
+}
+
+impl GraphConfigBuilder {
+    /// Create a new builder with the default config.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read ids from a dot-separated path instead of `id`/`_id`.
+    pub fn id_field(mut self, path: impl Into<String>) -> Self {
+        self.config = self.config.with_id_field(path);
+        self
+    }
+
+    /// Read labels from a dot-separated path instead of `type`/`kind`/`label`.
+    pub fn label_field(mut self, path: impl Into<String>) -> Self {
+        self.config = self.config.with_label_field(path);
+        self
+    }
+
+    /// Load `field` as a relation, restricting relations to the added fields.
+    pub fn add_relation(mut self, field: impl Into<String>) -> Self {
+        self.config = self.config.with_relation_field(field);
+        self
+    }
+
+    /// Type edges loaded from the relation field `field` as `rel_type`.
+    pub fn map_relation_type(
+        mut self,
+        field: impl Into<String>,
+        rel_type: impl Into<String>,
+    ) -> Self {
+        self.config = self.config.with_relation_type(field, rel_type);
+        self
+    }
+
+    /// Label child nodes that don't name their own with `label`.
+    pub fn default_label(mut self, label: impl Into<String>) -> Self {
+        self.config = self.config.with_default_label(label);
+        self
+    }
+
+    /// Set whether missing ids are auto-generated.
+    pub fn auto_id(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_auto_id(enabled);
+        self
+    }
+
+    /// Skip array elements that lack any of the given fields.
+    pub fn require_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config = self.config.with_required_fields(fields);
+        self
+    }
+
+    /// Label child nodes by their source field, ignoring label fields.
+    pub fn label_from_path(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_label_from_path(enabled);
+        self
+    }
+
+    /// Reference node data in the source document instead of copying it.
+    pub fn shared_data(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_shared_data(enabled);
+        self
+    }
+
+    /// Set whether nodes are sorted by id after loading.
+    pub fn sort_nodes_by_id(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_sort_nodes_by_id(enabled);
+        self
+    }
+
+    /// Set whether unresolved relation targets fail the load.
+    pub fn strict_relations(mut self, enabled: bool) -> Self {
+        self.config = self.config.with_strict_relations(enabled);
+        self
+    }
+
+    /// Load nodes only from the given dot-separated path.
+    pub fn node_path(mut self, path: impl Into<String>) -> Self {
+        self.config = self.config.with_node_path(path);
+        self
+    }
+
+    /// Build the GraphConfig.
+    pub fn build(self) -> GraphConfig {
+        self.config
+    }
+}
+
 /// JSON-based storage backend.
 ///
 /// Loads graph data from a JSON file or value.
@@ -16,6 +413,8 @@ pub struct JsonStorage {
     data: Arc<Value>,
     /// Storage metadata
     metadata: StorageMetadata,
+    /// Graph building options
+    config: GraphConfig,
 }
 
 impl JsonStorage {
@@ -28,6 +427,7 @@ impl JsonStorage {
         Self {
             data: Arc::new(data),
             metadata,
+            config: GraphConfig::default(),
         }
     }
 
@@ -47,6 +447,7 @@ impl JsonStorage {
         Ok(Self {
             data: Arc::new(data),
             metadata,
+            config: GraphConfig::default(),
         })
     }
 
@@ -57,6 +458,21 @@ impl JsonStorage {
         Ok(Self::from_value(data))
     }
 
+    /// Create a new JsonStorage by reading JSON from a reader.
+    ///
+    /// The payload is deserialized directly from the reader without first
+    /// buffering it into a `String`, which suits HTTP bodies and pipes.
+    pub fn from_reader<R: Read>(reader: R) -> StorageResult<Self> {
+        let data: Value = serde_json::from_reader(reader)?;
+        Ok(Self::from_value(data))
+    }
+
+    /// Use the given graph building options when loading.
+    pub fn with_config(mut self, config: GraphConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Get a reference to the underlying JSON data.
     pub fn data(&self) -> &Value {
         &self.data
@@ -65,7 +481,7 @@ impl JsonStorage {
 
 impl SyncStorage for JsonStorage {
     fn load_graph_sync(&self) -> StorageResult<Graph> {
-        build_graph_from_root_object(&self.data, "Root")
+        build_graph_from_shared(&self.data, "Root", &self.config)
     }
 
     fn get_node_sync(&self, _id: &str) -> StorageResult<Option<crate::graph::Node>> {
@@ -89,6 +505,51 @@ impl SyncStorage for JsonStorage {
 /// and objects) are removed from the root node's data since they are
 /// accessed via relationships.
 pub fn build_graph_from_root_object(json: &Value, root_label: &str) -> StorageResult<Graph> {
+    build_graph_with_config(json, root_label, &GraphConfig::default())
+}
+
+/// Build a graph from a root object JSON value using explicit options.
+///
+/// See [`build_graph_from_root_object`] for the mapping rules and
+/// [`GraphConfig`] for the available options.
+pub fn build_graph_with_config(
+    json: &Value,
+    root_label: &str,
+    config: &GraphConfig,
+) -> StorageResult<Graph> {
+    if config.shared_data {
+        let shared = Arc::new(json.clone());
+        build_graph(&shared, Some(&shared), root_label, config)
+    } else {
+        build_graph(json, None, root_label, config)
+    }
+}
+
+/// Build a graph from a JSON document that is already shared.
+///
+/// Like [`build_graph_with_config`], but with
+/// [`GraphConfig::shared_data`] the nodes reference `json` directly
+/// without copying it.
+pub fn build_graph_from_shared(
+    json: &Arc<Value>,
+    root_label: &str,
+    config: &GraphConfig,
+) -> StorageResult<Graph> {
+    let shared = config.shared_data.then_some(json);
+    build_graph(json, shared, root_label, config)
+}
+
+/// Build the graph; child node data references `shared` when given.
+fn build_graph(
+    json: &Value,
+    shared: Option<&Arc<Value>>,
+    root_label: &str,
+    config: &GraphConfig,
+) -> StorageResult<Graph> {
+    let node_data = |value: &Value, path: &[&str]| match shared {
+        Some(root) => NodeData::Ref(Arc::clone(root), json_pointer(path)),
+        None => NodeData::Owned(value.clone()),
+    };
     let mut graph = Graph::new();
 
     let root_obj = json
@@ -96,12 +557,9 @@ pub fn build_graph_from_root_object(json: &Value, root_label: &str) -> StorageRe
         .ok_or_else(|| StorageError::InvalidData("Root is not an object".to_string()))?;
 
     // Extract root node ID
-    let root_id = root_obj
-        .get("id")
-        .or_else(|| root_obj.get("_id"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("root")
-        .to_string();
+    let root_id = config
+        .id_for(root_obj)
+        .unwrap_or_else(|| "root".to_string());
 
     // Build root node data with scalar fields only
     let mut root_data = serde_json::Map::new();
@@ -118,159 +576,572 @@ pub fn build_graph_from_root_object(json: &Value, root_label: &str) -> StorageRe
     );
     let root_idx = graph.add_node(root_node);
 
-    // Process each field: arrays of objects and object values become child nodes
-    for (field_name, field_value) in root_obj {
-        match field_value {
-            Value::Array(arr) => {
-                if arr.is_empty() {
-                    continue;
+    if let Some(node_path) = &config.node_path {
+        let segments = path_segments(node_path);
+        let rel_type = segments
+            .iter()
+            .rfind(|segment| *segment != "*")
+            .map_or("nodes", String::as_str);
+        let names_leaf = segments.last().is_some_and(|segment| segment != "*");
+        let mut elements = Vec::new();
+        for (path, value) in navigate_json_path(json, node_path) {
+            if names_leaf && let Value::Object(obj) = value {
+                return Err(wrapper_object_error(node_path, &path, obj));
+            }
+            match value {
+                Value::Array(arr) => {
+                    elements.extend(arr.iter().enumerate().map(|(idx, element)| {
+                        let mut element_path = path.clone();
+                        element_path.push(idx.to_string());
+                        (element_path, element)
+                    }))
                 }
-                if let Some(first) = arr.first() {
-                    if !first.is_object() {
+                _ => elements.push((path, value)),
+            }
+        }
+
+        for (n, (path, element)) in elements.into_iter().enumerate() {
+            let Value::Object(obj) = element else {
+                continue;
+            };
+            if !config.has_required_fields(obj) {
+                continue;
+            }
+            let eid = child_id(obj, config, || format!("{}-{}", rel_type, n))?;
+            let elabel = config.label_for(obj, rel_type);
+            let path: Vec<&str> = path.iter().map(String::as_str).collect();
+            let ri = graph.add_node(Node::new(eid, Some(elabel), node_data(element, &path)));
+            graph.add_edge(Edge::new(root_idx, ri, rel_type));
+        }
+    } else {
+        // Process each field: arrays of objects and object values become child nodes
+        for (field_name, field_value) in root_obj {
+            match field_value {
+                Value::Array(arr) => {
+                    if arr.is_empty() {
                         continue;
                     }
+                    if let Some(first) = arr.first() {
+                        if !first.is_object() {
+                            continue;
+                        }
+
+                        for (idx, element) in arr.iter().enumerate() {
+                            if let Value::Object(obj) = element {
+                                if !config.has_required_fields(obj) {
+                                    continue;
+                                }
+                                let eid =
+                                    child_id(obj, config, || format!("{}-{}", field_name, idx))?;
+
+                                let elabel = config.label_for(obj, field_name);
 
-                    for (idx, element) in arr.iter().enumerate() {
-                        if let Value::Object(obj) = element {
-                            let eid = obj
-                                .get("id")
-                                .or_else(|| obj.get("_id"))
-                                .and_then(|v| v.as_str())
-                                .map(String::from)
-                                .unwrap_or_else(|| format!("{}-{}", field_name, idx));
-
-                            let elabel = obj
-                                .get("type")
-                                .or_else(|| obj.get("kind"))
-                                .or_else(|| obj.get("label"))
-                                .and_then(|v| v.as_str())
-                                .map(String::from)
-                                .unwrap_or_else(|| field_name.clone());
-
-                            let ri = graph.add_node(Node::new(eid, Some(elabel), element.clone()));
-                            graph.add_edge(Edge::new(root_idx, ri, field_name.clone()));
+                                let data = node_data(element, &[field_name, &idx.to_string()]);
+                                let ri = graph.add_node(Node::new(eid, Some(elabel), data));
+                                graph.add_edge(Edge::new(root_idx, ri, field_name.clone()));
+                            }
                         }
                     }
                 }
-            }
-            Value::Object(_) => {
-                let obj = field_value.as_object().unwrap();
-                let is_pure_wrapper = obj.values().all(|v| v.is_array() || v.is_object());
-
-                if is_pure_wrapper {
-                    // Unwrap pure wrappers — process their children at root level
-                    for (inner_key, inner_value) in obj {
-                        if let Some(inner_arr) = inner_value.as_array() {
-                            if inner_arr.is_empty() {
-                                continue;
-                            }
-                            if let Some(first) = inner_arr.first() {
-                                if !first.is_object() {
+                Value::Object(_) => {
+                    let obj = field_value.as_object().unwrap();
+                    let is_pure_wrapper = obj.values().all(|v| v.is_array() || v.is_object());
+
+                    if is_pure_wrapper {
+                        // Unwrap pure wrappers — process their children at root level
+                        for (inner_key, inner_value) in obj {
+                            if let Some(inner_arr) = inner_value.as_array() {
+                                if inner_arr.is_empty() {
                                     continue;
                                 }
-                                for (idx, element) in inner_arr.iter().enumerate() {
-                                    if let Value::Object(elem_obj) = element {
-                                        let eid = elem_obj
-                                            .get("id")
-                                            .or_else(|| elem_obj.get("_id"))
-                                            .and_then(|v| v.as_str())
-                                            .map(String::from)
-                                            .unwrap_or_else(|| format!("{}-{}", inner_key, idx));
-                                        let elabel = elem_obj
-                                            .get("type")
-                                            .or_else(|| elem_obj.get("kind"))
-                                            .or_else(|| elem_obj.get("label"))
-                                            .and_then(|v| v.as_str())
-                                            .map(String::from)
-                                            .unwrap_or_else(|| inner_key.clone());
-                                        let ri = graph.add_node(Node::new(
-                                            eid,
-                                            Some(elabel),
-                                            element.clone(),
-                                        ));
-                                        graph.add_edge(Edge::new(root_idx, ri, inner_key.clone()));
+                                if let Some(first) = inner_arr.first() {
+                                    if !first.is_object() {
+                                        continue;
+                                    }
+                                    for (idx, element) in inner_arr.iter().enumerate() {
+                                        if let Value::Object(elem_obj) = element {
+                                            if !config.has_required_fields(elem_obj) {
+                                                continue;
+                                            }
+                                            let eid = child_id(elem_obj, config, || {
+                                                format!("{}-{}", inner_key, idx)
+                                            })?;
+                                            let elabel = config.label_for(elem_obj, inner_key);
+                                            let data = node_data(
+                                                element,
+                                                &[field_name, inner_key, &idx.to_string()],
+                                            );
+                                            let ri =
+                                                graph.add_node(Node::new(eid, Some(elabel), data));
+                                            graph.add_edge(Edge::new(
+                                                root_idx,
+                                                ri,
+                                                inner_key.clone(),
+                                            ));
+                                        }
                                     }
                                 }
-                            }
-                        } else if let Some(inner_obj) = inner_value.as_object() {
-                            let inner_pure =
-                                inner_obj.values().all(|v| v.is_array() || v.is_object());
-                            if !inner_pure {
-                                // Leaf object → child node
-                                let eid = inner_obj
-                                    .get("id")
-                                    .or_else(|| inner_obj.get("_id"))
-                                    .and_then(|v| v.as_str())
-                                    .map(String::from)
-                                    .unwrap_or_else(|| inner_key.clone());
-                                let elabel = inner_obj
-                                    .get("type")
-                                    .or_else(|| inner_obj.get("kind"))
-                                    .or_else(|| inner_obj.get("label"))
-                                    .and_then(|v| v.as_str())
-                                    .map(String::from)
-                                    .unwrap_or_else(|| inner_key.clone());
-                                let ri = graph.add_node(Node::new(
-                                    eid,
-                                    Some(elabel),
-                                    inner_value.clone(),
-                                ));
-                                graph.add_edge(Edge::new(root_idx, ri, inner_key.clone()));
+                            } else if let Some(inner_obj) = inner_value.as_object() {
+                                let inner_pure =
+                                    inner_obj.values().all(|v| v.is_array() || v.is_object());
+                                if !inner_pure {
+                                    // Leaf object → child node
+                                    let eid = child_id(inner_obj, config, || inner_key.clone())?;
+                                    let elabel = config.label_for(inner_obj, inner_key);
+                                    let data = node_data(inner_value, &[field_name, inner_key]);
+                                    let ri = graph.add_node(Node::new(eid, Some(elabel), data));
+                                    graph.add_edge(Edge::new(root_idx, ri, inner_key.clone()));
+                                }
                             }
                         }
+                    } else {
+                        // Leaf object → single child node
+                        let eid = child_id(obj, config, || field_name.clone())?;
+
+                        let elabel = config.label_for(obj, field_name);
+
+                        let data = node_data(field_value, &[field_name]);
+                        let ri = graph.add_node(Node::new(eid, Some(elabel), data));
+                        graph.add_edge(Edge::new(root_idx, ri, field_name.clone()));
                     }
-                } else {
-                    // Leaf object → single child node
-                    let eid = obj
-                        .get("id")
-                        .or_else(|| obj.get("_id"))
-                        .and_then(|v| v.as_str())
-                        .map(String::from)
-                        .unwrap_or_else(|| field_name.clone());
-
-                    let elabel = obj
-                        .get("type")
-                        .or_else(|| obj.get("kind"))
-                        .or_else(|| obj.get("label"))
-                        .and_then(|v| v.as_str())
-                        .map(String::from)
-                        .unwrap_or_else(|| field_name.clone());
-
-                    let ri = graph.add_node(Node::new(eid, Some(elabel), field_value.clone()));
-                    graph.add_edge(Edge::new(root_idx, ri, field_name.clone()));
                 }
+                _ => {}
             }
-            _ => {}
         }
     }
 
     // Second pass: add inter-child edges from relation fields
     let mut inter_edges: Vec<(usize, usize, String)> = Vec::new();
+    // Entries that look like ids but match no node, as (from, field, target)
+    let mut unresolved: Vec<(usize, &String, &Value)> = Vec::new();
     for (child_idx, child_node) in graph.nodes.iter().enumerate() {
         if child_idx == root_idx {
             continue;
         }
-        if let Value::Object(data) = &child_node.data {
+        if let Value::Object(data) = &*child_node.data {
             for (field_name, field_value) in data {
+                if !config.is_relation_field(field_name) {
+                    continue;
+                }
                 if let Some(id_array) = field_value.as_array() {
                     for id_val in id_array {
-                        if let Some(to_id) = id_val.as_str()
-                            && let Some(to_idx) = graph.get_node_index(to_id)
-                        {
-                            inter_edges.push((child_idx, to_idx, field_name.clone()));
+                        match id_to_string(id_val).and_then(|id| graph.get_node_index(&id)) {
+                            Some(to_idx) => {
+                                inter_edges.push((child_idx, to_idx, field_name.clone()))
+                            }
+                            None if id_val.is_null()
+                                || id_val.is_string()
+                                || id_val.is_number() =>
+                            {
+                                unresolved.push((child_idx, field_name, id_val))
+                            }
+                            None => {}
                         }
                     }
                 }
             }
         }
     }
-    for (from, to, rel_type) in inter_edges {
-        graph.add_edge(Edge::new(from, to, rel_type));
+    // Which fields are relations doesn't depend on whether their entries
+    // resolve, so that a field whose targets are all missing still counts
+    let relation_fields = if config.strict_relations || !config.relation_types.is_empty() {
+        relation_field_names(&graph, root_idx, config, &inter_edges)
+    } else {
+        HashSet::new()
+    };
+    if config.strict_relations {
+        let broken: Vec<String> = unresolved
+            .iter()
+            .filter(|(_, field, _)| relation_fields.contains(field.as_str()))
+            .map(|(from, field, target)| {
+                format!("{} -[{}]-> {}", graph.nodes[*from].id, field, target)
+            })
+            .collect();
+        if !broken.is_empty() {
+            return Err(StorageError::InvalidData(format!(
+                "{} unresolved relation target(s): {}",
+                broken.len(),
+                broken.join(", ")
+            )));
+        }
+    }
+    config.check_relation_types(relation_fields.iter().map(String::as_str))?;
+    for (from, to, field) in inter_edges {
+        graph.add_edge(Edge::new(from, to, config.relation_type(&field)));
     }
 
+    if config.sort_nodes_by_id {
+        graph.sort_nodes_by_id();
+    }
     Ok(graph)
 }
 
+/// The fields loaded as relations: [`GraphConfig::relation_fields`] when
+/// set, and otherwise the array fields [`SchemaAnalyzer`] recommends as
+/// relations for the child nodes, plus any field with a resolved entry.
+fn relation_field_names(
+    graph: &Graph,
+    root_idx: usize,
+    config: &GraphConfig,
+    inter_edges: &[(usize, usize, String)],
+) -> HashSet<String> {
+    if !config.relation_fields.is_empty() {
+        return config.relation_fields.iter().cloned().collect();
+    }
+
+    // Analyze the child nodes grouped by label, as arrays of a document
+    let mut by_label = serde_json::Map::new();
+    for (idx, node) in graph.nodes.iter().enumerate() {
+        if idx == root_idx || !node.data.is_object() {
+            continue;
+        }
+        let label = node.label.as_deref().unwrap_or_default().to_string();
+        if let Value::Array(nodes) = by_label
+            .entry(label)
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            nodes.push((*node.data).clone());
+        }
+    }
+    let mut fields: HashSet<String> = SchemaAnalyzer::analyze(&Value::Object(by_label))
+        .map(|detection| detection.array_schemas)
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|schema| schema.recommended_relation_fields)
+        .collect();
+    fields.extend(inter_edges.iter().map(|(_, _, field)| field.clone()));
+    fields
+}
+
+/// Rebuild a JSON document from a graph, the inverse of
+/// [`build_graph_with_config`].
+///
+/// The root node is the first node without incoming edges. Its properties
+/// become the document's top-level fields, and the other nodes are listed
+/// as arrays of their data:
+///
+/// - with [`GraphConfig::node_path`], all of them in one array at that
+///   path, nested in objects for each named segment (`*` segments can't be
+///   reconstructed and are left out);
+/// - otherwise grouped by the type of the root's edge to them, or by label
+///   for nodes the root doesn't link to, such as ones created by `CREATE`.
+///
+/// Relation fields are kept as loaded while they still match the node's
+/// outgoing edges; otherwise they are rewritten as the ids of the edge
+/// targets, so relationships created or deleted by queries are persisted.
+/// Single-object fields and wrapper objects come back as arrays at the top
+/// level.
+pub fn graph_to_json(graph: &Graph, config: &GraphConfig) -> Value {
+    let mut has_incoming = vec![false; graph.nodes.len()];
+    for edge in &graph.edges {
+        has_incoming[edge.to] = true;
+    }
+    let Some(root_idx) = has_incoming.iter().position(|incoming| !incoming) else {
+        return Value::Object(serde_json::Map::new());
+    };
+    let mut document = match &*graph.nodes[root_idx].data {
+        Value::Object(obj) => obj.clone(),
+        _ => serde_json::Map::new(),
+    };
+
+    let mut outgoing: Vec<Vec<&Edge>> = vec![Vec::new(); graph.nodes.len()];
+    for edge in &graph.edges {
+        outgoing[edge.from].push(edge);
+    }
+    // The type of the root's first edge to each node
+    let mut root_edge_types: HashMap<usize, &str> = HashMap::new();
+    for edge in &outgoing[root_idx] {
+        root_edge_types.entry(edge.to).or_insert(&edge.rel_type);
+    }
+
+    // Group the child nodes, keeping first-seen order
+    let mut groups: Vec<(String, Vec<Value>)> = Vec::new();
+    for (idx, node) in graph.nodes.iter().enumerate() {
+        if idx == root_idx {
+            continue;
+        }
+        let key = match &config.node_path {
+            Some(_) => String::new(),
+            None => root_edge_types
+                .get(&idx)
+                .copied()
+                .or(node.label.as_deref())
+                .unwrap_or("nodes")
+                .to_string(),
+        };
+        let data = node_json(graph, idx, &outgoing[idx], config);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, values)) => values.push(data),
+            None => groups.push((key, vec![data])),
+        }
+    }
+
+    match &config.node_path {
+        Some(node_path) => {
+            let nodes = groups.pop().map(|(_, values)| values).unwrap_or_default();
+            let segments: Vec<String> = path_segments(node_path)
+                .into_iter()
+                .filter(|s| s != "*")
+                .collect();
+            if let Some((last, parents)) = segments.split_last() {
+                let mut target = &mut document;
+                for segment in parents {
+                    let entry = target
+                        .entry(segment.clone())
+                        .or_insert_with(|| Value::Object(serde_json::Map::new()));
+                    if !entry.is_object() {
+                        *entry = Value::Object(serde_json::Map::new());
+                    }
+                    target = entry.as_object_mut().unwrap();
+                }
+                target.insert(last.clone(), Value::Array(nodes));
+            }
+        }
+        None => {
+            for (key, values) in groups {
+                document.insert(key, Value::Array(values));
+            }
+        }
+    }
+    Value::Object(document)
+}
+
+/// A child node's data with its relation fields brought in line with its
+/// outgoing edges, given as `outgoing`.
+fn node_json(graph: &Graph, idx: usize, outgoing: &[&Edge], config: &GraphConfig) -> Value {
+    let Value::Object(mut data) = (*graph.nodes[idx].data).clone() else {
+        return (*graph.nodes[idx].data).clone();
+    };
+
+    let mut relations: Vec<(String, Vec<usize>)> = Vec::new();
+    for edge in outgoing {
+        let field = config.relation_field(&edge.rel_type);
+        match relations.iter_mut().find(|(rel, _)| rel == field) {
+            Some((_, targets)) => targets.push(edge.to),
+            None => relations.push((field.to_string(), vec![edge.to])),
+        }
+    }
+    // Relation fields whose edges have all been deleted
+    for (field, value) in &data {
+        if config.is_relation_field(field)
+            && !relations.iter().any(|(rel, _)| rel == field)
+            && !resolved_ids(graph, value).is_empty()
+        {
+            relations.push((field.clone(), Vec::new()));
+        }
+    }
+
+    for (rel, targets) in relations {
+        let mut loaded = data
+            .get(&rel)
+            .map(|value| resolved_ids(graph, value))
+            .unwrap_or_default();
+        let mut current = targets.clone();
+        loaded.sort_unstable();
+        current.sort_unstable();
+        if loaded != current {
+            let ids = targets
+                .into_iter()
+                .map(|to| id_value(&graph.nodes[to], config))
+                .collect();
+            data.insert(rel, Value::Array(ids));
+        }
+    }
+    Value::Object(data)
+}
+
+/// The nodes that the entries of a relation array resolve to.
+fn resolved_ids(graph: &Graph, value: &Value) -> Vec<usize> {
+    value
+        .as_array()
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|id| id_to_string(id).and_then(|id| graph.get_node_index(&id)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A node's id as written in its own data, so numeric ids stay numbers.
+fn id_value(node: &Node, config: &GraphConfig) -> Value {
+    if let Value::Object(obj) = &*node.data {
+        let own = match &config.id_field {
+            Some(path) => value_at_path(obj, path),
+            None => obj.get("id").or_else(|| obj.get("_id")),
+        };
+        if let Some(value) = own.filter(|value| id_to_string(value).as_deref() == Some(&node.id)) {
+            return value.clone();
+        }
+    }
+    Value::String(node.id.clone())
+}
+
+/// RFC 6901 JSON pointer for a path of object keys and array indices.
+fn json_pointer(path: &[&str]) -> String {
+    path.iter()
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// Canonical string form of a JSON id value.
+///
+/// Strings are used as-is and numbers are stringified, so that `1` and `"1"`
+/// resolve to the same entry in [`Graph::id_map`]. Whole floats stringify
+/// like integers (`7.0` is `"7"`). Strings are never parsed, so `"007"`
+/// stays distinct from `7`. Used for both node ids and relation targets so
+/// the two always agree.
+pub fn id_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < MAX_EXACT_FLOAT_INT => {
+                Some((f as i64).to_string())
+            }
+            _ => Some(n.to_string()),
+        },
+        _ => None,
+    }
+}
+
+/// Floats below this magnitude hold whole numbers exactly (2^53).
+const MAX_EXACT_FLOAT_INT: f64 = 9_007_199_254_740_992.0;
+
+/// Extract the id of a JSON object from its `id` or `_id` field.
+pub(crate) fn object_id(obj: &serde_json::Map<String, Value>) -> Option<String> {
+    obj.get("id")
+        .or_else(|| obj.get("_id"))
+        .and_then(id_to_string)
+}
+
+/// Split a dot-separated path into its keys.
+///
+/// `\.` stands for a dot inside a key and `\\` for a backslash, so
+/// `user\.profile.name` is the key `user.profile` followed by `name`. Any
+/// other backslash is kept as is.
+pub(crate) fn path_segments(path: &str) -> Vec<String> {
+    let mut segments = vec![String::new()];
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        let segment = segments.last_mut().unwrap();
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped @ ('.' | '\\')) => segment.push(escaped),
+                Some(other) => {
+                    segment.push('\\');
+                    segment.push(other);
+                }
+                None => segment.push('\\'),
+            },
+            '.' => segments.push(String::new()),
+            c => segment.push(c),
+        }
+    }
+    segments
+}
+
+/// Join keys into a dot-separated path, escaping dots and backslashes
+/// inside them; the inverse of [`path_segments`].
+fn join_path_segments<S: AsRef<str>>(segments: &[S]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.as_ref().replace('\\', "\\\\").replace('.', "\\."))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Look up a dot-separated path of keys in an object, e.g. `meta.id`.
+pub(crate) fn value_at_path<'a>(
+    obj: &'a serde_json::Map<String, Value>,
+    path: &str,
+) -> Option<&'a Value> {
+    let segments = path_segments(path);
+    let (first, rest) = segments.split_first()?;
+    rest.iter()
+        .try_fold(obj.get(first)?, |value, segment| value.get(segment))
+}
+
+/// Every value at a dot-separated path, with the concrete keys and indices
+/// leading to it.
+///
+/// A `*` segment expands to every element of an array or every value of an
+/// object; other segments look up an object key. Paths that lead nowhere
+/// contribute nothing.
+fn navigate_json_path<'a>(json: &'a Value, path: &str) -> Vec<(Vec<String>, &'a Value)> {
+    let mut current = vec![(Vec::new(), json)];
+    for segment in path_segments(path) {
+        let mut next = Vec::new();
+        for (prefix, value) in current {
+            let step = |key: String, child: &'a Value| {
+                let mut child_path = prefix.clone();
+                child_path.push(key);
+                (child_path, child)
+            };
+            match (segment.as_str(), value) {
+                ("*", Value::Array(arr)) => next.extend(
+                    arr.iter()
+                        .enumerate()
+                        .map(|(idx, child)| step(idx.to_string(), child)),
+                ),
+                ("*", Value::Object(obj)) => {
+                    next.extend(obj.iter().map(|(key, child)| step(key.clone(), child)))
+                }
+                (key, Value::Object(obj)) => {
+                    if let Some(child) = obj.get(key) {
+                        next.push(step(key.to_string(), child));
+                    }
+                }
+                _ => {}
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+/// The error for a `node_path` that leads to a wrapper object instead of an
+/// array, naming the arrays of objects inside it as candidate paths.
+fn wrapper_object_error(
+    node_path: &str,
+    path: &[String],
+    obj: &serde_json::Map<String, Value>,
+) -> StorageError {
+    let mut candidates: Vec<String> = SchemaAnalyzer::analyze(&Value::Object(obj.clone()))
+        .map(|detection| detection.array_schemas)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|schema| obj.get(&schema.path).is_some_and(Value::is_array))
+        .map(|schema| format!("{}.{}", node_path, join_path_segments(&[&schema.path])))
+        .collect();
+    candidates.sort();
+    let hint = if candidates.is_empty() {
+        "it contains no arrays of objects".to_string()
+    } else {
+        format!("did you mean {}?", candidates.join(" or "))
+    };
+    StorageError::InvalidData(format!(
+        "node_path '{}' leads to an object at '{}', not an array; {}",
+        node_path,
+        join_path_segments(path),
+        hint
+    ))
+}
+
+/// Resolve the id of a child object, generating one when allowed.
+fn child_id(
+    obj: &serde_json::Map<String, Value>,
+    config: &GraphConfig,
+    synthetic: impl FnOnce() -> String,
+) -> StorageResult<String> {
+    match config.id_for(obj) {
+        Some(id) => Ok(id),
+        None if config.auto_id => Ok(synthetic()),
+        None => Err(StorageError::InvalidData(format!(
+            "Node '{}' has no id field and auto_id is disabled",
+            synthetic()
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,6 +1161,549 @@ mod tests {
         assert_eq!(graph.nodes.len(), 3); // Root + 2 users
     }
 
+    #[test]
+    fn test_json_storage_from_reader() {
+        let json_bytes = br#"{"users": [{ "id": "1" }, { "id": "2" }]}"#;
+        let storage = JsonStorage::from_reader(&json_bytes[..]).unwrap();
+        let graph = storage.load_graph_sync().unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+
+        let result = JsonStorage::from_reader(&b"{ not json"[..]);
+        assert!(matches!(result, Err(StorageError::JsonParse(_))));
+    }
+
+    #[test]
+    fn test_node_path_wildcard() {
+        let data = json!({
+            "name": "Acme",
+            "departments": [
+                { "name": "R&D", "employees": [
+                    { "id": "e1", "manager": ["e3"] },
+                    { "id": "e2" }
+                ] },
+                { "name": "Sales", "employees": [{ "id": "e3" }] },
+                { "name": "Empty" }
+            ]
+        });
+
+        let config = GraphConfig::default().with_node_path("departments.*.employees");
+        let graph = build_graph_with_config(&data, "Company", &config).unwrap();
+        let ids: Vec<&str> = graph
+            .iter_nodes_with_label("employees")
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["e1", "e2", "e3"]);
+        // Only the path's nodes are loaded, and relations between them resolve
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.iter_edges_of_type("employees").count(), 3);
+        assert_eq!(graph.iter_edges_of_type("manager").count(), 1);
+
+        // Shared data points at each element's own position
+        let shared =
+            build_graph_with_config(&data, "Company", &config.with_shared_data(true)).unwrap();
+        assert_eq!(
+            shared.get_node("e3").unwrap().get_property("id"),
+            Some(&json!("e3"))
+        );
+
+        let objects = GraphConfig::default().with_node_path("departments.*");
+        let graph = build_graph_with_config(&data, "Company", &objects).unwrap();
+        assert_eq!(graph.iter_nodes_with_label("departments").count(), 3);
+        assert_eq!(
+            graph
+                .get_node("departments-2")
+                .unwrap()
+                .get_property("name"),
+            Some(&json!("Empty"))
+        );
+    }
+
+    #[test]
+    fn test_node_path_object_suggests_arrays() {
+        let data = json!({
+            "response": {
+                "data": {
+                    "page": { "number": 1 },
+                    "items": [{ "id": "a" }],
+                    "archived": [{ "id": "b" }],
+                    "tags": ["x"]
+                }
+            }
+        });
+        let config = GraphConfig::default().with_node_path("response.data");
+        let err = build_graph_with_config(&data, "Root", &config).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid data structure: node_path 'response.data' leads to an object at \
+             'response.data', not an array; did you mean response.data.archived or \
+             response.data.items?"
+        );
+
+        let config = GraphConfig::default().with_node_path("response.data.page");
+        let err = build_graph_with_config(&data, "Root", &config).unwrap_err();
+        assert!(
+            err.to_string()
+                .ends_with("it contains no arrays of objects")
+        );
+
+        let config = GraphConfig::default().with_node_path("response.data.items");
+        assert!(build_graph_with_config(&data, "Root", &config).is_ok());
+    }
+
+    #[test]
+    fn test_escaped_dots_in_paths() {
+        let data = json!({
+            "user.profile": {
+                "posts": [
+                    { "meta": { "post.id": "p1" }, "title": "Hello" },
+                    { "meta": { "post.id": "p2" }, "title": "Again" }
+                ]
+            },
+            "user": { "profile": { "posts": [] } }
+        });
+        let config = GraphConfig::default()
+            .with_node_path(r"user\.profile.posts")
+            .with_id_field(r"meta.post\.id");
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+        assert_eq!(graph.iter_nodes_with_label("posts").count(), 2);
+        assert!(graph.get_node("p1").is_some());
+
+        // Exported under the dotted key, not a nested path
+        let exported = graph_to_json(&graph, &config);
+        assert_eq!(exported["user.profile"]["posts"][1]["title"], "Again");
+
+        assert_eq!(
+            path_segments(r"a\.b.c\\.d\x"),
+            vec!["a.b".to_string(), "c\\".to_string(), "d\\x".to_string()]
+        );
+        assert_eq!(join_path_segments(&["a.b", "c\\"]), r"a\.b.c\\");
+    }
+
+    #[test]
+    fn test_graph_to_json_node_path() {
+        let data = json!({
+            "version": 2,
+            "response": {
+                "data": {
+                    "items": [
+                        { "id": "a", "next": ["b"] },
+                        { "id": "b" }
+                    ]
+                }
+            }
+        });
+        let config = GraphConfig::default().with_node_path("response.data.items");
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+        assert_eq!(graph_to_json(&graph, &config), data);
+    }
+
+    #[test]
+    fn test_strict_relations() {
+        let data = json!({
+            "users": [
+                { "id": "1", "friends": ["2", "9"], "tags": ["a", "b"] },
+                { "id": "2", "friends": [null, ""] }
+            ]
+        });
+
+        let lenient = build_graph_with_config(&data, "Root", &GraphConfig::default()).unwrap();
+        assert_eq!(lenient.iter_edges_of_type("friends").count(), 1);
+
+        let strict = GraphConfig::default()
+            .with_strict_relations(true)
+            .with_relation_field("friends");
+        let err = build_graph_with_config(&data, "Root", &strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid data structure: 3 unresolved relation target(s): \
+             1 -[friends]-> \"9\", 2 -[friends]-> null, 2 -[friends]-> \"\""
+        );
+
+        // Listed relation fields leave tag lists alone
+        let tags_only = json!({"users": [{ "id": "1", "tags": ["a"] }]});
+        assert!(build_graph_with_config(&tags_only, "Root", &strict).is_ok());
+
+        // Without listed fields, detected relations are checked even when
+        // none of their entries resolve
+        let strict = GraphConfig::default().with_strict_relations(true);
+        let dangling = json!({"users": [{ "id": "1", "friends": ["8", "9"] }]});
+        let err = build_graph_with_config(&dangling, "Root", &strict).unwrap_err();
+        assert!(err.to_string().contains("2 unresolved"), "{}", err);
+        let err = build_graph_with_config(&data, "Root", &strict).unwrap_err();
+        assert!(err.to_string().contains("1 -[tags]-> \"a\""), "{}", err);
+    }
+
+    #[test]
+    fn test_relation_types() {
+        let data = json!({
+            "people": [
+                { "id": "1", "reports_to": ["2"], "friends": ["2"] },
+                { "id": "2" }
+            ]
+        });
+        let config = GraphConfig::default()
+            .with_id_field("id")
+            .with_relation_type("reports_to", "MANAGED_BY");
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+
+        assert_eq!(graph.iter_edges_of_type("MANAGED_BY").count(), 1);
+        assert_eq!(graph.iter_edges_of_type("reports_to").count(), 0);
+        assert_eq!(graph.iter_edges_of_type("friends").count(), 1);
+        // Root edges keep the field name
+        assert_eq!(graph.iter_edges_of_type("people").count(), 2);
+
+        // Mapped edges are written back under their field
+        assert_eq!(graph_to_json(&graph, &config), data);
+    }
+
+    #[test]
+    fn test_ambiguous_relation_types() {
+        let data = json!({
+            "people": [
+                { "id": "1", "a": ["2"], "b": ["2"] },
+                { "id": "2" }
+            ]
+        });
+
+        // Two fields mapped to one type
+        let config = GraphConfig::default()
+            .with_relation_type("b", "KNOWS")
+            .with_relation_type("a", "KNOWS");
+        let err = build_graph_with_config(&data, "Root", &config).unwrap_err();
+        assert!(matches!(err, StorageError::ConfigError(_)));
+        assert!(err.to_string().contains("a and b both map to type KNOWS"));
+
+        // A type that is the name of another relation field
+        let config = GraphConfig::default().with_relation_type("a", "b");
+        let err = build_graph_with_config(&data, "Root", &config).unwrap_err();
+        assert!(matches!(err, StorageError::ConfigError(_)));
+
+        // ...even when that field's entries never resolve
+        let dangling = json!({"people": [{ "id": "1", "a": ["2"], "b": ["9"] }, { "id": "2" }]});
+        let err = build_graph_with_config(&dangling, "Root", &config).unwrap_err();
+        assert!(matches!(err, StorageError::ConfigError(_)));
+
+        // Swapping the two fields is unambiguous
+        let config = GraphConfig::default()
+            .with_relation_type("a", "b")
+            .with_relation_type("b", "a");
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+        assert_eq!(graph_to_json(&graph, &config), data);
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let data = json!({
+            "people": [
+                { "meta": { "id": "1" }, "reports_to": ["2"], "tags": ["2"] },
+                { "meta": { "id": "2", "kind": "Manager" } }
+            ]
+        });
+        let config = GraphConfig::builder()
+            .id_field("meta.id")
+            .label_field("meta.kind")
+            .default_label("Person")
+            .add_relation("reports_to")
+            .map_relation_type("reports_to", "MANAGED_BY")
+            .build();
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+
+        assert_eq!(
+            graph.get_node("1").unwrap().label.as_deref(),
+            Some("Person")
+        );
+        assert_eq!(
+            graph.get_node("2").unwrap().label.as_deref(),
+            Some("Manager")
+        );
+        assert_eq!(graph.iter_edges_of_type("MANAGED_BY").count(), 1);
+        // Fields not added as relations stay properties
+        assert_eq!(graph.iter_edges_of_type("tags").count(), 0);
+        assert_eq!(graph_to_json(&graph, &config), data);
+    }
+
+    #[test]
+    fn test_config_builder_nested_id_and_label_fields() {
+        let data = json!({
+            "users": [
+                { "meta": { "id": "u1", "kind": { "name": "Admin" } }, "friends": ["2"] },
+                { "meta": { "id": 2, "kind": { "name": "User" } }, "friends": ["u1", "x"] },
+                { "id": "ignored", "meta": {}, "friends": [2] }
+            ]
+        });
+        let config = GraphConfig::builder()
+            .id_field("meta.id")
+            .label_field("meta.kind.name")
+            .build();
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+        assert_eq!(
+            graph.get_node("u1").unwrap().label.as_deref(),
+            Some("Admin")
+        );
+        assert!(graph.get_node("ignored").is_none());
+        assert_eq!(graph.iter_edges_of_type("friends").count(), 3);
+
+        let strict = GraphConfig::builder()
+            .id_field("meta.id")
+            .label_field("meta.kind.name")
+            .auto_id(false)
+            .build();
+        assert!(build_graph_with_config(&data, "Root", &strict).is_err());
+    }
+
+    #[test]
+    fn test_config_builder_auto_id_and_required_fields() {
+        let data = json!({
+            "users": [
+                { "id": "u1", "name": "Alice", "friends": ["u2", "u3"] },
+                { "id": "u2" },
+                { "name": "Charlie" }
+            ]
+        });
+
+        let config = GraphConfig::builder().auto_id(false).build();
+        let result = build_graph_with_config(&data, "Root", &config);
+        assert!(matches!(result, Err(StorageError::InvalidData(_))));
+
+        let config = GraphConfig::builder().require_fields(["name"]).build();
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+        assert_eq!(graph.nodes.len(), 3); // Root + 2 named users
+        assert!(graph.get_node_index("u2").is_none());
+        assert!(graph.get_node_index("users-2").is_some());
+    }
+
+    #[test]
+    fn test_config_builder_labels() {
+        let data = json!({
+            "users": [
+                { "id": "u1", "type": "admin" },
+                { "id": "u2" }
+            ],
+            "settings": { "theme": "dark" }
+        });
+
+        let config = GraphConfig::builder().default_label("Node").build();
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+        let label = |id: &str| graph.get_node(id).unwrap().label.clone();
+        assert_eq!(label("u1").as_deref(), Some("admin"));
+        assert_eq!(label("u2").as_deref(), Some("Node"));
+        assert_eq!(label("settings").as_deref(), Some("Node"));
+
+        let config = GraphConfig::builder().label_from_path(true).build();
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+        assert_eq!(graph.iter_nodes_with_label("users").count(), 2);
+    }
+
+    #[test]
+    fn test_config_builder_shared_data() {
+        let data = Arc::new(json!({
+            "users": [
+                { "id": "u1", "name": "Alice", "friends": ["u2"] },
+                { "id": "u2", "name": "Bob" }
+            ]
+        }));
+        let config = GraphConfig::builder().shared_data(true).build();
+        let graph = build_graph_from_shared(&data, "Root", &config).unwrap();
+        let alice = graph.get_node("u1").unwrap();
+        assert!(alice.data.is_shared());
+        assert_eq!(alice.get_property("name"), Some(&json!("Alice")));
+        assert!(graph.edges.iter().any(|e| e.rel_type == "friends"));
+    }
+
+    #[test]
+    fn test_config_builder_node_path_and_sort() {
+        let data = json!({
+            "departments": [
+                { "name": "R&D", "employees": [
+                    { "id": "e3", "manager": ["e1"] },
+                    { "id": "e2" }
+                ] },
+                { "name": "Sales", "employees": [{ "id": "e1" }] }
+            ]
+        });
+
+        let config = GraphConfig::builder()
+            .node_path("departments.*.employees")
+            .sort_nodes_by_id(true)
+            .build();
+        let graph = build_graph_with_config(&data, "Company", &config).unwrap();
+        let ids: Vec<&str> = graph
+            .iter_nodes_with_label("employees")
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["e1", "e2", "e3"]);
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.iter_edges_of_type("manager").count(), 1);
+    }
+
+    #[test]
+    fn test_config_builder_strict_relations() {
+        let data = json!({
+            "users": [
+                { "id": "1", "friends": ["2", "9"], "tags": ["a", "b"] },
+                { "id": "2", "friends": [null, ""] }
+            ]
+        });
+
+        let strict = GraphConfig::builder()
+            .strict_relations(true)
+            .add_relation("friends")
+            .build();
+        let err = build_graph_with_config(&data, "Root", &strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid data structure: 3 unresolved relation target(s): \
+             1 -[friends]-> \"9\", 2 -[friends]-> null, 2 -[friends]-> \"\""
+        );
+
+        let lenient = GraphConfig::builder().add_relation("friends").build();
+        let graph = build_graph_with_config(&data, "Root", &lenient).unwrap();
+        assert_eq!(graph.iter_edges_of_type("friends").count(), 1);
+    }
+
+    #[test]
+    fn test_nested_id_and_label_fields() {
+        let data = json!({
+            "users": [
+                { "meta": { "id": "u1", "kind": { "name": "Admin" } }, "friends": ["2"] },
+                { "meta": { "id": 2, "kind": { "name": "User" } }, "friends": ["u1", "x"] },
+                { "id": "ignored", "meta": {}, "friends": [2] }
+            ]
+        });
+        let config = GraphConfig::default()
+            .with_id_field("meta.id")
+            .with_label_field("meta.kind.name");
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+
+        assert_eq!(
+            graph.get_node("u1").unwrap().label.as_deref(),
+            Some("Admin")
+        );
+        assert_eq!(graph.get_node("2").unwrap().label.as_deref(), Some("User"));
+        // The top-level id is not consulted; the synthetic id is used instead
+        assert!(graph.get_node("ignored").is_none());
+        assert_eq!(
+            graph.get_node("users-2").unwrap().label.as_deref(),
+            Some("users")
+        );
+
+        let friends: Vec<(&str, &str)> = graph
+            .iter_edges_of_type("friends")
+            .map(|e| {
+                (
+                    graph.nodes[e.from].id.as_str(),
+                    graph.nodes[e.to].id.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(friends, vec![("u1", "2"), ("2", "u1"), ("users-2", "2")]);
+
+        let strict = config.with_auto_id(false);
+        assert!(build_graph_with_config(&data, "Root", &strict).is_err());
+    }
+
+    #[test]
+    fn test_auto_id_assigns_synthetic_ids() {
+        let data = json!({
+            "users": [
+                { "id": "u1", "friends": ["u2"] },
+                { "name": "anonymous" },
+                { "id": "u2", "friends": ["u1"] }
+            ]
+        });
+
+        let graph = build_graph_from_root_object(&data, "Root").unwrap();
+        assert_eq!(graph.get_node_index("users-1"), Some(2));
+        let keyed_edges = graph
+            .edges
+            .iter()
+            .filter(|e| e.rel_type == "friends")
+            .count();
+        assert_eq!(keyed_edges, 2);
+
+        let config = GraphConfig::default().with_auto_id(false);
+        let result = build_graph_with_config(&data, "Root", &config);
+        assert!(matches!(result, Err(StorageError::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_require_fields_skips_incomplete_elements() {
+        let data = json!({
+            "users": [
+                { "id": "u1", "name": "Alice", "friends": ["u2", "u3"] },
+                { "id": "u2" },
+                { "id": "u3", "name": "Charlie" }
+            ]
+        });
+
+        let config = GraphConfig::default().with_required_fields(["name"]);
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+        assert_eq!(graph.nodes.len(), 3); // Root + 2 named users
+        assert!(graph.get_node_index("u2").is_none());
+
+        let friends = graph
+            .edges
+            .iter()
+            .filter(|e| e.rel_type == "friends")
+            .count();
+        assert_eq!(friends, 1);
+    }
+
+    #[test]
+    fn test_default_label_for_unlabeled_children() {
+        let data = json!({
+            "users": [
+                { "id": "u1", "type": "admin" },
+                { "id": "u2" }
+            ],
+            "settings": { "theme": "dark" }
+        });
+
+        let graph = build_graph_from_root_object(&data, "Root").unwrap();
+        assert_eq!(
+            graph.get_node("u2").unwrap().label.as_deref(),
+            Some("users")
+        );
+
+        let config = GraphConfig::default().with_default_label("Node");
+        let graph = build_graph_with_config(&data, "Root", &config).unwrap();
+        let label = |id: &str| graph.get_node(id).unwrap().label.clone();
+        assert_eq!(label("u1").as_deref(), Some("admin"));
+        assert_eq!(label("u2").as_deref(), Some("Node"));
+        assert_eq!(label("settings").as_deref(), Some("Node"));
+        assert_eq!(label("root").as_deref(), Some("Root"));
+    }
+
+    #[test]
+    fn test_shared_data_references_source() {
+        let data = Arc::new(json!({
+            "users": [
+                { "id": "u1", "name": "Alice", "friends": ["u2"] },
+                { "id": "u2", "name": "Bob" }
+            ],
+            "a/b": { "id": "x", "note": "escaped" }
+        }));
+        let config = GraphConfig::default().with_shared_data(true);
+        let graph = build_graph_from_shared(&data, "Root", &config).unwrap();
+
+        let alice = graph.get_node("u1").unwrap();
+        assert!(alice.data.is_shared());
+        assert_eq!(alice.get_property("name"), Some(&json!("Alice")));
+        assert_eq!(graph.get_node("x").unwrap().data["note"], json!("escaped"));
+        assert!(graph.edges.iter().any(|e| e.rel_type == "friends"));
+        // Child nodes hold references to the document rather than copies
+        assert!(Arc::strong_count(&data) > 2);
+
+        let mut graph = graph;
+        let idx = graph.get_node_index("u1").unwrap();
+        graph.nodes[idx].data["name"] = json!("Alicia");
+        assert!(!graph.nodes[idx].data.is_shared());
+        assert_eq!(data["users"][0]["name"], json!("Alice"));
+
+        let owned = build_graph_from_shared(&data, "Root", &GraphConfig::default()).unwrap();
+        assert!(!owned.get_node("u1").unwrap().data.is_shared());
+    }
+
     #[test]
     fn test_json_storage_metadata() {
         let data = json!({"users": []});
@@ -382,4 +1796,72 @@ mod tests {
             Some("root".to_string())
         );
     }
+
+    #[test]
+    fn test_mixed_string_and_numeric_ids_connect() {
+        let data = json!({
+            "users": [
+                { "id": 1, "name": "Alice", "friends": ["2"] },
+                { "id": "2", "name": "Bob", "friends": [1, 3] },
+                { "id": 3, "name": "Charlie", "friends": [] }
+            ]
+        });
+
+        let graph = build_graph_from_root_object(&data, "Root").unwrap();
+        assert_eq!(graph.get_node_index("1"), Some(1));
+        assert_eq!(graph.get_node_index("3"), Some(3));
+
+        let friends: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .filter(|e| e.rel_type == "friends")
+            .map(|e| {
+                (
+                    graph.nodes[e.from].id.as_str(),
+                    graph.nodes[e.to].id.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(friends, vec![("1", "2"), ("2", "1"), ("2", "3")]);
+    }
+
+    #[test]
+    fn test_zero_padded_string_ids() {
+        let data = json!({
+            "agents": [
+                { "id": "007", "partners": ["008", 8] },
+                { "id": "008", "partners": [7, 7.0] },
+                { "id": 7 }
+            ]
+        });
+
+        let graph = build_graph_from_root_object(&data, "Root").unwrap();
+        assert!(graph.get_node_index("007").is_some());
+        // A numeric reference never matches a zero-padded string id
+        let partners: Vec<(&str, &str)> = graph
+            .iter_edges_of_type("partners")
+            .map(|e| {
+                (
+                    graph.nodes[e.from].id.as_str(),
+                    graph.nodes[e.to].id.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(partners, vec![("007", "008"), ("008", "7"), ("008", "7")]);
+
+        let strict = GraphConfig::default().with_strict_relations(true);
+        let err = build_graph_with_config(&data, "Root", &strict).unwrap_err();
+        assert!(err.to_string().contains("007 -[partners]-> 8"));
+    }
+
+    #[test]
+    fn test_id_to_string() {
+        assert_eq!(id_to_string(&json!("a1")), Some("a1".to_string()));
+        assert_eq!(id_to_string(&json!(42)), Some("42".to_string()));
+        assert_eq!(id_to_string(&json!(7.0)), Some("7".to_string()));
+        assert_eq!(id_to_string(&json!(7.5)), Some("7.5".to_string()));
+        assert_eq!(id_to_string(&json!("007")), Some("007".to_string()));
+        assert_eq!(id_to_string(&json!(true)), None);
+        assert_eq!(id_to_string(&Value::Null), None);
+    }
 }
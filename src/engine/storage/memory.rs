@@ -64,11 +64,7 @@ impl MemoryStorage {
 
 impl SyncStorage for MemoryStorage {
     fn load_graph_sync(&self) -> StorageResult<Graph> {
-        Ok(Graph {
-            nodes: self.graph.nodes.clone(),
-            edges: self.graph.edges.clone(),
-            id_map: self.graph.id_map.clone(),
-        })
+        Ok(Graph::clone(&self.graph))
     }
 
     fn get_node_sync(&self, id: &str) -> StorageResult<Option<Node>> {
@@ -121,11 +117,7 @@ impl MemoryStorageBuilder {
 impl Clone for MemoryStorageBuilder {
     fn clone(&self) -> Self {
         Self {
-            graph: Graph {
-                nodes: self.graph.nodes.clone(),
-                edges: self.graph.edges.clone(),
-                id_map: self.graph.id_map.clone(),
-            },
+            graph: self.graph.clone(),
         }
     }
 }
@@ -3,12 +3,14 @@
 //! This module provides a pluggable storage interface that allows different
 //! data sources to be used with the query engine.
 
+pub mod csv;
 pub mod json;
 pub mod memory;
 pub mod storage_trait;
 
 // Re-export commonly used types
-pub use json::JsonStorage;
+pub use csv::{CsvConfig, CsvStorage};
+pub use json::{GraphConfig, GraphConfigBuilder, JsonStorage};
 pub use memory::{MemoryStorage, MemoryStorageBuilder};
 pub use storage_trait::{
     Storage, StorageError, StorageFeature, StorageMetadata, StorageResult, SyncStorage,
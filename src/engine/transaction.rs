@@ -0,0 +1,45 @@
+//! All-or-nothing batches of mutating queries.
+
+use super::{ExecutionOptions, QueryResult, Result};
+use crate::graph::Graph;
+
+/// A working copy of a graph that mutating queries are applied to.
+///
+/// Handed out by `CypherEngine::transaction`. Queries see the effects of
+/// earlier queries in the same transaction; the engine's graph only
+/// changes if the whole transaction succeeds.
+#[derive(Debug)]
+pub struct Transaction<'a> {
+    graph: Graph,
+    options: &'a ExecutionOptions,
+}
+
+impl<'a> Transaction<'a> {
+    /// Start a transaction on a copy of `graph`.
+    pub fn new(graph: &Graph, options: &'a ExecutionOptions) -> Self {
+        Self {
+            graph: graph.clone(),
+            options,
+        }
+    }
+
+    /// Execute a read-only query against the transaction's graph.
+    pub fn execute(&self, query: &str) -> Result<QueryResult> {
+        super::execute_with_options(query, &self.graph, self.options)
+    }
+
+    /// Execute a query that may mutate the transaction's graph.
+    pub fn execute_mut(&mut self, query: &str) -> Result<QueryResult> {
+        super::execute_mut(query, &mut self.graph, self.options)
+    }
+
+    /// The transaction's graph, including uncommitted changes.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Finish the transaction, returning the modified graph.
+    pub fn into_graph(self) -> Graph {
+        self.graph
+    }
+}
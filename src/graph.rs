@@ -1,5 +1,8 @@
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 /// A graph structure containing nodes and edges.
 #[derive(Debug, Clone)]
@@ -8,6 +11,8 @@ pub struct Graph {
     pub edges: Vec<Edge>,
     /// Maps node IDs to their index in the nodes vector
     pub id_map: HashMap<String, usize>,
+    /// Interned labels, shared by every node with the same label
+    labels: HashSet<Arc<str>>,
 }
 
 impl Graph {
@@ -17,17 +22,35 @@ impl Graph {
             nodes: Vec::new(),
             edges: Vec::new(),
             id_map: HashMap::new(),
+            labels: HashSet::new(),
         }
     }
 
     /// Add a node to the graph.
-    pub fn add_node(&mut self, node: Node) -> usize {
+    ///
+    /// The node's label is interned, so nodes with the same label share a
+    /// single allocation.
+    pub fn add_node(&mut self, mut node: Node) -> usize {
+        if let Some(label) = node.label.take() {
+            node.label = Some(self.intern_label(label));
+        }
         let idx = self.nodes.len();
         self.id_map.insert(node.id.clone(), idx);
         self.nodes.push(node);
         idx
     }
 
+    /// The shared copy of `label`, adding it to the pool if it is new.
+    fn intern_label(&mut self, label: Arc<str>) -> Arc<str> {
+        match self.labels.get(&label) {
+            Some(existing) => Arc::clone(existing),
+            None => {
+                self.labels.insert(Arc::clone(&label));
+                label
+            }
+        }
+    }
+
     /// Get a node by its ID.
     pub fn get_node(&self, id: &str) -> Option<&Node> {
         self.id_map.get(id).map(|&idx| &self.nodes[idx])
@@ -38,11 +61,54 @@ impl Graph {
         self.id_map.get(id).copied()
     }
 
+    /// Find the edge of type `rel_type` stored from `from` to `to`.
+    ///
+    /// With parallel edges of the same type, this is the first in edge order.
+    pub fn find_edge(&self, from: usize, to: usize, rel_type: &str) -> Option<&Edge> {
+        self.edges
+            .iter()
+            .find(|e| e.from == from && e.to == to && e.rel_type == rel_type)
+    }
+
     /// Add an edge to the graph.
+    ///
+    /// The endpoints are not checked; see [`Graph::try_add_edge`] for graphs
+    /// built by hand.
     pub fn add_edge(&mut self, edge: Edge) {
         self.edges.push(edge);
     }
 
+    /// Add an edge, rejecting it if either endpoint is not a node index.
+    pub fn try_add_edge(&mut self, edge: Edge) -> Result<(), String> {
+        self.check_edge(&edge)?;
+        self.edges.push(edge);
+        Ok(())
+    }
+
+    /// Check that every edge references existing nodes.
+    ///
+    /// Graphs loaded from JSON always pass; graphs assembled with
+    /// [`Graph::add_edge`] may not, and querying them can panic.
+    pub fn validate(&self) -> Result<(), String> {
+        self.edges.iter().try_for_each(|edge| self.check_edge(edge))
+    }
+
+    fn check_edge(&self, edge: &Edge) -> Result<(), String> {
+        for idx in [edge.from, edge.to] {
+            if idx >= self.nodes.len() {
+                return Err(format!(
+                    "Edge {} -[:{}]-> {} references node index {}, but the graph has {} nodes",
+                    edge.from,
+                    edge.rel_type,
+                    edge.to,
+                    idx,
+                    self.nodes.len()
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Get all edges from a given node index.
     pub fn get_outgoing_edges(&self, from_idx: usize) -> Vec<&Edge> {
         self.edges.iter().filter(|e| e.from == from_idx).collect()
@@ -52,6 +118,418 @@ impl Graph {
     pub fn get_incoming_edges(&self, to_idx: usize) -> Vec<&Edge> {
         self.edges.iter().filter(|e| e.to == to_idx).collect()
     }
+
+    /// Iterate over the nodes labeled `label`, in insertion order.
+    pub fn iter_nodes_with_label<'a>(&'a self, label: &'a str) -> impl Iterator<Item = &'a Node> {
+        self.nodes.iter().filter(move |node| node.has_label(label))
+    }
+
+    /// Iterate over the edges of type `rel_type`, in insertion order.
+    pub fn iter_edges_of_type<'a>(&'a self, rel_type: &'a str) -> impl Iterator<Item = &'a Edge> {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.rel_type == rel_type)
+    }
+
+    /// List every edge touching a node as `(direction, type, neighbor index)`.
+    ///
+    /// Outgoing edges come first, then incoming ones, each in edge order.
+    /// A self-loop is listed once in each direction.
+    pub fn node_relationships(&self, node_idx: usize) -> Vec<(Direction, String, usize)> {
+        let outgoing = self
+            .get_outgoing_edges(node_idx)
+            .into_iter()
+            .map(|e| (Direction::Outgoing, e.rel_type.clone(), e.to));
+        let incoming = self
+            .get_incoming_edges(node_idx)
+            .into_iter()
+            .map(|e| (Direction::Incoming, e.rel_type.clone(), e.from));
+        outgoing.chain(incoming).collect()
+    }
+
+    /// Number of hops on a shortest path from `from_id` to `to_id`,
+    /// following edges in their direction and, when given, only edges of
+    /// type `rel_type`.
+    ///
+    /// A node is at distance 0 from itself. Returns `None` if either id is
+    /// unknown or `to_id` can't be reached.
+    pub fn distance(&self, from_id: &str, to_id: &str, rel_type: Option<&str>) -> Option<usize> {
+        let from = self.get_node_index(from_id)?;
+        let to = self.get_node_index(to_id)?;
+        if from == to {
+            return Some(0);
+        }
+
+        let mut adjacency = vec![Vec::new(); self.nodes.len()];
+        for edge in &self.edges {
+            if rel_type.is_none_or(|rel_type| edge.rel_type == rel_type) {
+                adjacency[edge.from].push(edge.to);
+            }
+        }
+
+        let mut visited = vec![false; self.nodes.len()];
+        visited[from] = true;
+        let mut queue = VecDeque::from([(from, 0)]);
+        while let Some((idx, depth)) = queue.pop_front() {
+            for &next in &adjacency[idx] {
+                if next == to {
+                    return Some(depth + 1);
+                }
+                if !visited[next] {
+                    visited[next] = true;
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+        None
+    }
+
+    /// Nodes with no incoming or outgoing edges, in insertion order.
+    ///
+    /// A self-loop counts as an edge. Graphs loaded from JSON link every
+    /// child to the root, so orphans there are nodes cut off by queries;
+    /// in CSV loads they are rows without relations.
+    pub fn orphan_nodes(&self) -> Vec<&Node> {
+        let mut connected = vec![false; self.nodes.len()];
+        for edge in &self.edges {
+            connected[edge.from] = true;
+            connected[edge.to] = true;
+        }
+        self.nodes
+            .iter()
+            .zip(connected)
+            .filter(|(_, connected)| !connected)
+            .map(|(node, _)| node)
+            .collect()
+    }
+
+    /// Map each degree to the number of nodes with that degree.
+    ///
+    /// `direction` counts outgoing or incoming edges only; `None` counts
+    /// both, so a self-loop adds 2. Nodes without edges are counted under
+    /// degree 0, and the counts add up to [`Graph::node_count`].
+    pub fn degree_distribution(&self, direction: Option<Direction>) -> HashMap<usize, usize> {
+        let mut degrees = vec![0; self.nodes.len()];
+        for edge in &self.edges {
+            if direction != Some(Direction::Incoming) {
+                degrees[edge.from] += 1;
+            }
+            if direction != Some(Direction::Outgoing) {
+                degrees[edge.to] += 1;
+            }
+        }
+
+        let mut distribution = HashMap::new();
+        for degree in degrees {
+            *distribution.entry(degree).or_insert(0) += 1;
+        }
+        distribution
+    }
+
+    /// Number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Number of edges in the graph.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Compute summary statistics for the graph in a single pass.
+    pub fn stats(&self) -> GraphStats {
+        let mut nodes_per_label: HashMap<String, usize> = HashMap::new();
+        for node in &self.nodes {
+            if let Some(ref label) = node.label {
+                *nodes_per_label.entry(label.to_string()).or_insert(0) += 1;
+            }
+        }
+
+        let mut edges_per_type: HashMap<String, usize> = HashMap::new();
+        for edge in &self.edges {
+            *edges_per_type.entry(edge.rel_type.clone()).or_insert(0) += 1;
+        }
+
+        GraphStats {
+            node_count: self.nodes.len(),
+            edge_count: self.edges.len(),
+            nodes_per_label,
+            edges_per_type,
+        }
+    }
+
+    /// Export the graph as an adjacency matrix.
+    ///
+    /// Returns node ids in insertion order together with a square matrix
+    /// whose entry `[i][j]` counts the edges from node `i` to node `j`. If
+    /// `rel_type` is given, only edges of that type are counted.
+    pub fn to_adjacency_matrix(&self, rel_type: Option<&str>) -> (Vec<String>, Vec<Vec<u32>>) {
+        let ids = self.nodes.iter().map(|n| n.id.clone()).collect();
+        let mut matrix = vec![vec![0u32; self.nodes.len()]; self.nodes.len()];
+        for edge in &self.edges {
+            if rel_type.is_none_or(|t| t == edge.rel_type) {
+                matrix[edge.from][edge.to] += 1;
+            }
+        }
+        (ids, matrix)
+    }
+
+    /// Count how many nodes hold each distinct value of `property`.
+    ///
+    /// Values are keyed by their string form: strings as-is and everything
+    /// else as JSON text, so `1` and `"1"` share a bucket. Nodes without the
+    /// property are counted under `"null"` together with explicit `null`s,
+    /// as `MATCH (n) RETURN n.prop, COUNT(*)` would group them.
+    pub fn property_histogram(&self, property: &str) -> HashMap<String, usize> {
+        let mut histogram = HashMap::new();
+        for node in &self.nodes {
+            let key = match node.get_property(property) {
+                Some(Value::String(s)) => s.clone(),
+                Some(value) => value.to_string(),
+                None => "null".to_string(),
+            };
+            *histogram.entry(key).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// Find every node whose `key` property equals `value`.
+    ///
+    /// Values are compared as JSON, not as strings: `"1"` does not match
+    /// `1`, while numbers match by value, so `1` matches `1.0`. Integers
+    /// are compared exactly, even beyond the range of `f64`. A `null`
+    /// value matches nothing, as `WHERE n.key = null` would. This scans
+    /// every node, which is still much cheaper than parsing and running the
+    /// equivalent `MATCH` query.
+    pub fn find_nodes_by_property(&self, key: &str, value: &Value) -> Vec<&Node> {
+        if value.is_null() {
+            return Vec::new();
+        }
+        self.nodes
+            .iter()
+            .filter(|node| {
+                node.get_property(key)
+                    .is_some_and(|property| match (property, value) {
+                        (Value::Number(a), Value::Number(b)) => numbers_equal(a, b),
+                        _ => property == value,
+                    })
+            })
+            .collect()
+    }
+
+    /// Remove nodes together with every edge touching them.
+    ///
+    /// Remaining nodes keep their relative order. Edge endpoints and
+    /// `id_map` are re-indexed to the new positions. Returns the number
+    /// of edges removed.
+    pub fn detach_delete_nodes(&mut self, indices: &HashSet<usize>) -> usize {
+        let edge_count = self.edges.len();
+        self.edges
+            .retain(|e| !indices.contains(&e.from) && !indices.contains(&e.to));
+        let removed_edges = edge_count - self.edges.len();
+
+        let mut remap = vec![None; self.nodes.len()];
+        let mut next_idx = 0;
+        for (idx, slot) in remap.iter_mut().enumerate() {
+            if !indices.contains(&idx) {
+                *slot = Some(next_idx);
+                next_idx += 1;
+            }
+        }
+
+        let mut idx = 0;
+        self.nodes.retain(|_| {
+            let keep = !indices.contains(&idx);
+            idx += 1;
+            keep
+        });
+
+        for edge in &mut self.edges {
+            edge.from = remap[edge.from].expect("edge endpoint was removed");
+            edge.to = remap[edge.to].expect("edge endpoint was removed");
+        }
+
+        self.id_map = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (node.id.clone(), idx))
+            .collect();
+
+        removed_edges
+    }
+
+    /// Compare this graph with `other`, taken as the newer version.
+    ///
+    /// Nodes are matched by id and count as changed when their data
+    /// differs (labels are not compared). Edges are compared by
+    /// `(from id, to id, type)`; parallel edges count individually, so a
+    /// dropped duplicate shows up as removed. Every list in the result is
+    /// sorted.
+    pub fn diff(&self, other: &Graph) -> GraphDiff {
+        let mut diff = GraphDiff::default();
+        for node in &self.nodes {
+            match other.get_node(&node.id) {
+                None => diff.removed_nodes.push(node.id.clone()),
+                Some(newer) if *newer.data != *node.data => {
+                    diff.changed_nodes.push(node.id.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        diff.added_nodes = other
+            .nodes
+            .iter()
+            .filter(|node| self.get_node(&node.id).is_none())
+            .map(|node| node.id.clone())
+            .collect();
+
+        fn edge_counts(graph: &Graph) -> HashMap<EdgeKey, usize> {
+            let mut counts = HashMap::new();
+            for edge in &graph.edges {
+                let key = (
+                    graph.nodes[edge.from].id.clone(),
+                    graph.nodes[edge.to].id.clone(),
+                    edge.rel_type.clone(),
+                );
+                *counts.entry(key).or_insert(0) += 1;
+            }
+            counts
+        }
+        let before = edge_counts(self);
+        let after = edge_counts(other);
+        for (key, &count) in &before {
+            let kept = after.get(key).copied().unwrap_or(0);
+            diff.removed_edges
+                .extend(std::iter::repeat_n(key.clone(), count.saturating_sub(kept)));
+        }
+        for (key, &count) in &after {
+            let kept = before.get(key).copied().unwrap_or(0);
+            diff.added_edges
+                .extend(std::iter::repeat_n(key.clone(), count.saturating_sub(kept)));
+        }
+
+        diff.added_nodes.sort();
+        diff.removed_nodes.sort();
+        diff.changed_nodes.sort();
+        diff.added_edges.sort();
+        diff.removed_edges.sort();
+        diff
+    }
+
+    /// Reorder nodes by id, so the order no longer depends on insertion.
+    ///
+    /// Integer ids come first in numeric order (`"2"` before `"10"`), and
+    /// the remaining ids follow in string order. Edge endpoints and
+    /// `id_map` are re-indexed to the new positions, and edges are sorted
+    /// by their remapped endpoints and then type, so that rows from
+    /// relationship patterns come out in the same order too.
+    pub fn sort_nodes_by_id(&mut self) {
+        let mut indexed: Vec<(usize, Node)> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .enumerate()
+            .collect();
+        indexed.sort_by_cached_key(|(_, node)| {
+            let parsed = node.id.parse::<i64>();
+            (parsed.is_err(), parsed.unwrap_or(0), node.id.clone())
+        });
+
+        let mut remap = vec![0; indexed.len()];
+        for (new_idx, (old_idx, _)) in indexed.iter().enumerate() {
+            remap[*old_idx] = new_idx;
+        }
+        self.nodes = indexed.into_iter().map(|(_, node)| node).collect();
+
+        for edge in &mut self.edges {
+            edge.from = remap[edge.from];
+            edge.to = remap[edge.to];
+        }
+        self.edges
+            .sort_by(|a, b| (a.from, a.to, &a.rel_type).cmp(&(b.from, b.to, &b.rel_type)));
+
+        self.id_map = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (node.id.clone(), idx))
+            .collect();
+    }
+}
+
+/// Whether two JSON numbers are equal by value.
+///
+/// Integers are compared exactly; only when either side is a float are
+/// both compared as `f64`.
+fn numbers_equal(a: &serde_json::Number, b: &serde_json::Number) -> bool {
+    if let (Some(x), Some(y)) = (a.as_i64(), b.as_i64()) {
+        return x == y;
+    }
+    if let (Some(x), Some(y)) = (a.as_u64(), b.as_u64()) {
+        return x == y;
+    }
+    if a.is_f64() || b.is_f64() {
+        return a.as_f64() == b.as_f64();
+    }
+    // A negative i64 and a u64 beyond i64::MAX
+    false
+}
+
+/// Summary statistics for a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphStats {
+    /// Total number of nodes
+    pub node_count: usize,
+    /// Total number of edges
+    pub edge_count: usize,
+    /// Number of nodes per label (unlabeled nodes are not counted)
+    pub nodes_per_label: HashMap<String, usize>,
+    /// Number of edges per relationship type
+    pub edges_per_type: HashMap<String, usize>,
+}
+
+impl GraphStats {
+    /// Number of distinct node labels.
+    pub fn label_count(&self) -> usize {
+        self.nodes_per_label.len()
+    }
+
+    /// The label with the most nodes and its count, ties going to the
+    /// alphabetically first label. `None` when no node is labeled.
+    pub fn most_common_label(&self) -> Option<(&str, usize)> {
+        self.nodes_per_label
+            .iter()
+            .map(|(label, count)| (label.as_str(), *count))
+            .min_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)))
+    }
+}
+
+/// An edge identified by `(from id, to id, type)`.
+pub type EdgeKey = (String, String, String);
+
+/// Differences between two graphs, as computed by [`Graph::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    /// Ids of nodes only in the newer graph
+    pub added_nodes: Vec<String>,
+    /// Ids of nodes only in the older graph
+    pub removed_nodes: Vec<String>,
+    /// Ids of nodes in both graphs whose data differs
+    pub changed_nodes: Vec<String>,
+    /// Edges only in the newer graph
+    pub added_edges: Vec<EdgeKey>,
+    /// Edges only in the older graph
+    pub removed_edges: Vec<EdgeKey>,
+}
+
+impl GraphDiff {
+    /// Whether the two graphs have the same nodes, data and edges.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
 }
 
 impl Default for Graph {
@@ -64,22 +542,89 @@ impl Default for Graph {
 #[derive(Debug, Clone)]
 pub struct Node {
     pub id: String,
-    pub label: Option<String>,
-    pub data: Value,
+    pub label: Option<Arc<str>>,
+    pub data: NodeData,
 }
 
 impl Node {
     /// Create a new node.
-    pub fn new(id: impl Into<String>, label: Option<String>, data: Value) -> Self {
+    pub fn new(id: impl Into<String>, label: Option<String>, data: impl Into<NodeData>) -> Self {
         Self {
             id: id.into(),
-            label,
-            data,
+            label: label.map(Arc::from),
+            data: data.into(),
+        }
+    }
+}
+
+/// The JSON properties of a node.
+///
+/// Either an owned value, or a reference into a shared source document
+/// given as a JSON pointer, which avoids copying every node's data when the
+/// document is already held in memory. Both dereference to the `Value`;
+/// mutable access converts a reference into an owned copy first.
+#[derive(Clone)]
+pub enum NodeData {
+    Owned(Value),
+    Ref(Arc<Value>, String),
+}
+
+impl NodeData {
+    /// Whether this data is a reference into a shared document.
+    pub fn is_shared(&self) -> bool {
+        matches!(self, NodeData::Ref(..))
+    }
+}
+
+impl From<Value> for NodeData {
+    fn from(value: Value) -> Self {
+        NodeData::Owned(value)
+    }
+}
+
+impl Deref for NodeData {
+    type Target = Value;
+
+    fn deref(&self) -> &Value {
+        static NULL: Value = Value::Null;
+        match self {
+            NodeData::Owned(value) => value,
+            NodeData::Ref(root, pointer) => root.pointer(pointer).unwrap_or(&NULL),
+        }
+    }
+}
+
+impl DerefMut for NodeData {
+    fn deref_mut(&mut self) -> &mut Value {
+        if let NodeData::Ref(..) = self {
+            *self = NodeData::Owned(Value::clone(self));
+        }
+        match self {
+            NodeData::Owned(value) => value,
+            NodeData::Ref(..) => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Debug for NodeData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeData::Owned(value) => value.fmt(f),
+            // Don't dump the whole shared document for every node
+            NodeData::Ref(_, pointer) => f.debug_tuple("Ref").field(pointer).finish(),
         }
     }
 }
 
 impl Node {
+    /// Whether the node carries `label`. Unlabeled nodes have no label.
+    ///
+    /// Use this rather than comparing [`Node::label`] directly, so that
+    /// every label check agrees.
+    pub fn has_label(&self, label: &str) -> bool {
+        self.label.as_deref() == Some(label)
+    }
+
     /// Get a property value from the node's data.
     pub fn get_property(&self, key: &str) -> Option<&Value> {
         self.data.get(key)
@@ -103,6 +648,20 @@ impl Node {
             _ => None,
         })
     }
+
+    /// Get a property as a bool.
+    pub fn get_property_as_bool(&self, key: &str) -> Option<bool> {
+        self.data.get(key).and_then(Value::as_bool)
+    }
+}
+
+/// Direction of an edge relative to a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The edge starts at the node
+    Outgoing,
+    /// The edge ends at the node
+    Incoming,
 }
 
 /// An edge in the graph.
@@ -111,6 +670,8 @@ pub struct Edge {
     pub from: usize,
     pub to: usize,
     pub rel_type: String,
+    /// Relationship properties (`Value::Null` when the edge has none).
+    pub data: Value,
 }
 
 impl Edge {
@@ -120,8 +681,20 @@ impl Edge {
             from,
             to,
             rel_type: rel_type.into(),
+            data: Value::Null,
         }
     }
+
+    /// Attach relationship properties to the edge.
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Get a relationship property value.
+    pub fn get_property(&self, key: &str) -> Option<&Value> {
+        self.data.get(key)
+    }
 }
 
 #[cfg(test)]
@@ -129,6 +702,30 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    fn create_test_graph() -> Graph {
+        let mut graph = Graph::new();
+        graph.add_node(Node::new(
+            "1".to_string(),
+            Some("admin".to_string()),
+            json!({"id": "1", "role": "admin", "age": 30}),
+        ));
+        graph.add_node(Node::new(
+            "2".to_string(),
+            Some("user".to_string()),
+            json!({"id": "2", "role": "user", "age": 25}),
+        ));
+        graph.add_node(Node::new(
+            "3".to_string(),
+            Some("admin".to_string()),
+            json!({"id": "3", "role": "admin", "age": 35}),
+        ));
+
+        graph.add_edge(Edge::new(0, 1, "knows".to_string()));
+        graph.add_edge(Edge::new(1, 2, "knows".to_string()));
+
+        graph
+    }
+
     #[test]
     fn test_graph_creation() {
         let graph = Graph::new();
@@ -173,18 +770,382 @@ mod tests {
         assert_eq!(graph.edges.len(), 1);
     }
 
+    #[test]
+    fn test_detach_delete_nodes() {
+        let mut graph = Graph::new();
+        for id in ["1", "2", "3"] {
+            graph.add_node(Node::new(id, Some("User".to_string()), json!({})));
+        }
+        graph.add_edge(Edge::new(0, 1, "knows"));
+        graph.add_edge(Edge::new(1, 0, "knows"));
+        graph.add_edge(Edge::new(0, 2, "knows"));
+
+        let removed = graph.detach_delete_nodes(&HashSet::from([1]));
+        assert_eq!(removed, 2);
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.get_node("2").is_none());
+        assert_eq!(graph.get_node_index("3"), Some(1));
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].from, 0);
+        assert_eq!(graph.edges[0].to, 1);
+    }
+
+    #[test]
+    fn test_sort_nodes_by_id() {
+        let mut graph = Graph::new();
+        for id in ["10", "b", "2", "a"] {
+            graph.add_node(Node::new(id, None, json!({})));
+        }
+        graph.add_edge(Edge::new(0, 2, "knows"));
+        graph.add_edge(Edge::new(3, 1, "likes"));
+        graph.add_edge(Edge::new(0, 1, "knows"));
+
+        graph.sort_nodes_by_id();
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "10", "a", "b"]);
+        assert_eq!(graph.get_node_index("b"), Some(3));
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| {
+                (
+                    graph.nodes[e.from].id.as_str(),
+                    graph.nodes[e.to].id.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(edges, vec![("10", "2"), ("10", "b"), ("a", "b")]);
+    }
+
+    #[test]
+    fn test_sort_nodes_by_mixed_ids() {
+        for order in [["10a", "9", "10"], ["9", "10", "10a"], ["10", "10a", "9"]] {
+            let mut graph = Graph::new();
+            for id in order {
+                graph.add_node(Node::new(id, None, json!({})));
+            }
+            graph.sort_nodes_by_id();
+            let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+            assert_eq!(ids, vec!["9", "10", "10a"]);
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut before = Graph::new();
+        for id in ["1", "2", "3"] {
+            before.add_node(Node::new(id, Some("User".to_string()), json!({"id": id})));
+        }
+        before.add_edge(Edge::new(0, 1, "knows"));
+        before.add_edge(Edge::new(1, 2, "knows"));
+        assert!(before.diff(&before.clone()).is_empty());
+
+        let mut after = before.clone();
+        after.add_node(Node::new("4", Some("User".to_string()), json!({"id": "4"})));
+        after.add_edge(Edge::new(3, 0, "knows"));
+        after.edges.retain(|e| !(e.from == 1 && e.to == 2));
+        if let Value::Object(obj) = &mut *after.nodes[0].data {
+            obj.insert("name".to_string(), json!("Alice"));
+        }
+
+        let diff = before.diff(&after);
+        let key = |from: &str, to: &str| (from.to_string(), to.to_string(), "knows".to_string());
+        assert_eq!(diff.added_nodes, vec!["4"]);
+        assert!(diff.removed_nodes.is_empty());
+        assert_eq!(diff.changed_nodes, vec!["1"]);
+        assert_eq!(diff.added_edges, vec![key("4", "1")]);
+        assert_eq!(diff.removed_edges, vec![key("2", "3")]);
+
+        // The reverse diff swaps additions and removals
+        let reverse = after.diff(&before);
+        assert_eq!(reverse.removed_nodes, vec!["4"]);
+        assert_eq!(reverse.added_edges, vec![key("2", "3")]);
+    }
+
+    #[test]
+    fn test_find_edge_with_data() {
+        let mut graph = Graph::new();
+        graph.add_node(Node::new("1", None, json!({})));
+        graph.add_node(Node::new("2", None, json!({})));
+        graph.add_edge(Edge::new(0, 1, "knows").with_data(json!({"since": 2020})));
+
+        let edge = graph.find_edge(0, 1, "knows").unwrap();
+        assert_eq!(edge.get_property("since"), Some(&json!(2020)));
+        assert!(graph.find_edge(1, 0, "knows").is_none());
+    }
+
+    #[test]
+    fn test_labels_are_interned() {
+        let mut graph = Graph::new();
+        for i in 0..1000 {
+            let label = if i % 2 == 0 { "even" } else { "odd" };
+            graph.add_node(Node::new(i.to_string(), Some(label.to_string()), json!({})));
+        }
+        graph.add_node(Node::new("x", None, json!({})));
+
+        // 1000 labeled nodes share two label allocations
+        let mut distinct: Vec<*const u8> = graph
+            .nodes
+            .iter()
+            .filter_map(|n| n.label.as_ref().map(|l| l.as_ptr()))
+            .collect();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 2);
+        assert_eq!(graph.nodes[0].label.as_deref(), Some("even"));
+        assert_eq!(graph.stats().nodes_per_label["odd"], 500);
+    }
+
+    #[test]
+    fn test_stats_per_label() {
+        let mut graph = Graph::new();
+        for (id, label) in [
+            ("1", Some("admin")),
+            ("2", Some("user")),
+            ("3", Some("user")),
+            ("4", Some("guest")),
+            ("5", Some("user")),
+            ("6", None),
+        ] {
+            graph.add_node(Node::new(id, label.map(str::to_string), json!({})));
+        }
+
+        let stats = graph.stats();
+        assert_eq!(stats.node_count, 6);
+        assert_eq!(stats.label_count(), 3);
+        assert_eq!(
+            stats.nodes_per_label,
+            HashMap::from([
+                ("admin".to_string(), 1),
+                ("user".to_string(), 3),
+                ("guest".to_string(), 1),
+            ])
+        );
+        assert_eq!(stats.most_common_label(), Some(("user", 3)));
+        assert_eq!(Graph::new().stats().most_common_label(), None);
+    }
+
+    #[test]
+    fn test_to_adjacency_matrix() {
+        let mut graph = Graph::new();
+        for id in ["a", "b", "c"] {
+            graph.add_node(Node::new(id, None, json!({})));
+        }
+        graph.add_edge(Edge::new(0, 1, "knows"));
+        graph.add_edge(Edge::new(0, 1, "likes"));
+        graph.add_edge(Edge::new(1, 2, "knows"));
+        graph.add_edge(Edge::new(2, 2, "likes"));
+
+        let (ids, matrix) = graph.to_adjacency_matrix(None);
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(matrix, vec![vec![0, 2, 0], vec![0, 0, 1], vec![0, 0, 1]]);
+
+        let (_, matrix) = graph.to_adjacency_matrix(Some("knows"));
+        assert_eq!(matrix, vec![vec![0, 1, 0], vec![0, 0, 1], vec![0, 0, 0]]);
+    }
+
+    #[test]
+    fn test_dangling_edges() {
+        let mut graph = Graph::new();
+        graph.add_node(Node::new("a", None, json!({})));
+        graph.add_node(Node::new("b", None, json!({})));
+
+        assert!(graph.try_add_edge(Edge::new(0, 1, "knows")).is_ok());
+        let err = graph.try_add_edge(Edge::new(1, 2, "knows")).unwrap_err();
+        assert!(err.contains("node index 2"));
+        assert_eq!(graph.edge_count(), 1);
+        assert!(graph.validate().is_ok());
+
+        graph.add_edge(Edge::new(5, 0, "knows"));
+        assert!(graph.validate().unwrap_err().contains("node index 5"));
+    }
+
+    #[test]
+    fn test_node_relationships() {
+        let mut graph = create_test_graph();
+        graph.add_edge(Edge::new(2, 2, "self"));
+
+        assert_eq!(
+            graph.node_relationships(1),
+            vec![
+                (Direction::Outgoing, "knows".to_string(), 2),
+                (Direction::Incoming, "knows".to_string(), 0),
+            ]
+        );
+        assert_eq!(graph.node_relationships(2).len(), 3);
+        assert!(graph.node_relationships(5).is_empty());
+    }
+
+    #[test]
+    fn test_iter_nodes_with_label_and_edges_of_type() {
+        let mut graph = create_test_graph();
+        graph.add_node(Node::new("4", None, json!({})));
+        graph.add_edge(Edge::new(2, 0, "likes"));
+
+        let admins: Vec<&str> = graph
+            .iter_nodes_with_label("admin")
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(admins, vec!["1", "3"]);
+        assert_eq!(graph.iter_nodes_with_label("guest").count(), 0);
+
+        let knows: Vec<(usize, usize)> = graph
+            .iter_edges_of_type("knows")
+            .map(|e| (e.from, e.to))
+            .collect();
+        assert_eq!(knows, vec![(0, 1), (1, 2)]);
+        assert_eq!(graph.iter_edges_of_type("likes").count(), 1);
+        assert_eq!(graph.iter_edges_of_type("blocks").count(), 0);
+    }
+
+    #[test]
+    fn test_distance() {
+        let mut graph = Graph::new();
+        for id in ["1", "2", "3", "4", "5"] {
+            graph.add_node(Node::new(id, None, json!({})));
+        }
+        graph.add_edge(Edge::new(0, 1, "knows"));
+        graph.add_edge(Edge::new(1, 2, "knows"));
+        graph.add_edge(Edge::new(2, 3, "likes"));
+        graph.add_edge(Edge::new(0, 3, "blocks"));
+
+        assert_eq!(graph.distance("1", "1", None), Some(0));
+        assert_eq!(graph.distance("1", "2", None), Some(1));
+        assert_eq!(graph.distance("1", "4", None), Some(1));
+        assert_eq!(graph.distance("1", "3", Some("knows")), Some(2));
+        assert_eq!(graph.distance("1", "4", Some("knows")), None);
+        assert_eq!(graph.distance("2", "4", Some("likes")), None);
+        assert_eq!(graph.distance("2", "4", None), Some(2));
+        // Edges are followed in their direction only
+        assert_eq!(graph.distance("2", "1", None), None);
+        assert_eq!(graph.distance("1", "5", None), None);
+        assert_eq!(graph.distance("1", "missing", None), None);
+    }
+
+    #[test]
+    fn test_orphan_nodes() {
+        let mut graph = Graph::new();
+        for id in ["1", "2", "3", "4"] {
+            graph.add_node(Node::new(id, None, json!({})));
+        }
+        graph.add_edge(Edge::new(0, 1, "knows"));
+        graph.add_edge(Edge::new(3, 3, "self"));
+
+        let orphans: Vec<&str> = graph.orphan_nodes().iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(orphans, vec!["3"]);
+        assert!(Graph::new().orphan_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_degree_distribution() {
+        // A star: the hub points at four leaves, one of which loops
+        let mut graph = Graph::new();
+        for id in ["hub", "a", "b", "c", "d"] {
+            graph.add_node(Node::new(id, None, json!({})));
+        }
+        for leaf in 1..=4 {
+            graph.add_edge(Edge::new(0, leaf, "spoke"));
+        }
+        graph.add_edge(Edge::new(4, 4, "self"));
+
+        assert_eq!(
+            graph.degree_distribution(Some(Direction::Outgoing)),
+            HashMap::from([(4, 1), (0, 3), (1, 1)])
+        );
+        assert_eq!(
+            graph.degree_distribution(Some(Direction::Incoming)),
+            HashMap::from([(0, 1), (1, 3), (2, 1)])
+        );
+        assert_eq!(
+            graph.degree_distribution(None),
+            HashMap::from([(4, 1), (1, 3), (3, 1)])
+        );
+        assert!(Graph::new().degree_distribution(None).is_empty());
+    }
+
+    #[test]
+    fn test_property_histogram() {
+        let mut graph = Graph::new();
+        for (id, data) in [
+            ("a", json!({"role": "admin"})),
+            ("b", json!({"role": "user"})),
+            ("c", json!({"role": "user"})),
+            ("d", json!({"role": null})),
+            ("e", json!({})),
+            ("f", json!({"role": 1})),
+        ] {
+            graph.add_node(Node::new(id, None, data));
+        }
+
+        let histogram = graph.property_histogram("role");
+        assert_eq!(histogram.len(), 4);
+        assert_eq!(histogram["admin"], 1);
+        assert_eq!(histogram["user"], 2);
+        assert_eq!(histogram["null"], 2);
+        assert_eq!(histogram["1"], 1);
+    }
+
+    #[test]
+    fn test_find_nodes_by_property() {
+        let mut graph = Graph::new();
+        for (id, data) in [
+            ("a", json!({"name": "Alice", "age": 30, "active": true})),
+            ("b", json!({"name": "Bob", "age": 30.0, "active": false})),
+            ("c", json!({"name": "30", "age": 41, "active": "true"})),
+            ("d", json!({"name": null})),
+            ("e", json!({"big": 9007199254740993i64})),
+            ("f", json!({"big": u64::MAX})),
+        ] {
+            graph.add_node(Node::new(id, None, data));
+        }
+        let ids = |key: &str, value: Value| -> Vec<String> {
+            graph
+                .find_nodes_by_property(key, &value)
+                .iter()
+                .map(|n| n.id.clone())
+                .collect()
+        };
+
+        assert_eq!(ids("name", json!("Bob")), vec!["b"]);
+        assert_eq!(ids("name", json!("30")), vec!["c"]);
+        assert_eq!(ids("age", json!(30)), vec!["a", "b"]);
+        // Integers beyond 2^53 don't collide with their neighbours
+        assert_eq!(ids("big", json!(9007199254740993i64)), vec!["e"]);
+        assert!(ids("big", json!(9007199254740992i64)).is_empty());
+        assert_eq!(ids("big", json!(u64::MAX)), vec!["f"]);
+        assert!(ids("big", json!(u64::MAX - 1)).is_empty());
+        assert_eq!(ids("active", json!(true)), vec!["a"]);
+        assert_eq!(ids("active", json!(false)), vec!["b"]);
+        assert!(ids("name", Value::Null).is_empty());
+        assert!(ids("missing", json!("x")).is_empty());
+    }
+
+    #[test]
+    fn test_node_has_label() {
+        let node = Node::new("1", Some("User".to_string()), json!({}));
+        assert!(node.has_label("User"));
+        assert!(!node.has_label("user"));
+        assert!(!node.has_label(""));
+
+        let unlabeled = Node::new("2", None, json!({}));
+        assert!(!unlabeled.has_label("User"));
+        assert!(!unlabeled.has_label(""));
+    }
+
     #[test]
     fn test_node_get_property() {
         let node = Node::new(
             "1".to_string(),
             Some("User".to_string()),
-            json!({"name": "Alice", "age": 30}),
+            json!({"name": "Alice", "age": 30, "active": true}),
         );
         assert_eq!(
             node.get_property_as_string("name"),
             Some("Alice".to_string())
         );
         assert_eq!(node.get_property_as_i64("age"), Some(30));
+        assert_eq!(node.get_property_as_bool("active"), Some(true));
+        assert!(node.get_property_as_bool("name").is_none());
         assert!(node.get_property_as_string("unknown").is_none());
     }
 }
@@ -29,6 +29,7 @@
 //! assert_eq!(result.get_single_value().unwrap().as_i64(), Some(55));
 //! ```
 
+pub mod builder;
 pub mod engine;
 pub mod graph;
 pub mod parser;
@@ -36,12 +37,20 @@ pub mod schema;
 
 use serde_json::Value;
 use std::fmt;
+use std::sync::Mutex;
 
+pub use builder::QueryBuilder;
 pub use engine::storage::SyncStorage;
-pub use engine::{EngineError, QueryResult, Result};
-pub use engine::{JsonStorage, MemoryStorage, MemoryStorageBuilder};
-pub use graph::{Edge, Graph, Node};
-pub use schema::{RootObjectSchema, SchemaAnalyzer, SchemaDetection, SchemaError};
+pub use engine::{CompiledQuery, EngineError, ExecutionOptions, QueryResult, Result, Transaction};
+pub use engine::{
+    CsvConfig, CsvStorage, GraphConfig, GraphConfigBuilder, JsonStorage, MemoryStorage,
+    MemoryStorageBuilder,
+};
+pub use graph::{Edge, EdgeKey, Graph, GraphDiff, GraphStats, Node};
+pub use parser::ast;
+pub use schema::{
+    AnalyzeOptions, IdUniqueness, RootObjectSchema, SchemaAnalyzer, SchemaDetection, SchemaError,
+};
 
 /// Error type for CypherEngine operations.
 #[derive(Debug)]
@@ -89,9 +98,169 @@ impl From<EngineError> for CypherError {
 /// ```
 pub struct CypherEngine {
     graph: graph::Graph,
+    options: ExecutionOptions,
+    cache: Option<Mutex<engine::QueryCache>>,
 }
 
 impl CypherEngine {
+    fn from_graph(graph: graph::Graph) -> Self {
+        Self {
+            graph,
+            options: ExecutionOptions::default(),
+            cache: None,
+        }
+    }
+
+    /// Cache the results of up to `capacity` distinct queries.
+    ///
+    /// [`CypherEngine::execute`] returns a cached result when the same query
+    /// string runs again, skipping parsing and execution; the least recently
+    /// used entry is evicted when the cache is full. Failed queries are not
+    /// cached. The cache is cleared by [`CypherEngine::execute_mut`] and by
+    /// the option setters, so results never outlive the graph they came from.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::CypherEngine;
+    /// use serde_json::json;
+    ///
+    /// let data = json!({"users": [{"id": "1"}, {"id": "2"}]});
+    /// let mut engine = CypherEngine::from_json_auto(&data).unwrap().with_query_cache(16);
+    /// let count = "MATCH (u:users) RETURN COUNT(u)";
+    /// assert_eq!(engine.execute(count).unwrap().get_single_value().unwrap(), 2);
+    ///
+    /// engine.execute_mut("MATCH (u:users) WHERE u.id = \"1\" DETACH DELETE u").unwrap();
+    /// assert_eq!(engine.execute(count).unwrap().get_single_value().unwrap(), 1);
+    /// ```
+    pub fn with_query_cache(mut self, capacity: usize) -> Self {
+        self.cache = Some(Mutex::new(engine::QueryCache::new(capacity)));
+        self
+    }
+
+    fn clear_cache(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            cache.get_mut().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+    }
+
+    /// Limit the number of intermediate rows a query may produce.
+    ///
+    /// Queries whose matching step exceeds `max_rows` bindings abort with
+    /// an [`EngineError::ExecutionError`] instead of exhausting memory.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::CypherEngine;
+    /// use serde_json::json;
+    ///
+    /// let data = json!({"users": [{"id": "1"}, {"id": "2"}]});
+    /// let engine = CypherEngine::from_json_auto(&data).unwrap().with_max_rows(4);
+    /// assert!(engine.execute("MATCH (a), (b) RETURN a.id, b.id").is_err());
+    /// ```
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.options.max_rows = Some(max_rows);
+        self.clear_cache();
+        self
+    }
+
+    /// Cap variable-length relationships at `depth` hops.
+    ///
+    /// Unbounded patterns such as `[*]`, and ranges reaching further than
+    /// `depth`, stop there. The default cap is
+    /// [`ExecutionOptions::DEFAULT_MAX_PATH_DEPTH`] (10). See
+    /// [`CypherEngine::with_strict_path_depth`] to fail such queries instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::CypherEngine;
+    /// use serde_json::json;
+    ///
+    /// let data = json!({"items": [
+    ///     {"id": "a", "next": ["b"]}, {"id": "b", "next": ["c"]}, {"id": "c"}
+    /// ]});
+    /// let engine = CypherEngine::from_json_auto(&data).unwrap().with_max_path_depth(1);
+    /// let result = engine
+    ///     .execute("MATCH (a:items {id: \"a\"})-[:next*]->(b) RETURN b.id")
+    ///     .unwrap();
+    /// assert_eq!(result.rows.len(), 1);
+    /// ```
+    pub fn with_max_path_depth(mut self, depth: usize) -> Self {
+        self.options.max_path_depth = Some(depth);
+        self.clear_cache();
+        self
+    }
+
+    /// Fail queries whose variable-length patterns would go past the depth
+    /// cap, rather than stopping there silently.
+    pub fn with_strict_path_depth(mut self, enabled: bool) -> Self {
+        self.options.strict_path_depth = enabled;
+        self.clear_cache();
+        self
+    }
+
+    /// Make `CONTAINS`, `STARTS WITH` and `ENDS WITH` ignore case.
+    ///
+    /// Both operands are lowercased before comparing. The default is
+    /// case-sensitive, as in Cypher.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::CypherEngine;
+    /// use serde_json::json;
+    ///
+    /// let data = json!({"users": [{"id": "1", "name": "Alice"}]});
+    /// let engine = CypherEngine::from_json_auto(&data)
+    ///     .unwrap()
+    ///     .with_case_insensitive_text(true);
+    /// let result = engine
+    ///     .execute("MATCH (u:users) WHERE u.name STARTS WITH \"al\" RETURN u.id")
+    ///     .unwrap();
+    /// assert_eq!(result.rows.len(), 1);
+    /// ```
+    pub fn with_case_insensitive_text(mut self, enabled: bool) -> Self {
+        self.options.case_insensitive_text = enabled;
+        self.clear_cache();
+        self
+    }
+
+    /// Make a custom scalar function callable from queries as `name(...)`.
+    ///
+    /// The function receives its evaluated arguments and may be used
+    /// anywhere a built-in can: `WHERE`, `WITH`, `RETURN` and `SET`. Names
+    /// are case-insensitive, and built-in functions are looked up first, so
+    /// a custom function can't shadow one. An error returned by the function
+    /// fails the query when all its arguments are literals and evaluates to
+    /// `null` otherwise, as for built-ins.
+    ///
+    /// The function must be `Send + Sync`: the engine may be shared between
+    /// threads, which then call it concurrently.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::CypherEngine;
+    /// use serde_json::{Value, json};
+    ///
+    /// let data = json!({"users": [{"id": "1", "age": 30}]});
+    /// let mut engine = CypherEngine::from_json_auto(&data).unwrap();
+    /// engine.register_function("double", |args| {
+    ///     Ok(args[0].as_f64().map_or(Value::Null, |n| json!(n * 2.0)))
+    /// });
+    /// let result = engine.execute("MATCH (u:users) RETURN double(u.age) AS d").unwrap();
+    /// assert_eq!(result.rows[0]["d"], json!(60.0));
+    /// ```
+    pub fn register_function<F>(&mut self, name: &str, function: F)
+    where
+        F: Fn(&[Value]) -> engine::FunctionResult<Value> + Send + Sync + 'static,
+    {
+        self.options.functions.register(name, function);
+        self.clear_cache();
+    }
+
     /// Create a new CypherEngine from JSON data with automatic schema detection.
     ///
     /// This method automatically analyzes the JSON structure and infers the
@@ -125,7 +294,7 @@ impl CypherEngine {
             .unwrap_or("Root");
         let graph = build_graph_from_root_object(json, label)
             .map_err(|e| CypherError::GraphBuild(e.to_string()))?;
-        Ok(Self { graph })
+        Ok(Self::from_graph(graph))
     }
 
     /// Create a new CypherEngine from JSON data with a custom root label.
@@ -154,7 +323,187 @@ impl CypherEngine {
         use engine::storage::json::build_graph_from_root_object;
         let graph = build_graph_from_root_object(json, label)
             .map_err(|e| CypherError::GraphBuild(e.to_string()))?;
-        Ok(Self { graph })
+        Ok(Self::from_graph(graph))
+    }
+
+    /// Create a new CypherEngine from JSON data with a custom root label and
+    /// graph building options.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::{CypherEngine, GraphConfig};
+    /// use serde_json::json;
+    ///
+    /// let data = json!({"users": [{"id": "1"}, {"name": "unkeyed"}]});
+    ///
+    /// let strict = GraphConfig::default().with_auto_id(false);
+    /// assert!(CypherEngine::from_json_with_config(&data, "Root", &strict).is_err());
+    ///
+    /// let engine =
+    ///     CypherEngine::from_json_with_config(&data, "Root", &GraphConfig::default()).unwrap();
+    /// let result = engine.execute("MATCH (u:users) RETURN COUNT(u)").unwrap();
+    /// assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+    /// ```
+    pub fn from_json_with_config(
+        json: &Value,
+        label: &str,
+        config: &GraphConfig,
+    ) -> std::result::Result<Self, CypherError> {
+        let graph = engine::storage::json::build_graph_with_config(json, label, config)
+            .map_err(|e| CypherError::GraphBuild(e.to_string()))?;
+        Ok(Self::from_graph(graph))
+    }
+
+    /// Create a new CypherEngine from any serde-serializable value.
+    ///
+    /// The value is serialized to JSON and must produce an object; it is then
+    /// loaded like [`CypherEngine::from_json_with_config`] with a `Root` label.
+    /// Serialization failures are reported as [`CypherError::GraphBuild`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::{CypherEngine, GraphConfig};
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct User {
+    ///     id: String,
+    ///     age: i64,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Dataset {
+    ///     users: Vec<User>,
+    /// }
+    ///
+    /// let data = Dataset {
+    ///     users: vec![User { id: "1".into(), age: 30 }, User { id: "2".into(), age: 25 }],
+    /// };
+    /// let engine = CypherEngine::from_serializable(&data, &GraphConfig::default()).unwrap();
+    /// let result = engine.execute("MATCH (u:users) RETURN SUM(u.age)").unwrap();
+    /// assert_eq!(result.get_single_value().unwrap().as_i64(), Some(55));
+    /// ```
+    pub fn from_serializable<T: serde::Serialize>(
+        value: &T,
+        config: &GraphConfig,
+    ) -> std::result::Result<Self, CypherError> {
+        let json = serde_json::to_value(value)
+            .map_err(|e| CypherError::GraphBuild(format!("Serialization failed: {}", e)))?;
+        Self::from_json_with_config(&json, "Root", config)
+    }
+
+    /// Create a new CypherEngine from newline-delimited JSON (NDJSON).
+    ///
+    /// Each non-blank line must be a JSON object and becomes one node. The
+    /// lines are gathered into a `nodes` array under a `Root` node, so nodes
+    /// are labeled `nodes` unless they carry a `type`, `kind` or `label`
+    /// field, and relation arrays between them are resolved as usual.
+    /// A malformed line is reported with its 1-based line number.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::{CypherEngine, GraphConfig};
+    ///
+    /// let ndjson = r#"{"id": "1", "type": "User", "friends": ["2"]}
+    /// {"id": "2", "type": "User", "friends": []}
+    /// "#;
+    ///
+    /// let engine = CypherEngine::from_ndjson_str(ndjson, &GraphConfig::default()).unwrap();
+    /// let result = engine
+    ///     .execute("MATCH (a:User)-[:friends]->(b:User) RETURN b.id")
+    ///     .unwrap();
+    /// assert_eq!(result.rows[0]["b.id"], "2");
+    /// ```
+    pub fn from_ndjson_str(
+        ndjson: &str,
+        config: &GraphConfig,
+    ) -> std::result::Result<Self, CypherError> {
+        let mut nodes = Vec::new();
+        for (line_idx, line) in ndjson.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let value: Value = serde_json::from_str(line).map_err(|e| {
+                CypherError::GraphBuild(format!("NDJSON line {}: {}", line_idx + 1, e))
+            })?;
+            if !value.is_object() {
+                return Err(CypherError::GraphBuild(format!(
+                    "NDJSON line {}: expected a JSON object",
+                    line_idx + 1
+                )));
+            }
+            nodes.push(value);
+        }
+
+        let mut root = serde_json::Map::new();
+        root.insert("nodes".to_string(), Value::Array(nodes));
+        Self::from_json_with_config(&Value::Object(root), "Root", config)
+    }
+
+    /// Create a new CypherEngine from CSV text with a header line.
+    ///
+    /// Each row becomes one node; `config` names the id, label and relation
+    /// columns (see [`CsvConfig`]). Relation cells list related row ids,
+    /// separated by `;` by default. Malformed rows are reported as
+    /// [`CypherError::GraphBuild`] with their line number.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::{CsvConfig, CypherEngine};
+    ///
+    /// let csv = "id,name,friends\n1,Alice,2;3\n2,Bob,1\n3,Carol,\n";
+    /// let config = CsvConfig::default()
+    ///     .with_default_label("User")
+    ///     .with_relation_column("friends");
+    ///
+    /// let engine = CypherEngine::from_csv_str(csv, &config).unwrap();
+    /// let result = engine
+    ///     .execute("MATCH (a:User {name: \"Alice\"})-[:friends]->(b) RETURN b.name")
+    ///     .unwrap();
+    /// assert_eq!(result.rows.len(), 2);
+    /// ```
+    pub fn from_csv_str(csv: &str, config: &CsvConfig) -> std::result::Result<Self, CypherError> {
+        let graph = engine::storage::csv::build_graph_from_csv(csv, config)
+            .map_err(|e| CypherError::GraphBuild(e.to_string()))?;
+        Ok(Self::from_graph(graph))
+    }
+
+    /// Create a new CypherEngine from the graph loaded by any storage backend,
+    /// executing queries with `options`.
+    ///
+    /// The backend's own configuration decides how the graph is built, e.g.
+    /// [`JsonStorage::with_config`] or [`CsvStorage::with_config`]. Load
+    /// failures are reported as [`CypherError::GraphBuild`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::graph::Node;
+    /// use cypher_rs::{CypherEngine, ExecutionOptions, MemoryStorageBuilder};
+    /// use serde_json::json;
+    ///
+    /// let storage = MemoryStorageBuilder::new()
+    ///     .add_node(Node::new("1", Some("User".to_string()), json!({"age": 30})))
+    ///     .build();
+    /// let engine = CypherEngine::from_storage(&storage, ExecutionOptions::default()).unwrap();
+    /// let result = engine.execute("MATCH (u:User) RETURN u.age").unwrap();
+    /// assert_eq!(result.rows[0]["u.age"], 30);
+    /// ```
+    pub fn from_storage(
+        storage: &dyn SyncStorage,
+        options: ExecutionOptions,
+    ) -> std::result::Result<Self, CypherError> {
+        let graph = storage
+            .load_graph_sync()
+            .map_err(|e| CypherError::GraphBuild(e.to_string()))?;
+        Ok(Self {
+            options,
+            ..Self::from_graph(graph)
+        })
     }
 
     /// Analyze JSON data and return schema detection information.
@@ -195,7 +544,199 @@ impl CypherEngine {
     /// let result = engine.execute("MATCH (u) RETURN u.id, u.role").unwrap();
     /// ```
     pub fn execute(&self, query: &str) -> Result<QueryResult> {
-        engine::execute(query, &self.graph)
+        self.cached(query, || {
+            engine::execute_with_options(query, &self.graph, &self.options)
+        })
+    }
+
+    /// Execute a query and report how many rows it has before `SKIP`/`LIMIT`.
+    ///
+    /// The count is stored in [`QueryResult::total_count`] and covers rows
+    /// after WHERE filtering and aggregation, so one query gives both a page
+    /// and the total for pagination. Results are not cached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use cypher_rs::CypherEngine;
+    /// # use serde_json::json;
+    /// # let data = json!({"users": [{"id": "1"}, {"id": "2"}, {"id": "3"}]});
+    /// let engine = CypherEngine::from_json_auto(&data).unwrap();
+    /// let page = engine
+    ///     .execute_with_total("MATCH (u:users) RETURN u.id ORDER BY u.id LIMIT 2")
+    ///     .unwrap();
+    /// assert_eq!(page.row_count(), 2);
+    /// assert_eq!(page.total_count, Some(3));
+    /// ```
+    pub fn execute_with_total(&self, query: &str) -> Result<QueryResult> {
+        let options = ExecutionOptions {
+            total_count: true,
+            ..self.options.clone()
+        };
+        engine::execute_with_options(query, &self.graph, &options)
+    }
+
+    /// Execute a query and write its rows to `writer` as NDJSON, one JSON
+    /// object per line, returning the number of rows written.
+    ///
+    /// Plain projections are written row by row as they are projected,
+    /// without collecting a [`QueryResult`]; queries with aggregates,
+    /// `DISTINCT` or `ORDER BY` still gather their rows first. The query
+    /// cache is neither read nor filled. Errors from the query or from
+    /// writing are returned; rows written before a write error stay written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use cypher_rs::CypherEngine;
+    /// # use serde_json::json;
+    /// # let data = json!({"users": [{"id": "1"}, {"id": "2"}]});
+    /// let engine = CypherEngine::from_json_auto(&data).unwrap();
+    /// let mut out = Vec::new();
+    /// let rows = engine
+    ///     .execute_to_writer("MATCH (u:users) RETURN u.id", &mut out)
+    ///     .unwrap();
+    /// assert_eq!(rows, 2);
+    /// assert_eq!(String::from_utf8(out).unwrap().lines().count(), 2);
+    /// ```
+    pub fn execute_to_writer(
+        &self,
+        query: &str,
+        writer: &mut impl std::io::Write,
+    ) -> Result<usize> {
+        engine::execute_to_writer(query, &self.graph, &self.options, writer)
+    }
+
+    /// Parse a query once so it can be run repeatedly with [`CypherEngine::run`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use cypher_rs::CypherEngine;
+    /// # use serde_json::json;
+    /// # let data = json!({"users": [{"id": "1"}, {"id": "2"}]});
+    /// let engine = CypherEngine::from_json_auto(&data).unwrap();
+    /// let count = engine.prepare("MATCH (u:users) RETURN COUNT(u)").unwrap();
+    /// for _ in 0..3 {
+    ///     let result = engine.run(&count).unwrap();
+    ///     assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+    /// }
+    /// ```
+    pub fn prepare(&self, query: &str) -> Result<CompiledQuery> {
+        CompiledQuery::new(query)
+    }
+
+    /// Execute a query prepared with [`CypherEngine::prepare`], skipping parsing.
+    ///
+    /// Behaves like [`CypherEngine::execute`] on the query's source text,
+    /// including the query cache.
+    pub fn run(&self, compiled: &CompiledQuery) -> Result<QueryResult> {
+        self.cached(compiled.source(), || {
+            compiled.execute(&self.graph, &self.options)
+        })
+    }
+
+    /// Return the cached result for `query`, or compute and cache it.
+    fn cached(
+        &self,
+        query: &str,
+        execute: impl FnOnce() -> Result<QueryResult>,
+    ) -> Result<QueryResult> {
+        let Some(cache) = &self.cache else {
+            return execute();
+        };
+
+        let lock = || cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(result) = lock().get(query) {
+            return Ok(result);
+        }
+        let result = execute()?;
+        lock().insert(query, result.clone());
+        Ok(result)
+    }
+
+    /// Execute a Cypher query that may mutate the graph.
+    ///
+    /// Supports `SET`, `DELETE` and `DETACH DELETE` in addition to everything
+    /// accepted by [`CypherEngine::execute`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use cypher_rs::CypherEngine;
+    /// # use serde_json::json;
+    /// # let data = json!({"users": [{"id": "1"}, {"id": "2"}]});
+    /// let mut engine = CypherEngine::from_json_auto(&data).unwrap();
+    /// engine
+    ///     .execute_mut("MATCH (u:users) WHERE u.id = \"1\" DETACH DELETE u")
+    ///     .unwrap();
+    /// let result = engine.execute("MATCH (u:users) RETURN COUNT(u)").unwrap();
+    /// assert_eq!(result.get_single_value().unwrap().as_i64(), Some(1));
+    /// ```
+    pub fn execute_mut(&mut self, query: &str) -> Result<QueryResult> {
+        self.clear_cache();
+        engine::execute_mut(query, &mut self.graph, &self.options)
+    }
+
+    /// Execute each query of a semicolon-separated script in order.
+    ///
+    /// Semicolons inside string literals don't split queries. Every query
+    /// runs as with [`CypherEngine::execute_mut`], so later queries see
+    /// the changes of earlier ones; a failing query doesn't stop the rest.
+    /// Returns one result per non-empty query.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use cypher_rs::CypherEngine;
+    /// # use serde_json::json;
+    /// # let data = json!({"users": [{"id": "1"}, {"id": "2"}]});
+    /// let mut engine = CypherEngine::from_json_auto(&data).unwrap();
+    /// let results = engine.execute_script(
+    ///     "MATCH (u:users) WHERE u.id = \"1\" SET u.vip = \"yes\";
+    ///      MATCH (u:users) WHERE u.vip = \"yes\" RETURN u.id",
+    /// );
+    /// assert_eq!(results.len(), 2);
+    /// assert_eq!(results[1].as_ref().unwrap().rows[0]["u.id"], "1");
+    /// ```
+    pub fn execute_script(&mut self, script: &str) -> Vec<Result<QueryResult>> {
+        parser::split_queries(script)
+            .into_iter()
+            .map(|query| self.execute_mut(query))
+            .collect()
+    }
+
+    /// Apply a batch of queries atomically.
+    ///
+    /// `f` runs its queries against a copy of the graph. If it returns
+    /// `Ok`, the copy replaces the engine's graph; if it returns an error
+    /// or panics, the engine's graph is left exactly as it was.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use cypher_rs::CypherEngine;
+    /// # use serde_json::json;
+    /// # let data = json!({"users": [{"id": "1"}, {"id": "2"}]});
+    /// let mut engine = CypherEngine::from_json_auto(&data).unwrap();
+    /// let result = engine.transaction(|txn| {
+    ///     txn.execute_mut("MATCH (u:users) WHERE u.id = \"1\" SET u.vip = \"yes\"")?;
+    ///     txn.execute_mut("MATCH (u:users) WHERE u.id = \"2\" DELETE u")
+    /// });
+    /// // The second DELETE fails (u is still connected), so nothing changed
+    /// assert!(result.is_err());
+    /// let vip = engine.execute("MATCH (u:users) WHERE u.vip = \"yes\" RETURN COUNT(u)").unwrap();
+    /// assert_eq!(vip.get_single_value().unwrap().as_i64(), Some(0));
+    /// ```
+    pub fn transaction<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Transaction<'_>) -> Result<T>,
+    {
+        let mut txn = Transaction::new(&self.graph, &self.options);
+        let value = f(&mut txn)?;
+        self.graph = txn.into_graph();
+        self.clear_cache();
+        Ok(value)
     }
 
     /// Get a reference to the underlying graph.
@@ -203,6 +744,28 @@ impl CypherEngine {
         &self.graph
     }
 
+    /// Export the graph as a JSON document shaped like the one it was
+    /// loaded from with `config`, including changes made by queries.
+    ///
+    /// See [`graph_to_json`](engine::storage::json::graph_to_json) for how
+    /// the document is rebuilt.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::{CypherEngine, GraphConfig};
+    /// use serde_json::json;
+    ///
+    /// let data = json!({"users": [{"id": "1", "age": 30}]});
+    /// let config = GraphConfig::default();
+    /// let mut engine = CypherEngine::from_json_with_config(&data, "Root", &config).unwrap();
+    /// engine.execute_mut("MATCH (u:users) SET u.age = 31").unwrap();
+    /// assert_eq!(engine.to_json(&config), json!({"users": [{"id": "1", "age": 31}]}));
+    /// ```
+    pub fn to_json(&self, config: &GraphConfig) -> Value {
+        engine::storage::json::graph_to_json(&self.graph, config)
+    }
+
     /// Get the Neo4j-style schema representation of this engine's graph.
     ///
     /// # Example
@@ -226,30 +789,49 @@ impl CypherEngine {
             return output;
         }
 
-        // Group nodes by label
-        let mut labels_by_label: std::collections::HashMap<String, Vec<&graph::Node>> =
-            std::collections::HashMap::new();
-        for node in &self.graph.nodes {
-            let label = node.label.as_ref().unwrap().clone();
-            labels_by_label.entry(label).or_default().push(node);
+        // Unlabeled nodes get their own entries, written `()` in patterns,
+        // so they are neither counted as a label nor merged with a real one
+        fn pattern_of(node: &graph::Node) -> String {
+            match node.label.as_deref() {
+                Some(label) => format!("(:{})", label),
+                None => "()".to_string(),
+            }
         }
+        let stats = self.graph.stats();
+        let unlabeled = stats.node_count - stats.nodes_per_label.values().sum::<usize>();
 
         output.push_str("Node Types:\n");
-        let mut label_names: Vec<String> = labels_by_label.keys().cloned().collect();
+        let mut label_names: Vec<&String> = stats.nodes_per_label.keys().collect();
         label_names.sort();
         for label in &label_names {
-            let count = labels_by_label.get(label).map(|v| v.len()).unwrap_or(0);
-            output.push_str(&format!("  (:{} {} nodes)\n", label, count));
+            output.push_str(&format!(
+                "  (:{} {} nodes)\n",
+                label, stats.nodes_per_label[*label]
+            ));
         }
+        if unlabeled > 0 {
+            output.push_str(&format!("  (unlabeled {} nodes)\n", unlabeled));
+        }
+        output.push_str(&format!("  {} labels\n", stats.label_count()));
         output.push('\n');
 
         output.push_str("Properties:\n");
-        for label in &label_names {
-            if let Some(nodes) = labels_by_label.get(label)
-                && let Some(first_node) = nodes.first()
+        let mut entries: Vec<(Option<&str>, String)> = label_names
+            .iter()
+            .map(|label| (Some(label.as_str()), format!(":{}", label)))
+            .collect();
+        if unlabeled > 0 {
+            entries.push((None, "(unlabeled)".to_string()));
+        }
+        for (label, name) in entries {
+            if let Some(first_node) = self
+                .graph
+                .nodes
+                .iter()
+                .find(|n| n.label.as_deref() == label)
             {
                 let mut properties: Vec<String> = Vec::new();
-                if let Value::Object(obj) = &first_node.data {
+                if let Value::Object(obj) = &*first_node.data {
                     for (key, value) in obj {
                         let type_str = match value {
                             Value::String(_) => "STRING",
@@ -263,7 +845,7 @@ impl CypherEngine {
                     }
                 }
                 if !properties.is_empty() {
-                    output.push_str(&format!("  :{} {{{}}}\n", label, properties.join(", ")));
+                    output.push_str(&format!("  {} {{{}}}\n", name, properties.join(", ")));
                 }
             }
         }
@@ -272,51 +854,22 @@ impl CypherEngine {
         if !self.graph.edges.is_empty() {
             output.push_str("Relationship Types:\n");
 
-            let mut rel_types: std::collections::HashMap<
-                String,
-                (
-                    std::collections::HashSet<String>,
-                    std::collections::HashSet<String>,
-                ),
-            > = std::collections::HashMap::new();
-
+            // Edge counts per (type, from label, to label), sorted by type
+            let mut rel_patterns: std::collections::BTreeMap<(&str, String, String), usize> =
+                std::collections::BTreeMap::new();
             for edge in &self.graph.edges {
-                let from_label = self.graph.nodes[edge.from].label.as_ref().unwrap().clone();
-                let to_label = self.graph.nodes[edge.to].label.as_ref().unwrap().clone();
-
-                rel_types
-                    .entry(edge.rel_type.clone())
-                    .or_insert_with(|| {
-                        (
-                            std::collections::HashSet::new(),
-                            std::collections::HashSet::new(),
-                        )
-                    })
-                    .0
-                    .insert(from_label);
-                rel_types
-                    .entry(edge.rel_type.clone())
-                    .or_insert_with(|| {
-                        (
-                            std::collections::HashSet::new(),
-                            std::collections::HashSet::new(),
-                        )
-                    })
-                    .1
-                    .insert(to_label);
+                let from = pattern_of(&self.graph.nodes[edge.from]);
+                let to = pattern_of(&self.graph.nodes[edge.to]);
+                *rel_patterns
+                    .entry((edge.rel_type.as_str(), from, to))
+                    .or_default() += 1;
             }
 
-            let mut sorted_rels: Vec<_> = rel_types.into_iter().collect();
-            sorted_rels.sort_by(|a, b| a.0.cmp(&b.0));
-
-            for (rel_type, (from_labels, to_labels)) in sorted_rels {
-                let from: Vec<_> = from_labels.into_iter().collect();
-                let to: Vec<_> = to_labels.into_iter().collect();
-                for f in &from {
-                    for t in &to {
-                        output.push_str(&format!("  (:{})-[:{}]->(:{})\n", f, rel_type, t));
-                    }
-                }
+            for ((rel_type, from, to), count) in rel_patterns {
+                output.push_str(&format!(
+                    "  {}-[:{}]->{} [{} edges]\n",
+                    from, rel_type, to, count
+                ));
             }
         }
 
@@ -374,14 +927,14 @@ mod tests {
 
         let result = engine.execute("MATCH (u:users) RETURN u.id").unwrap();
         assert_eq!(result.rows.len(), 2);
-        assert_eq!(result.rows[0]["u.id"], 1);
-        assert_eq!(result.rows[1]["u.id"], 2);
+        assert_eq!(result.rows[0]["u.id"], "1");
+        assert_eq!(result.rows[1]["u.id"], "2");
 
         let result = engine
             .execute("MATCH (u:users) WHERE u.name = \"Alice\" RETURN u.id")
             .unwrap();
         assert_eq!(result.rows.len(), 1);
-        assert_eq!(result.rows[0]["u.id"], 1);
+        assert_eq!(result.rows[0]["u.id"], "1");
     }
 
     #[test]
@@ -403,48 +956,374 @@ mod tests {
     }
 
     #[test]
-    fn test_nested_json_path() {
+    fn test_size_of_array_property_ordered() {
         let data = json!({
-            "data": {
-                "users": [
-                    { "id": "1", "role": "admin" },
-                    { "id": "2", "role": "user" }
-                ]
-            }
+            "users": [
+                { "id": "1", "name": "Alice", "friends": ["2", "3"] },
+                { "id": "2", "name": "Bob", "friends": ["1"] },
+                { "id": "3", "name": "Charlie", "friends": [] }
+            ]
         });
 
         let engine = CypherEngine::from_json_auto(&data).unwrap();
-        let result = engine.execute("MATCH (u:users) RETURN COUNT(u)").unwrap();
-        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+
+        let result = engine
+            .execute(
+                "MATCH (u:users) RETURN u.id, size(u.friends) AS friend_count ORDER BY friend_count DESC",
+            )
+            .unwrap();
+        assert_eq!(result.columns, vec!["u.id", "friend_count"]);
+        assert_eq!(
+            result.rows,
+            vec![
+                json!({"u.id": "1", "friend_count": 2}),
+                json!({"u.id": "2", "friend_count": 1}),
+                json!({"u.id": "3", "friend_count": 0})
+            ]
+        );
     }
 
     #[test]
-    fn test_result_as_json() {
+    fn test_register_function() {
         let data = json!({
             "users": [
-                { "id": "1", "name": "Alice" },
-                { "id": "2", "name": "Bob" }
+                { "id": "1", "age": 30 },
+                { "id": "2", "age": 25 }
             ]
         });
 
-        let engine = CypherEngine::from_json_auto(&data).unwrap();
+        let mut engine = CypherEngine::from_json_auto(&data)
+            .unwrap()
+            .with_query_cache(4);
+        assert!(
+            engine
+                .execute("MATCH (u:users) RETURN double(u.age)")
+                .is_err()
+        );
+
+        engine.register_function("double", |args| match &args[0] {
+            Value::Number(n) if n.is_i64() => Ok(json!(n.as_i64().unwrap() * 2)),
+            Value::Number(n) => Ok(json!(n.as_f64().unwrap() * 2.0)),
+            Value::Null => Ok(Value::Null),
+            other => Err(engine::FunctionError::TypeError(
+                "double".to_string(),
+                format!("expected a number, got {}", other),
+            )),
+        });
 
         let result = engine
-            .execute("MATCH (u:users) RETURN u.id, u.name")
+            .execute("MATCH (u:users) WHERE Double(u.age) = 60 RETURN u.id, double(u.age) AS d")
             .unwrap();
-        let json_array = result.as_json_array();
+        assert_eq!(result.rows, vec![json!({"u.id": "1", "d": 60})]);
 
-        assert!(json_array.is_array());
-        assert_eq!(json_array.as_array().unwrap().len(), 2);
+        let err = engine
+            .execute("MATCH (u:users) RETURN double(\"x\")")
+            .unwrap_err();
+        assert!(err.to_string().contains("expected a number"), "{}", err);
     }
 
     #[test]
-    fn test_where_operators() {
+    fn test_optional_match_counts_ignore_nulls() {
         let data = json!({
-            "items": [
-                { "id": "1", "value": 10 },
-                { "id": "2", "value": 20 },
-                { "id": "3", "value": 30 }
+            "users": [
+                { "id": "1", "name": "Alice", "friends": ["2", "3"] },
+                { "id": "2", "name": "Bob", "friends": [] },
+                { "id": "3", "name": "Carol", "friends": ["1"] }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let result = engine
+            .execute(
+                "MATCH (u:users) OPTIONAL MATCH (u)-[:friends]->(f) RETURN u.id, COUNT(f) AS c",
+            )
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                json!({"u.id": "1", "c": 2}),
+                json!({"u.id": "2", "c": 0}),
+                json!({"u.id": "3", "c": 1})
+            ]
+        );
+
+        // A WHERE that rejects every match keeps the row with nulls
+        let result = engine
+            .execute(
+                "MATCH (u:users) OPTIONAL MATCH (u)-[:friends]->(f) WHERE f.name STARTS WITH \"C\" \
+                 RETURN u.id, f.name",
+            )
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                json!({"u.id": "1", "f.name": "Carol"}),
+                json!({"u.id": "2", "f.name": null}),
+                json!({"u.id": "3", "f.name": null})
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_start_node_counts_neighbors() {
+        let data = json!({
+            "users": [
+                { "id": "1", "name": "Alice", "friends": ["2", "3"] },
+                { "id": "2", "name": "Bob", "friends": ["1"] },
+                { "id": "3", "name": "Charlie", "friends": [] }
+            ]
+        });
+
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let result = engine
+            .execute("MATCH (u)-[:friends]->(v) RETURN u.name, COUNT(v) AS friends")
+            .unwrap();
+        assert_eq!(result.columns, vec!["u.name", "friends"]);
+        assert_eq!(
+            result.rows,
+            vec![
+                json!({"u.name": "Alice", "friends": 2}),
+                json!({"u.name": "Bob", "friends": 1})
+            ]
+        );
+
+        // Incoming direction groups by the friended user instead
+        let result = engine
+            .execute("MATCH (u)<-[:friends]-(v) RETURN u.name, COUNT(v)")
+            .unwrap();
+        assert_eq!(result.rows.len(), 3);
+    }
+
+    #[test]
+    fn test_top_n_roles_by_count() {
+        let roles = [
+            "admin", "user", "user", "guest", "user", "admin", "owner", "guest", "user", "admin",
+            "guest", "guest", "auditor",
+        ];
+        let users: Vec<Value> = roles
+            .iter()
+            .enumerate()
+            .map(|(i, role)| json!({ "id": i.to_string(), "role": role }))
+            .collect();
+        let engine = CypherEngine::from_json_auto(&json!({ "users": users })).unwrap();
+
+        let result = engine
+            .execute("MATCH (u:users) RETURN u.role, COUNT(u) AS c ORDER BY c DESC LIMIT 3")
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                json!({"u.role": "user", "c": 4}),
+                json!({"u.role": "guest", "c": 4}),
+                json!({"u.role": "admin", "c": 3})
+            ]
+        );
+
+        // LIMIT cuts after sorting: the first group seen ("admin") isn't first
+        let result = engine
+            .execute("MATCH (u:users) RETURN u.role, COUNT(u) AS c ORDER BY c DESC, u.role LIMIT 1")
+            .unwrap();
+        assert_eq!(result.rows, vec![json!({"u.role": "guest", "c": 4})]);
+    }
+
+    #[test]
+    fn test_nested_json_path() {
+        let data = json!({
+            "data": {
+                "users": [
+                    { "id": "1", "role": "admin" },
+                    { "id": "2", "role": "user" }
+                ]
+            }
+        });
+
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+        let result = engine.execute("MATCH (u:users) RETURN COUNT(u)").unwrap();
+        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+    }
+
+    #[test]
+    fn test_result_as_json() {
+        let data = json!({
+            "users": [
+                { "id": "1", "name": "Alice" },
+                { "id": "2", "name": "Bob" }
+            ]
+        });
+
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let result = engine
+            .execute("MATCH (u:users) RETURN u.id, u.name")
+            .unwrap();
+        let json_array = result.as_json_array();
+
+        assert!(json_array.is_array());
+        assert_eq!(json_array.as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_execute_to_writer() {
+        let data = json!({
+            "users": [
+                { "id": "1", "name": "Alice", "tags": ["a"] },
+                { "id": "2", "name": "Bob", "tags": [] }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let mut out = Vec::new();
+        let written = engine
+            .execute_to_writer(
+                "MATCH (u:users) RETURN u.id, u.tags ORDER BY u.id",
+                &mut out,
+            )
+            .unwrap();
+        assert_eq!(written, 2);
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                json!({"u.id": "1", "u.tags": ["a"]}),
+                json!({"u.id": "2", "u.tags": []}),
+            ]
+        );
+
+        // Paging applies while streaming; sorted and aggregated queries
+        // gather their rows first
+        let ndjson = |query: &str| {
+            let mut out = Vec::new();
+            engine.execute_to_writer(query, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(
+            ndjson("MATCH (u:users) RETURN u.id SKIP 1 LIMIT 5"),
+            "{\"u.id\":\"2\"}\n"
+        );
+        assert_eq!(
+            ndjson("MATCH (u:users) RETURN u.id ORDER BY u.id DESC LIMIT 1"),
+            "{\"u.id\":\"2\"}\n"
+        );
+        assert_eq!(
+            ndjson("MATCH (u:users) RETURN COUNT(u) AS n"),
+            "{\"n\":2}\n"
+        );
+
+        // The query cache is bypassed
+        let cached = CypherEngine::from_json_auto(&data)
+            .unwrap()
+            .with_query_cache(4);
+        cached
+            .execute_to_writer("MATCH (u:users) RETURN u.id", &mut Vec::new())
+            .unwrap();
+        assert!(cached.cache.as_ref().unwrap().lock().unwrap().is_empty());
+
+        // Write errors propagate
+        struct Broken;
+        impl std::io::Write for Broken {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk full"))
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let err = engine
+            .execute_to_writer("MATCH (u:users) RETURN u.id", &mut Broken)
+            .unwrap_err();
+        assert!(matches!(err, EngineError::Io(_)));
+    }
+
+    #[test]
+    fn test_anonymous_nodes_in_each_match_clause() {
+        let data = json!({
+            "users": [
+                { "id": "1", "friends": ["3"] },
+                { "id": "2", "likes": ["3"] },
+                { "id": "3" }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let result = engine
+            .execute("MATCH ()-[:friends]->(x) OPTIONAL MATCH ()-[:likes]->(z) RETURN x.id, z.id")
+            .unwrap();
+        assert_eq!(result.rows, vec![json!({"x.id": "3", "z.id": "3"})]);
+    }
+
+    #[test]
+    fn test_from_storage() {
+        let mut graph = graph::Graph::new();
+        for (id, age) in [("1", 30), ("2", 25)] {
+            graph.add_node(graph::Node::new(
+                id,
+                Some("User".to_string()),
+                json!({"id": id, "age": age}),
+            ));
+        }
+        graph.add_edge(graph::Edge::new(0, 1, "knows"));
+        let storage = MemoryStorage::from_graph(graph);
+        let engine = CypherEngine::from_storage(&storage, ExecutionOptions::default()).unwrap();
+        let result = engine
+            .execute("MATCH (a:User)-[:knows]->(b:User) RETURN a.id, b.age")
+            .unwrap();
+        assert_eq!(result.rows, vec![json!({"a.id": "1", "b.age": 25})]);
+
+        // The execution options apply to every query
+        let options = ExecutionOptions {
+            max_rows: Some(1),
+            ..ExecutionOptions::default()
+        };
+        let engine = CypherEngine::from_storage(&storage, options).unwrap();
+        assert!(engine.execute("MATCH (u:User) RETURN u.id").is_err());
+
+        // The backend's config applies, and load errors are reported
+        let data = json!({"users": [{ "id": "1", "friends": ["1", "9"] }]});
+        let storage = JsonStorage::from_value(data);
+        assert!(CypherEngine::from_storage(&storage, ExecutionOptions::default()).is_ok());
+        let strict = storage.with_config(GraphConfig::default().with_strict_relations(true));
+        assert!(matches!(
+            CypherEngine::from_storage(&strict, ExecutionOptions::default()),
+            Err(CypherError::GraphBuild(_))
+        ));
+    }
+
+    #[test]
+    fn test_execute_script() {
+        let data = json!({
+            "users": [
+                { "id": "1", "bio": "likes a;b" },
+                { "id": "2", "bio": "plain" }
+            ]
+        });
+        let mut engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let results = engine.execute_script(
+            "MATCH (u:users) WHERE u.bio = \"likes a;b\" RETURN u.id;
+             MATCH (u:users) RETURN COUNT(u);
+             MATCH (u:users) RETURN x;",
+        );
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().rows[0]["u.id"], "1");
+        assert_eq!(
+            results[1].as_ref().unwrap().get_single_value(),
+            Some(&json!(2))
+        );
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn test_where_operators() {
+        let data = json!({
+            "items": [
+                { "id": "1", "value": 10 },
+                { "id": "2", "value": 20 },
+                { "id": "3", "value": 30 }
             ]
         });
 
@@ -600,8 +1479,30 @@ mod tests {
         assert!(schema.contains("Graph Schema"));
         assert!(schema.contains("Node Types:"));
         assert!(schema.contains("(:users"));
+        assert!(schema.contains("  2 labels\n"));
         assert!(schema.contains("Relationship Types:"));
-        assert!(schema.contains("friends"));
+        assert!(schema.contains("  (:users)-[:friends]->(:users) [4 edges]\n"));
+        assert!(schema.contains("  (:Root)-[:users]->(:users) [3 edges]\n"));
+
+        // Unlabeled nodes are listed apart, and not merged with a real Node label
+        let mut graph = graph::Graph::new();
+        graph.add_node(graph::Node::new("1", None, json!({"name": "a"})));
+        graph.add_node(graph::Node::new("2", Some("User".to_string()), json!({})));
+        graph.add_node(graph::Node::new(
+            "3",
+            Some("Node".to_string()),
+            json!({"n": 1}),
+        ));
+        graph.add_edge(graph::Edge::new(0, 1, "knows"));
+        let stats = graph.stats();
+        let schema = CypherEngine::from_graph(graph).get_schema();
+        assert!(schema.contains("  (:Node 1 nodes)\n"));
+        assert!(schema.contains("  (unlabeled 1 nodes)\n"));
+        assert!(schema.contains(&format!("  {} labels\n", stats.label_count())));
+        assert!(schema.contains("  2 labels\n"));
+        assert!(schema.contains("  :Node {n: NUMBER}\n"));
+        assert!(schema.contains("  (unlabeled) {name: STRING}\n"));
+        assert!(schema.contains("  ()-[:knows]->(:User) [1 edges]\n"));
     }
 
     #[test]
@@ -637,6 +1538,539 @@ mod tests {
         assert!(pattern.contains(":users"));
     }
 
+    #[test]
+    fn test_from_ndjson_str() {
+        let ndjson = "{\"id\": \"1\", \"name\": \"Alice\"}\n\n{\"id\": \"2\", \"name\": \"Bob\"}\n";
+        let engine = CypherEngine::from_ndjson_str(ndjson, &GraphConfig::default()).unwrap();
+        let result = engine.execute("MATCH (n:nodes) RETURN COUNT(n)").unwrap();
+        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+
+        let err =
+            CypherEngine::from_ndjson_str("{\"id\": \"1\"}\n{oops\n", &GraphConfig::default())
+                .err()
+                .unwrap();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_from_csv_str() {
+        let csv = "id,name,age,friends\n1,Alice,30,2;3\n2,\"Bob, Jr.\",25,1\n3,Carol,41,\n";
+        let config = CsvConfig::default().with_relation_column("friends");
+        let engine = CypherEngine::from_csv_str(csv, &config).unwrap();
+
+        let result = engine
+            .execute("MATCH (a:rows)-[:friends]->(b) WHERE a.id = \"1\" RETURN b.name")
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![json!({"b.name": "Bob, Jr."}), json!({"b.name": "Carol"})]
+        );
+        let result = engine.execute("MATCH (u) RETURN SUM(u.age)").unwrap();
+        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(96));
+
+        let err = CypherEngine::from_csv_str("id,name\n1\n", &config)
+            .err()
+            .unwrap();
+        assert!(matches!(err, CypherError::GraphBuild(_)));
+    }
+
+    #[test]
+    fn test_with_collect_feeds_in() {
+        let data = json!({
+            "teams": [
+                { "id": "t1", "active": true },
+                { "id": "t2", "active": false },
+                { "id": "t3", "active": true }
+            ],
+            "members": [
+                { "id": "m1", "parent": "t1" },
+                { "id": "m2", "parent": "t2" },
+                { "id": "m3", "parent": "t3" },
+                { "id": "m4", "parent": "t1" }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let result = engine
+            .execute(
+                "MATCH (t:teams) WHERE t.active WITH COLLECT(t.id) AS ids \
+                 MATCH (m:members) WHERE m.parent IN ids RETURN m.id",
+            )
+            .unwrap();
+        let ids: Vec<&str> = result
+            .rows
+            .iter()
+            .map(|r| r["m.id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["m1", "m3", "m4"]);
+
+        let result = engine
+            .execute("MATCH (t:teams) WITH COLLECT(t.id) AS ids RETURN ids")
+            .unwrap();
+        assert_eq!(result.rows, vec![json!({"ids": ["t1", "t2", "t3"]})]);
+    }
+
+    #[test]
+    fn test_to_json_round_trip() {
+        let data = json!({
+            "id": "doc",
+            "title": "Example",
+            "users": [
+                { "id": 1, "name": "Alice", "friends": [2, 3] },
+                { "id": 2, "name": "Bob", "friends": [1] },
+                { "id": 3, "name": "Carol", "friends": [] }
+            ],
+            "teams": [
+                { "id": "t1", "members": [1, 2], "tags": ["x"] }
+            ]
+        });
+        let config = GraphConfig::default();
+        let mut engine = CypherEngine::from_json_with_config(&data, "Root", &config).unwrap();
+        assert_eq!(engine.to_json(&config), data);
+
+        engine
+            .execute_mut("MATCH (a:users)-[r:friends]->(b:users) WHERE a.name = \"Alice\" AND b.name = \"Carol\" DELETE r")
+            .unwrap();
+        let exported = engine.to_json(&config);
+        assert_eq!(exported["users"][0]["friends"], json!([2]));
+        assert_eq!(exported["teams"], data["teams"]);
+
+        let reloaded = CypherEngine::from_json_with_config(&exported, "Root", &config).unwrap();
+        let diff = engine.graph().diff(reloaded.graph());
+        assert!(diff.added_edges.is_empty() && diff.removed_edges.is_empty());
+    }
+
+    #[test]
+    fn test_multi_hop_per_hop_types() {
+        let data = json!({
+            "employees": [
+                { "id": "ceo", "manages": ["lead1", "lead2"], "works_on": ["strategy"] },
+                { "id": "lead1", "manages": ["dev1"], "works_on": ["api"] },
+                { "id": "lead2", "manages": [], "works_on": ["web", "api"] },
+                { "id": "dev1", "manages": [], "works_on": ["web"] }
+            ],
+            "projects": [
+                { "id": "strategy" },
+                { "id": "api" },
+                { "id": "web" }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let pairs = |query: &str| -> Vec<(String, String)> {
+            let mut pairs: Vec<_> = engine
+                .execute(query)
+                .unwrap()
+                .rows
+                .iter()
+                .map(|r| {
+                    (
+                        r["a.id"].as_str().unwrap().to_string(),
+                        r["c.id"].as_str().unwrap().to_string(),
+                    )
+                })
+                .collect();
+            pairs.sort();
+            pairs
+        };
+        let pair = |a: &str, c: &str| (a.to_string(), c.to_string());
+
+        // The projects of each manager's direct reports; the managers' own
+        // projects and two-hop `manages` chains don't leak in
+        assert_eq!(
+            pairs("MATCH (a)-[:manages]->(b)-[:works_on]->(c) RETURN a.id, c.id"),
+            vec![
+                pair("ceo", "api"),
+                pair("ceo", "api"),
+                pair("ceo", "web"),
+                pair("lead1", "web"),
+            ]
+        );
+        assert_eq!(
+            pairs("MATCH (a)-[:manages]->(b)-[:manages]->(c) RETURN a.id, c.id"),
+            vec![pair("ceo", "dev1")]
+        );
+        assert!(pairs("MATCH (a)-[:works_on]->(b)-[:manages]->(c) RETURN a.id, c.id").is_empty());
+    }
+
+    #[test]
+    fn test_undirected_relationship_direction() {
+        let data = json!({
+            "users": [
+                { "id": "1", "friends": ["2", "3"] },
+                { "id": "2", "friends": ["1"] },
+                { "id": "3", "friends": [] }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let result = engine
+            .execute("MATCH (a)-[r:friends]-(b) WHERE a.id = \"1\" RETURN b.id, r")
+            .unwrap();
+        let mut matches: Vec<(String, String, String)> = result
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row["b.id"].as_str().unwrap().to_string(),
+                    row["r"]["start"].as_str().unwrap().to_string(),
+                    row["r"]["direction"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        matches.sort();
+        let m = |b: &str, start: &str, direction: &str| {
+            (b.to_string(), start.to_string(), direction.to_string())
+        };
+
+        // The mutual friendship matches both of its edges; the one-way
+        // friendship only goes out from 1
+        assert_eq!(
+            matches,
+            vec![
+                m("2", "1", "forward"),
+                m("2", "2", "backward"),
+                m("3", "1", "forward"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_return_boolean_expressions() {
+        let data = json!({
+            "users": [
+                { "id": "1", "age": 30 },
+                { "id": "2", "age": 12 },
+                { "id": "3", "age": 18 },
+                { "id": "4", "age": 70 }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let result = engine
+            .execute(
+                "MATCH (u:users) RETURN u.id, u.age >= 18 AS is_adult, u.age < 13 OR u.id = \"4\"",
+            )
+            .unwrap();
+        assert_eq!(
+            result.columns,
+            vec!["u.id", "is_adult", "u.age < 13 OR u.id = \"4\""]
+        );
+        let flags: Vec<(bool, bool)> = result
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row["is_adult"].as_bool().unwrap(),
+                    row[&result.columns[2]].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            flags,
+            vec![(true, false), (false, true), (true, false), (true, true)]
+        );
+    }
+
+    #[test]
+    fn test_sum_of_products() {
+        let data = json!({
+            "orders": [
+                { "id": "1", "customer": "a", "price": 3, "quantity": 2 },
+                { "id": "2", "customer": "a", "price": 5, "quantity": 1 },
+                { "id": "3", "customer": "b", "price": 1.5, "quantity": 4 },
+                { "id": "4", "customer": "b", "price": 2 }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let result = engine
+            .execute("MATCH (o:orders) RETURN SUM(o.price * o.quantity)")
+            .unwrap();
+        assert_eq!(result.columns, vec!["SUM(o.price * o.quantity)"]);
+        assert_eq!(result.get_single_value(), Some(&json!(17.0)));
+
+        // `*` binds tighter than `+`; a missing quantity makes the row null
+        let result = engine
+            .execute(
+                "MATCH (o:orders) RETURN o.customer, SUM(o.price * o.quantity + 1) AS total \
+                 ORDER BY o.customer",
+            )
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                json!({"o.customer": "a", "total": 13}),
+                json!({"o.customer": "b", "total": 7.0}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_distinct() {
+        let data = json!({
+            "users": [
+                { "id": "1", "role": "dev" },
+                { "id": "2", "role": "ops" },
+                { "id": "3", "role": "dev" },
+                { "id": "4" },
+                { "id": "5", "role": "qa" },
+                { "id": "6", "role": "ops" }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let result = engine
+            .execute(
+                "MATCH (u:users) RETURN COLLECT(DISTINCT u.role) AS roles, COUNT(DISTINCT u.role)",
+            )
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![json!({"roles": ["dev", "ops", "qa"], "COUNT(DISTINCT u.role)": 3})]
+        );
+    }
+
+    #[test]
+    fn test_with_filters_on_aggregate_alias() {
+        let mut users = vec![
+            json!({ "id": "1", "friends": (2..=12).map(|i| i.to_string()).collect::<Vec<_>>() }),
+            json!({ "id": "2", "friends": ["1", "3", "4"] }),
+            json!({ "id": "3", "friends": ["1", "2"] }),
+        ];
+        users.extend((4..=12).map(|i| json!({ "id": i.to_string(), "friends": [] })));
+        let engine = CypherEngine::from_json_auto(&json!({ "users": users })).unwrap();
+
+        // `fc` compares as a number, so 11 passes `>= 3`
+        let result = engine
+            .execute(
+                "MATCH (u)-[:friends]->(f) WITH u, COUNT(f) AS fc WHERE fc >= 3 RETURN u.id, fc",
+            )
+            .unwrap();
+        assert_eq!(result.columns, vec!["u.id", "fc"]);
+        assert_eq!(
+            result.rows,
+            vec![
+                json!({"u.id": "1", "fc": 11}),
+                json!({"u.id": "2", "fc": 3})
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_nodes_by_id_ignores_source_order() {
+        let forward = json!({
+            "users": [
+                { "id": "1", "friends": ["2", "10"] },
+                { "id": "2", "friends": ["10", "1"] },
+                { "id": "10", "friends": ["1"] }
+            ]
+        });
+        let backward = json!({
+            "users": [
+                { "id": "10", "friends": ["1"] },
+                { "id": "2", "friends": ["1", "10"] },
+                { "id": "1", "friends": ["10", "2"] }
+            ]
+        });
+        let config = GraphConfig::default().with_sort_nodes_by_id(true);
+        let run = |data: &Value| {
+            let engine = CypherEngine::from_json_with_config(data, "Root", &config).unwrap();
+            engine
+                .execute("MATCH (u:users)-[:friends]->(f) RETURN u.id, f.id")
+                .unwrap()
+                .rows
+        };
+
+        let rows = run(&forward);
+        assert_eq!(rows, run(&backward));
+        let pairs: Vec<(&str, &str)> = rows
+            .iter()
+            .map(|r| (r["u.id"].as_str().unwrap(), r["f.id"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("1", "2"),
+                ("1", "10"),
+                ("2", "1"),
+                ("2", "10"),
+                ("10", "1")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_max_path_depth() {
+        let items: Vec<Value> = (0..20)
+            .map(|i| json!({ "id": i.to_string(), "next": [(i + 1).to_string()] }))
+            .collect();
+        let data = json!({ "items": items });
+        let count = |engine: &CypherEngine, range: &str| {
+            engine
+                .execute(&format!(
+                    "MATCH (a:items {{id: \"0\"}})-[:next{}]->(b) RETURN b.id",
+                    range
+                ))
+                .map(|result| result.rows.len())
+        };
+
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+        assert_eq!(count(&engine, "*").unwrap(), 10);
+
+        let engine = engine.with_max_path_depth(3);
+        assert_eq!(count(&engine, "*").unwrap(), 3);
+        // Explicit ranges are capped too
+        assert_eq!(count(&engine, "*1..5").unwrap(), 3);
+        assert_eq!(count(&engine, "*1..2").unwrap(), 2);
+
+        let strict = engine.with_strict_path_depth(true);
+        let err = count(&strict, "*").unwrap_err();
+        assert!(err.to_string().contains("max_path_depth (3)"), "{}", err);
+        assert!(count(&strict, "*1..5").is_err());
+        assert_eq!(count(&strict, "*1..3").unwrap(), 3);
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_on_error() {
+        let data = json!({
+            "users": [
+                { "id": "1", "name": "Alice" },
+                { "id": "2", "name": "Bob" }
+            ]
+        });
+        let mut engine = CypherEngine::from_json_auto(&data)
+            .unwrap()
+            .with_query_cache(4);
+        let count = "MATCH (u:users) WHERE u.name = \"Carol\" RETURN COUNT(u)";
+        let carols = |engine: &CypherEngine| {
+            engine
+                .execute(count)
+                .unwrap()
+                .get_single_value()
+                .unwrap()
+                .as_i64()
+        };
+        assert_eq!(carols(&engine), Some(0));
+
+        let result = engine.transaction(|txn| {
+            txn.execute_mut("MATCH (u:users) WHERE u.id = \"1\" SET u.name = \"Carol\"")?;
+            assert_eq!(
+                txn.execute(count)?.get_single_value().unwrap().as_i64(),
+                Some(1)
+            );
+            txn.execute_mut("MATCH (u:users) RETURN nope(u)")
+        });
+        assert!(result.is_err());
+        assert_eq!(carols(&engine), Some(0));
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = engine.transaction(|txn| -> Result<()> {
+                txn.execute_mut("MATCH (u:users) SET u.name = \"Carol\"")?;
+                panic!("boom")
+            });
+        }));
+        assert!(panicked.is_err());
+        assert_eq!(carols(&engine), Some(0));
+
+        let updated = engine
+            .transaction(|txn| {
+                txn.execute_mut("MATCH (u:users) WHERE u.id = \"1\" SET u.name = \"Carol\"")?;
+                txn.execute_mut("MATCH (u:users) WHERE u.id = \"2\" SET u.name = \"Carol\"")?;
+                Ok(txn.graph().node_count())
+            })
+            .unwrap();
+        assert_eq!(updated, 3);
+        assert_eq!(carols(&engine), Some(2));
+    }
+
+    #[test]
+    fn test_execute_with_total() {
+        let data = json!({
+            "users": [
+                { "id": "1", "role": "admin" },
+                { "id": "2", "role": "user" },
+                { "id": "3", "role": "user" },
+                { "id": "4", "role": "user" }
+            ]
+        });
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+
+        let query =
+            "MATCH (u:users) WHERE u.role = \"user\" RETURN u.id ORDER BY u.id SKIP 1 LIMIT 1";
+        let result = engine.execute_with_total(query).unwrap();
+        assert_eq!(result.rows, vec![json!({"u.id": "3"})]);
+        assert_eq!(result.total_count, Some(3));
+        assert_eq!(engine.execute(query).unwrap().total_count, None);
+
+        let result = engine
+            .execute_with_total("MATCH (u:users) RETURN u.role, COUNT(u) LIMIT 1")
+            .unwrap();
+        assert_eq!(result.total_count, Some(2));
+    }
+
+    #[test]
+    fn test_label_from_path() {
+        let data = json!({
+            "users": [
+                { "id": "u1", "type": "admin" },
+                { "id": "u2", "type": "member" }
+            ],
+            "posts": [
+                { "id": "p1", "kind": "article", "author": ["u1"] }
+            ]
+        });
+
+        let engine = CypherEngine::from_json_auto(&data).unwrap();
+        let result = engine.execute("MATCH (u:users) RETURN COUNT(u)").unwrap();
+        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(0));
+
+        let config = GraphConfig::default().with_label_from_path(true);
+        let engine = CypherEngine::from_json_with_config(&data, "Root", &config).unwrap();
+        let result = engine.execute("MATCH (u:users) RETURN COUNT(u)").unwrap();
+        assert_eq!(result.get_single_value().unwrap().as_i64(), Some(2));
+        let result = engine
+            .execute("MATCH (p:posts)-[:author]->(u:users) RETURN u.id")
+            .unwrap();
+        assert_eq!(result.rows, vec![json!({"u.id": "u1"})]);
+    }
+
+    #[test]
+    fn test_from_serializable_reports_errors() {
+        use std::collections::HashMap;
+
+        // Maps with non-string keys can't be serialized to JSON
+        let mut bad: HashMap<(i32, i32), i32> = HashMap::new();
+        bad.insert((1, 2), 3);
+        let err = CypherEngine::from_serializable(&bad, &GraphConfig::default())
+            .err()
+            .unwrap();
+        assert!(matches!(err, CypherError::GraphBuild(_)));
+    }
+
+    #[test]
+    fn test_with_max_rows() {
+        let data = json!({
+            "users": [
+                { "id": "1", "name": "Alice" },
+                { "id": "2", "name": "Bob" },
+                { "id": "3", "name": "Charlie" }
+            ]
+        });
+
+        let engine = CypherEngine::from_json_auto(&data)
+            .unwrap()
+            .with_max_rows(10);
+
+        let result = engine.execute("MATCH (u:users) RETURN u.name").unwrap();
+        assert_eq!(result.rows.len(), 3);
+
+        let err = engine
+            .execute("MATCH (a:users), (b:users), (c:users) RETURN a.name")
+            .unwrap_err();
+        assert!(err.to_string().contains("result set exceeded max_rows"));
+    }
+
     #[test]
     fn test_from_json_with_label() {
         let data = json!({
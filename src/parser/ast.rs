@@ -1,36 +1,152 @@
+//! The syntax tree of a parsed Cypher query.
+//!
+//! [`parse_query`](crate::parser::parse_query) produces a [`Query`], which
+//! tools such as linters, formatters and analyzers can inspect without
+//! executing it. Every node implements `Display`, rendering canonical Cypher
+//! that parses back to an equal tree, and serde's traits.
+//!
+//! The types are re-exported as `cypher_rs::ast`. New query features add
+//! fields and variants, so match on them with a wildcard arm.
+
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Query {
     pub match_clause: MatchClause,
     pub where_clause: Option<WhereClause>,
+    /// `OPTIONAL MATCH` clauses applied in order after the first MATCH
+    pub optional_matches: Vec<OptionalMatchClause>,
+    /// `WITH` stages applied in order after the first MATCH
+    pub with_clauses: Vec<WithClause>,
     pub return_clause: ReturnClause,
     pub order_by_clause: Option<OrderByClause>,
+    /// Rows to drop after sorting
+    pub skip: Option<usize>,
+    /// Maximum rows to return after sorting and skipping
+    pub limit: Option<usize>,
+    pub set_clause: Option<SetClause>,
+    pub delete_clause: Option<DeleteClause>,
+}
+
+impl Query {
+    /// Every variable the query binds or references: in patterns, `WITH`
+    /// aliases, filters, projections, `ORDER BY`, `SET` and `DELETE`.
+    ///
+    /// `ORDER BY` may name `RETURN` aliases, which are included too. The
+    /// `*` of `COUNT(*)` is not a variable.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::ast::Query;
+    /// use cypher_rs::parser::parse_query;
+    ///
+    /// let query: Query = parse_query("MATCH (a)-[r]->(b) RETURN a.id").unwrap();
+    /// let mut vars: Vec<String> = query.variables().into_iter().collect();
+    /// vars.sort();
+    /// assert_eq!(vars, ["a", "b", "r"]);
+    /// ```
+    pub fn variables(&self) -> HashSet<String> {
+        let mut vars: HashSet<&str> = HashSet::new();
+        let match_clauses = std::iter::once(&self.match_clause)
+            .chain(self.optional_matches.iter().map(|o| &o.match_clause))
+            .chain(self.with_clauses.iter().flat_map(|w| &w.match_clause));
+        for match_clause in match_clauses {
+            vars.extend(match_clause.variables());
+        }
+
+        let where_clauses = self
+            .where_clause
+            .iter()
+            .chain(self.optional_matches.iter().flat_map(|o| &o.where_clause))
+            .chain(
+                self.with_clauses
+                    .iter()
+                    .flat_map(|w| w.where_clause.iter().chain(&w.match_where)),
+            );
+        for where_clause in where_clauses {
+            where_clause.expression.collect_variables(&mut vars);
+        }
+
+        let projections = self
+            .with_clauses
+            .iter()
+            .map(|w| &w.projection)
+            .chain(std::iter::once(&self.return_clause));
+        for item in projections.flat_map(|p| &p.items) {
+            item.expression.collect_variables(&mut vars);
+        }
+        vars.extend(
+            self.with_clauses
+                .iter()
+                .flat_map(|w| &w.projection.items)
+                .filter_map(|item| item.alias.as_deref()),
+        );
+
+        for sort_item in self.order_by_clause.iter().flat_map(|o| &o.items) {
+            sort_item.expression.collect_variables(&mut vars);
+        }
+        for set_item in self.set_clause.iter().flat_map(|s| &s.items) {
+            vars.insert(&set_item.variable);
+            set_item.value.collect_variables(&mut vars);
+        }
+        if let Some(delete) = &self.delete_clause {
+            vars.extend(delete.variables.iter().map(String::as_str));
+        }
+
+        vars.remove("*");
+        vars.into_iter().map(str::to_string).collect()
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MatchClause {
     pub patterns: Vec<PatternPart>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl MatchClause {
+    /// The node and relationship variables the patterns name.
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.patterns
+            .iter()
+            .flat_map(|p| &p.chains)
+            .flat_map(|chain| match chain {
+                PatternChain::Node(node_pat) => [None, node_pat.variable.as_ref()],
+                PatternChain::Relationship(rel_pat, node_pat) => {
+                    [rel_pat.variable.as_ref(), node_pat.variable.as_ref()]
+                }
+            })
+            .flatten()
+            .map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PatternPart {
     pub chains: Vec<PatternChain>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PatternChain {
     Node(NodePattern),
     Relationship(RelationshipPattern, NodePattern),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NodePattern {
     pub variable: Option<String>,
+    /// Labels the node must carry: all of them (`:a:b`), or any one of them
+    /// when `any_label` is set (`:a|b`)
     pub labels: Vec<String>,
+    pub any_label: bool,
+    /// Inline `{key: value}` equality predicates; other comparisons are
+    /// written in `WHERE`
+    pub properties: Vec<(String, Literal)>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RelationshipPattern {
     pub variable: Option<String>,
     pub rel_type: Option<String>,
@@ -38,65 +154,123 @@ pub struct RelationshipPattern {
     pub direction: Direction,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
     Left,
     Right,
     Both,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Range {
     pub start: Option<usize>,
     pub end: Option<usize>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An `OPTIONAL MATCH`: extends each row with the matches of a pattern, or
+/// keeps the row with the pattern's new variables bound to null when nothing
+/// matches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OptionalMatchClause {
+    pub match_clause: MatchClause,
+    /// Filter on the matches; rows whose matches are all filtered out are
+    /// kept with nulls
+    pub where_clause: Option<WhereClause>,
+}
+
+/// A `WITH` stage: projects the rows so far, optionally filters them, and
+/// optionally matches further patterns from each projected row.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithClause {
+    /// The projected items; anything but a bare variable carries an alias
+    pub projection: ReturnClause,
+    /// Filter on the projected rows
+    pub where_clause: Option<WhereClause>,
+    pub match_clause: Option<MatchClause>,
+    /// Filter following `match_clause`
+    pub match_where: Option<WhereClause>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WhereClause {
     pub expression: Expression,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Expression {
     Or(Vec<Expression>),
     And(Vec<Expression>),
     Comparison(Comparison),
     Aggregate(AggregateExpression),
+    /// `n:a:b`: the node bound to `variable` carries every label
+    HasLabels(LabelPredicate),
+}
+
+impl Expression {
+    /// Add the variables this expression references to `vars`.
+    pub fn collect_variables<'a>(&'a self, vars: &mut HashSet<&'a str>) {
+        match self {
+            Expression::And(exprs) | Expression::Or(exprs) => {
+                for e in exprs {
+                    e.collect_variables(vars);
+                }
+            }
+            Expression::Comparison(comp) => {
+                comp.left.collect_variables(vars);
+                if let Some(right) = &comp.right {
+                    right.collect_variables(vars);
+                }
+            }
+            Expression::Aggregate(agg) => {
+                if let Some(argument) = &agg.argument {
+                    argument.collect_variables(vars);
+                }
+            }
+            Expression::HasLabels(predicate) => {
+                vars.insert(&predicate.variable);
+            }
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabelPredicate {
+    pub variable: String,
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AggregateExpression {
     pub func: AggregateFunction,
-    pub variable: String,
-    pub property: Option<String>,
+    /// Whether duplicate argument values are dropped before aggregating
+    #[serde(default)]
+    pub distinct: bool,
+    /// The expression aggregated in each row, or `None` for `COUNT(*)`
+    pub argument: Option<ValueExpression>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AggregateFunction {
     Count,
     Sum,
+    /// Gather non-null values into a list
+    Collect,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Comparison {
-    pub left: PropertyOrVariable,
+    pub left: ValueExpression,
     pub operator: Option<ComparisonOperator>,
-    pub right: Option<Term>,
+    pub right: Option<ValueExpression>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PropertyOrVariable {
     pub variable: String,
     pub property: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Term {
-    Literal(Literal),
-    PropertyOrVariable(PropertyOrVariable),
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ComparisonOperator {
     Eq,
     NotEq,
@@ -105,39 +279,474 @@ pub enum ComparisonOperator {
     LtEq,
     GtEq,
     Contains,
+    StartsWith,
+    EndsWith,
+    In,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Literal {
     String(String),
     Number(i64),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl From<&str> for Literal {
+    fn from(s: &str) -> Self {
+        Literal::String(s.to_string())
+    }
+}
+
+impl From<String> for Literal {
+    fn from(s: String) -> Self {
+        Literal::String(s)
+    }
+}
+
+impl From<i64> for Literal {
+    fn from(n: i64) -> Self {
+        Literal::Number(n)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ReturnClause {
     pub distinct: bool,
     pub items: Vec<ReturnItem>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReturnItem {
     pub expression: Expression,
     pub alias: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetClause {
+    pub items: Vec<SetItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetItem {
+    pub variable: String,
+    pub property: String,
+    pub value: ValueExpression,
+}
+
+/// An expression producing a JSON value rather than a boolean.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ValueExpression {
+    Literal(Literal),
+    List(Vec<Literal>),
+    PropertyOrVariable(PropertyOrVariable),
+    Function(FunctionCall),
+    Add(Box<ValueExpression>, Box<ValueExpression>),
+    Multiply(Box<ValueExpression>, Box<ValueExpression>),
+}
+
+impl ValueExpression {
+    /// The property or variable this expression reads, if that's all it is.
+    pub fn as_property_or_variable(&self) -> Option<&PropertyOrVariable> {
+        match self {
+            ValueExpression::PropertyOrVariable(pv) => Some(pv),
+            _ => None,
+        }
+    }
+
+    /// Add the variables this expression references to `vars`.
+    pub fn collect_variables<'a>(&'a self, vars: &mut HashSet<&'a str>) {
+        match self {
+            ValueExpression::PropertyOrVariable(pv) => {
+                vars.insert(&pv.variable);
+            }
+            ValueExpression::Function(func) => {
+                for arg in &func.args {
+                    arg.collect_variables(vars);
+                }
+            }
+            ValueExpression::Add(left, right) | ValueExpression::Multiply(left, right) => {
+                left.collect_variables(vars);
+                right.collect_variables(vars);
+            }
+            ValueExpression::Literal(_) | ValueExpression::List(_) => {}
+        }
+    }
+}
+
+/// A scalar function call such as `toBoolean(n.flag)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCall {
+    /// The function name as written in the query
+    pub name: String,
+    pub args: Vec<ValueExpression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeleteClause {
+    pub detach: bool,
+    pub variables: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OrderByClause {
     pub items: Vec<SortItem>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SortItem {
-    pub expression: PropertyOrVariable,
+    /// A returned column or alias, or an expression evaluated per row
+    pub expression: ValueExpression,
     pub direction: SortDirection,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SortDirection {
     Asc,
     Desc,
 }
+
+// Rendering back to Cypher.
+//
+// The output is canonical rather than byte-identical to the original query
+// (keywords upper-cased, one space around operators), but it parses back to
+// an equal AST.
+
+fn write_joined<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    items: &[T],
+    sep: &str,
+) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(sep)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MATCH {}", self.match_clause)?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " WHERE {}", where_clause)?;
+        }
+        for optional_match in &self.optional_matches {
+            write!(f, " {}", optional_match)?;
+        }
+        for with_clause in &self.with_clauses {
+            write!(f, " {}", with_clause)?;
+        }
+        if let Some(set_clause) = &self.set_clause {
+            return write!(f, " {}", set_clause);
+        }
+        if let Some(delete_clause) = &self.delete_clause {
+            return write!(f, " {}", delete_clause);
+        }
+        write!(f, " RETURN {}", self.return_clause)?;
+        if let Some(order_by_clause) = &self.order_by_clause {
+            write!(f, " ORDER BY {}", order_by_clause)?;
+        }
+        if let Some(skip) = self.skip {
+            write!(f, " SKIP {}", skip)?;
+        }
+        if let Some(limit) = self.limit {
+            write!(f, " LIMIT {}", limit)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for OptionalMatchClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OPTIONAL MATCH {}", self.match_clause)?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " WHERE {}", where_clause)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WithClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WITH {}", self.projection)?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " WHERE {}", where_clause)?;
+        }
+        if let Some(match_clause) = &self.match_clause {
+            write!(f, " MATCH {}", match_clause)?;
+            if let Some(match_where) = &self.match_where {
+                write!(f, " WHERE {}", match_where)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MatchClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_joined(f, &self.patterns, ", ")
+    }
+}
+
+impl fmt::Display for PatternPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_joined(f, &self.chains, "")
+    }
+}
+
+impl fmt::Display for PatternChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternChain::Node(node) => write!(f, "{}", node),
+            PatternChain::Relationship(rel, node) => write!(f, "{}{}", rel, node),
+        }
+    }
+}
+
+impl fmt::Display for NodePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(")?;
+        if let Some(variable) = &self.variable {
+            f.write_str(variable)?;
+        }
+        if self.any_label {
+            write!(f, ":{}", self.labels.join("|"))?;
+        } else {
+            for label in &self.labels {
+                write!(f, ":{}", label)?;
+            }
+        }
+        if !self.properties.is_empty() {
+            if self.variable.is_some() || !self.labels.is_empty() {
+                f.write_str(" ")?;
+            }
+            f.write_str("{")?;
+            for (i, (key, value)) in self.properties.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}: {}", key, value)?;
+            }
+            f.write_str("}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for RelationshipPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (open, close) = match self.direction {
+            Direction::Left => ("<-", "-"),
+            Direction::Right => ("-", "->"),
+            Direction::Both => ("-", "-"),
+        };
+        write!(f, "{}[", open)?;
+        if let Some(variable) = &self.variable {
+            f.write_str(variable)?;
+        }
+        if let Some(rel_type) = &self.rel_type {
+            write!(f, ":{}", rel_type)?;
+        }
+        if let Some(range) = &self.range {
+            write!(f, "{}", range)?;
+        }
+        write!(f, "]{}", close)
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("*")?;
+        match (self.start, self.end) {
+            (None, None) => Ok(()),
+            (Some(start), Some(end)) if start == end => write!(f, "{}", start),
+            (start, end) => {
+                if let Some(start) = start {
+                    write!(f, "{}", start)?;
+                }
+                f.write_str("..")?;
+                if let Some(end) = end {
+                    write!(f, "{}", end)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for WhereClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Or(parts) => write_joined(f, parts, " OR "),
+            Expression::And(parts) => write_joined(f, parts, " AND "),
+            Expression::Comparison(comparison) => write!(f, "{}", comparison),
+            Expression::Aggregate(aggregate) => write!(f, "{}", aggregate),
+            Expression::HasLabels(predicate) => write!(f, "{}", predicate),
+        }
+    }
+}
+
+impl fmt::Display for LabelPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.variable)?;
+        for label in &self.labels {
+            write!(f, ":{}", label)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for AggregateExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let func = match self.func {
+            AggregateFunction::Count => "COUNT",
+            AggregateFunction::Sum => "SUM",
+            AggregateFunction::Collect => "COLLECT",
+        };
+        write!(f, "{}(", func)?;
+        if self.distinct {
+            f.write_str("DISTINCT ")?;
+        }
+        match &self.argument {
+            Some(argument) => write!(f, "{})", argument),
+            None => f.write_str("*)"),
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.left)?;
+        if let (Some(operator), Some(right)) = (&self.operator, &self.right) {
+            write!(f, " {} {}", operator, right)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PropertyOrVariable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.variable)?;
+        if let Some(property) = &self.property {
+            write!(f, ".{}", property)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ComparisonOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ComparisonOperator::Eq => "=",
+            ComparisonOperator::NotEq => "<>",
+            ComparisonOperator::Lt => "<",
+            ComparisonOperator::Gt => ">",
+            ComparisonOperator::LtEq => "<=",
+            ComparisonOperator::GtEq => ">=",
+            ComparisonOperator::Contains => "CONTAINS",
+            ComparisonOperator::StartsWith => "STARTS WITH",
+            ComparisonOperator::EndsWith => "ENDS WITH",
+            ComparisonOperator::In => "IN",
+        })
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::String(s) => {
+                write!(f, "\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            Literal::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl fmt::Display for ReturnClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.distinct {
+            f.write_str("DISTINCT ")?;
+        }
+        write_joined(f, &self.items, ", ")
+    }
+}
+
+impl fmt::Display for ReturnItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expression)?;
+        if let Some(alias) = &self.alias {
+            write!(f, " AS {}", alias)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SetClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SET ")?;
+        write_joined(f, &self.items, ", ")
+    }
+}
+
+impl fmt::Display for SetItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{} = {}", self.variable, self.property, self.value)
+    }
+}
+
+impl fmt::Display for ValueExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueExpression::Literal(literal) => write!(f, "{}", literal),
+            ValueExpression::List(items) => {
+                f.write_str("[")?;
+                write_joined(f, items, ", ")?;
+                f.write_str("]")
+            }
+            ValueExpression::PropertyOrVariable(pv) => write!(f, "{}", pv),
+            ValueExpression::Function(call) => write!(f, "{}", call),
+            ValueExpression::Add(left, right) => write!(f, "{} + {}", left, right),
+            ValueExpression::Multiply(left, right) => write!(f, "{} * {}", left, right),
+        }
+    }
+}
+
+impl fmt::Display for FunctionCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        write_joined(f, &self.args, ", ")?;
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for DeleteClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.detach {
+            f.write_str("DETACH ")?;
+        }
+        f.write_str("DELETE ")?;
+        write_joined(f, &self.variables, ", ")
+    }
+}
+
+impl fmt::Display for OrderByClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_joined(f, &self.items, ", ")
+    }
+}
+
+impl fmt::Display for SortItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        write!(f, "{} {}", self.expression, direction)
+    }
+}
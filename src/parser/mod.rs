@@ -21,8 +21,14 @@ pub fn parse_query(query_str: &str) -> Result<ast::Query> {
 
     let mut match_clause = None;
     let mut where_clause = None;
+    let mut optional_matches = Vec::new();
+    let mut with_clauses = Vec::new();
     let mut return_clause = None;
     let mut order_by_clause = None;
+    let mut skip = None;
+    let mut limit = None;
+    let mut set_clause = None;
+    let mut delete_clause = None;
 
     for pair in pairs.into_iter().next().unwrap().into_inner() {
         match pair.as_rule() {
@@ -34,6 +40,12 @@ pub fn parse_query(query_str: &str) -> Result<ast::Query> {
             Rule::where_clause => {
                 where_clause = Some(parse_where_clause(pair)?);
             }
+            Rule::optional_match_clause => {
+                optional_matches.push(parse_optional_match_clause(pair)?);
+            }
+            Rule::with_clause => {
+                with_clauses.push(parse_with_clause(pair)?);
+            }
             Rule::RETURN => {}
             Rule::DISTINCT => {}
             Rule::return_clause => {
@@ -44,30 +56,82 @@ pub fn parse_query(query_str: &str) -> Result<ast::Query> {
             Rule::order_by_clause => {
                 order_by_clause = Some(parse_order_by_clause(pair)?);
             }
+            Rule::skip_clause => {
+                skip = Some(parse_count(pair, "SKIP")?);
+            }
+            Rule::limit_clause => {
+                limit = Some(parse_count(pair, "LIMIT")?);
+            }
+            Rule::set_clause => {
+                set_clause = Some(parse_set_clause(pair)?);
+            }
+            Rule::delete_clause => {
+                delete_clause = Some(parse_delete_clause(pair));
+            }
             _ => {}
         }
     }
 
+    // Mutation queries have no RETURN clause
+    let return_clause = match return_clause {
+        Some(return_clause) => return_clause,
+        None if set_clause.is_some() || delete_clause.is_some() => ast::ReturnClause::default(),
+        None => return Err(anyhow!("Missing RETURN clause")),
+    };
+
     Ok(ast::Query {
         match_clause: match_clause.ok_or_else(|| anyhow!("Missing MATCH clause"))?,
         where_clause,
-        return_clause: return_clause.ok_or_else(|| anyhow!("Missing RETURN clause"))?,
+        optional_matches,
+        with_clauses,
+        return_clause,
         order_by_clause,
+        skip,
+        limit,
+        set_clause,
+        delete_clause,
     })
 }
 
+/// Split a script into its queries at semicolons outside string literals.
+///
+/// Queries are trimmed, and empty ones (such as after a trailing `;`) are
+/// left out.
+pub fn split_queries(script: &str) -> Vec<&str> {
+    let mut queries = Vec::new();
+    let mut start = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in script.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            ';' if !in_string => {
+                queries.push(&script[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    queries.push(&script[start..]);
+    queries
+        .into_iter()
+        .map(str::trim)
+        .filter(|query| !query.is_empty())
+        .collect()
+}
+
 /// Detect unsupported Cypher keywords in the query and return a helpful error message.
 fn detect_unsupported_features(query_str: &str) -> Option<String> {
     let upper = query_str.to_uppercase();
     let unsupported = [
-        ("SKIP", "SKIP"),
-        ("LIMIT", "LIMIT"),
         ("CREATE", "CREATE"),
         ("MERGE", "MERGE"),
-        ("SET", "SET"),
-        ("DELETE", "DELETE"),
         ("REMOVE", "REMOVE"),
-        ("WITH", "WITH"),
         ("UNION", "UNION"),
         ("CALL", "CALL"),
         ("YIELD", "YIELD"),
@@ -75,9 +139,6 @@ fn detect_unsupported_features(query_str: &str) -> Option<String> {
         ("FOREACH", "FOREACH"),
         ("EXISTS", "EXISTS"),
         ("CASE", "CASE"),
-        ("STARTS", "STARTS WITH"),
-        ("ENDS", "ENDS WITH"),
-        ("IN", " IN "),
         ("IS NULL", "IS NULL"),
         ("IS NOT NULL", "IS NOT NULL"),
     ];
@@ -90,16 +151,8 @@ fn detect_unsupported_features(query_str: &str) -> Option<String> {
             || upper.contains(&format!("({}", keyword))
             || upper.contains(&format!(",{}", keyword))
         {
-            // Avoid false positives for already-supported keywords
-            if keyword == "SET" && !upper.contains(" RETURN ") {
-                // "SET" inside a JSON-like context, skip
-                continue;
-            }
-            if keyword == "IN" && upper.contains(" DISTINCT") {
-                continue;
-            }
             return Some(format!(
-                "Unsupported feature: {}. Supported clauses: MATCH, WHERE, RETURN, ORDER BY.",
+                "Unsupported feature: {}. Supported clauses: MATCH, OPTIONAL MATCH, WHERE, WITH, RETURN, ORDER BY, SKIP, LIMIT, SET, [DETACH] DELETE.",
                 label
             ));
         }
@@ -143,22 +196,44 @@ fn parse_pattern_part(pair: Pair<Rule>) -> Result<ast::PatternPart> {
 fn parse_node_pattern(pair: Pair<Rule>) -> Result<ast::NodePattern> {
     let mut variable = None;
     let mut labels = Vec::new();
+    let mut any_label = false;
+    let mut properties = Vec::new();
 
     for p in pair.into_inner() {
         match p.as_rule() {
             Rule::variable => variable = Some(p.as_str().to_string()),
+            Rule::property_map => {
+                for entry in p.into_inner() {
+                    let mut inner = entry.into_inner();
+                    let key = inner.next().unwrap().as_str().to_string();
+                    let value = parse_literal(inner.next().unwrap())?;
+                    properties.push((key, value));
+                }
+            }
             Rule::node_labels => {
                 for l in p.into_inner() {
-                    if l.as_rule() == Rule::node_label {
-                        let label_name = l.into_inner().next().unwrap().as_str().to_string();
-                        labels.push(label_name);
+                    match l.as_rule() {
+                        Rule::node_label => {
+                            let label_name = l.into_inner().next().unwrap().as_str().to_string();
+                            labels.push(label_name);
+                        }
+                        Rule::label_alternatives => {
+                            any_label = true;
+                            labels.extend(l.into_inner().map(|name| name.as_str().to_string()));
+                        }
+                        _ => {}
                     }
                 }
             }
             _ => {}
         }
     }
-    Ok(ast::NodePattern { variable, labels })
+    Ok(ast::NodePattern {
+        variable,
+        labels,
+        any_label,
+        properties,
+    })
 }
 
 fn parse_relationship_pattern(pair: Pair<Rule>) -> Result<ast::RelationshipPattern> {
@@ -186,7 +261,7 @@ fn parse_relationship_pattern(pair: Pair<Rule>) -> Result<ast::RelationshipPatte
                         rel_type = Some(type_pair.as_str().to_string());
                     }
                     Rule::range_literal => {
-                        range = parse_range_literal(d).ok();
+                        range = Some(parse_range_literal(d)?);
                     }
                     _ => {}
                 }
@@ -208,6 +283,70 @@ fn parse_where_clause(pair: Pair<Rule>) -> Result<ast::WhereClause> {
     Ok(ast::WhereClause { expression })
 }
 
+fn parse_optional_match_clause(pair: Pair<Rule>) -> Result<ast::OptionalMatchClause> {
+    let mut match_clause = None;
+    let mut where_clause = None;
+    for p in pair.into_inner() {
+        match p.as_rule() {
+            Rule::pattern => match_clause = Some(parse_match_clause(p)?),
+            Rule::where_clause => where_clause = Some(parse_where_clause(p)?),
+            _ => {}
+        }
+    }
+    Ok(ast::OptionalMatchClause {
+        match_clause: match_clause.ok_or_else(|| anyhow!("Missing OPTIONAL MATCH pattern"))?,
+        where_clause,
+    })
+}
+
+fn parse_with_clause(pair: Pair<Rule>) -> Result<ast::WithClause> {
+    let mut projection = None;
+    let mut where_clause = None;
+    let mut match_clause = None;
+    let mut match_where = None;
+
+    for p in pair.into_inner() {
+        match p.as_rule() {
+            Rule::return_clause => projection = Some(parse_return_clause(p)?),
+            Rule::pattern => match_clause = Some(parse_match_clause(p)?),
+            // A WHERE after the stage's MATCH filters the match, not the projection
+            Rule::where_clause if match_clause.is_some() => {
+                match_where = Some(parse_where_clause(p)?);
+            }
+            Rule::where_clause => where_clause = Some(parse_where_clause(p)?),
+            _ => {}
+        }
+    }
+
+    let projection = projection.ok_or_else(|| anyhow!("Missing WITH items"))?;
+    for item in &projection.items {
+        let bare_variable = matches!(
+            &item.expression,
+            ast::Expression::Comparison(ast::Comparison {
+                left: ast::ValueExpression::PropertyOrVariable(ast::PropertyOrVariable {
+                    property: None,
+                    ..
+                }),
+                operator: None,
+                ..
+            })
+        );
+        if item.alias.is_none() && !bare_variable {
+            return Err(anyhow!(
+                "Expression in WITH must be aliased (use AS): {}",
+                item.expression
+            ));
+        }
+    }
+
+    Ok(ast::WithClause {
+        projection,
+        where_clause,
+        match_clause,
+        match_where,
+    })
+}
+
 fn parse_return_clause(pair: Pair<Rule>) -> Result<ast::ReturnClause> {
     let mut items = Vec::new();
     let mut distinct = false;
@@ -242,13 +381,23 @@ fn parse_return_item(pair: Pair<Rule>) -> Result<ast::ReturnItem> {
     Ok(ast::ReturnItem { expression, alias })
 }
 
+/// Parse a SKIP or LIMIT count, which must be a non-negative integer that
+/// fits in `usize`.
+fn parse_count(pair: Pair<Rule>, clause: &str) -> Result<usize> {
+    let text = pair.as_str().trim();
+    if text.starts_with('-') {
+        return Err(anyhow!("{} must not be negative, got {}", clause, text));
+    }
+    text.parse::<usize>()
+        .map_err(|e| anyhow!("Invalid {} value {}: {}", clause, text, e))
+}
+
 fn parse_order_by_clause(pair: Pair<Rule>) -> Result<ast::OrderByClause> {
     let mut items = Vec::new();
     for p in pair.into_inner() {
         if p.as_rule() == Rule::sort_item {
             let mut inner = p.into_inner();
-            let expr_pair = inner.next().unwrap();
-            let expression = parse_property_or_variable(expr_pair)?;
+            let expression = parse_value_expression(inner.next().unwrap())?;
 
             let direction = if let Some(dir_pair) = inner.next() {
                 match dir_pair.as_str().to_uppercase().as_str() {
@@ -268,6 +417,98 @@ fn parse_order_by_clause(pair: Pair<Rule>) -> Result<ast::OrderByClause> {
     Ok(ast::OrderByClause { items })
 }
 
+fn parse_set_clause(pair: Pair<Rule>) -> Result<ast::SetClause> {
+    let mut items = Vec::new();
+    for p in pair.into_inner() {
+        if p.as_rule() == Rule::set_item {
+            let mut inner = p.into_inner();
+            let variable = inner.next().unwrap().as_str().to_string();
+            let property = inner.next().unwrap().as_str().to_string();
+            let value = parse_value_expression(inner.next().unwrap())?;
+            items.push(ast::SetItem {
+                variable,
+                property,
+                value,
+            });
+        }
+    }
+    Ok(ast::SetClause { items })
+}
+
+fn parse_value_expression(pair: Pair<Rule>) -> Result<ast::ValueExpression> {
+    let mut result: Option<ast::ValueExpression> = None;
+    for p in pair.into_inner() {
+        if p.as_rule() != Rule::product {
+            continue;
+        }
+        let operand = parse_product(p)?;
+        result = Some(match result {
+            Some(left) => ast::ValueExpression::Add(Box::new(left), Box::new(operand)),
+            None => operand,
+        });
+    }
+    result.ok_or_else(|| anyhow!("Empty value expression"))
+}
+
+fn parse_product(pair: Pair<Rule>) -> Result<ast::ValueExpression> {
+    let mut result: Option<ast::ValueExpression> = None;
+    for p in pair.into_inner() {
+        if p.as_rule() != Rule::value_operand {
+            continue;
+        }
+        let operand = parse_value_operand(p)?;
+        result = Some(match result {
+            Some(left) => ast::ValueExpression::Multiply(Box::new(left), Box::new(operand)),
+            None => operand,
+        });
+    }
+    result.ok_or_else(|| anyhow!("Empty value expression"))
+}
+
+fn parse_value_operand(pair: Pair<Rule>) -> Result<ast::ValueExpression> {
+    let inner = pair.into_inner().next().unwrap();
+    match inner.as_rule() {
+        Rule::list_literal => {
+            let items = inner
+                .into_inner()
+                .map(parse_literal)
+                .collect::<Result<Vec<_>>>()?;
+            Ok(ast::ValueExpression::List(items))
+        }
+        Rule::literal => Ok(ast::ValueExpression::Literal(parse_literal(inner)?)),
+        Rule::function_call => {
+            let mut parts = inner.into_inner();
+            let name = parts.next().unwrap().as_str().to_string();
+            let args = parts
+                .map(parse_value_expression)
+                .collect::<Result<Vec<_>>>()?;
+            Ok(ast::ValueExpression::Function(ast::FunctionCall {
+                name,
+                args,
+            }))
+        }
+        Rule::property_or_variable => Ok(ast::ValueExpression::PropertyOrVariable(
+            parse_property_or_variable(inner)?,
+        )),
+        _ => unreachable!(),
+    }
+}
+
+fn parse_delete_clause(pair: Pair<Rule>) -> ast::DeleteClause {
+    let mut detach = false;
+    let mut variables = Vec::new();
+
+    for p in pair.into_inner() {
+        match p.as_rule() {
+            Rule::DETACH => detach = true,
+            Rule::variable => variables.push(p.as_str().to_string()),
+            _ => {}
+        }
+    }
+
+    ast::DeleteClause { detach, variables }
+}
+
 fn parse_range_literal(pair: Pair<Rule>) -> Result<ast::Range> {
     let mut start = None;
     let mut end = None;
@@ -277,18 +518,18 @@ fn parse_range_literal(pair: Pair<Rule>) -> Result<ast::Range> {
             let inner = p.clone().into_inner().next().unwrap();
             match inner.as_rule() {
                 Rule::range_exact => {
-                    let val = inner.as_str().parse::<usize>().ok();
-                    start = val;
-                    end = val;
+                    let val = parse_count(inner, "Relationship range bound")?;
+                    start = Some(val);
+                    end = Some(val);
                 }
                 _ => {
                     for q in p.into_inner() {
                         match q.as_rule() {
                             Rule::range_start => {
-                                start = q.as_str().parse::<usize>().ok();
+                                start = Some(parse_count(q, "Relationship range bound")?);
                             }
                             Rule::range_end => {
-                                end = q.as_str().parse::<usize>().ok();
+                                end = Some(parse_count(q, "Relationship range bound")?);
                             }
                             _ => {}
                         }
@@ -336,11 +577,28 @@ fn parse_comparison_expression(pair: Pair<Rule>) -> Result<ast::Expression> {
     if left_pair.as_rule() == Rule::aggregate_call {
         return parse_aggregate_call(left_pair);
     }
+    if left_pair.as_rule() == Rule::label_predicate {
+        let mut parts = left_pair.into_inner();
+        let variable = parts.next().unwrap().as_str().to_string();
+        let labels = parts
+            .map(|label| label.into_inner().next().unwrap().as_str().to_string())
+            .collect();
+        return Ok(ast::Expression::HasLabels(ast::LabelPredicate {
+            variable,
+            labels,
+        }));
+    }
 
-    let left = parse_property_or_variable(left_pair)?;
+    let left = parse_value_expression(left_pair)?;
 
     if let Some(op_pair) = inner.next() {
-        let operator = match op_pair.as_str().to_uppercase().as_str() {
+        let op_str = op_pair
+            .as_str()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_uppercase();
+        let operator = match op_str.as_str() {
             "=" => ast::ComparisonOperator::Eq,
             "<>" => ast::ComparisonOperator::NotEq,
             "<" => ast::ComparisonOperator::Lt,
@@ -348,11 +606,14 @@ fn parse_comparison_expression(pair: Pair<Rule>) -> Result<ast::Expression> {
             "<=" => ast::ComparisonOperator::LtEq,
             ">=" => ast::ComparisonOperator::GtEq,
             "CONTAINS" => ast::ComparisonOperator::Contains,
+            "STARTS WITH" => ast::ComparisonOperator::StartsWith,
+            "ENDS WITH" => ast::ComparisonOperator::EndsWith,
+            "IN" => ast::ComparisonOperator::In,
             _ => unreachable!(),
         };
 
         let right_pair = inner.next().unwrap();
-        let right = parse_term(right_pair)?;
+        let right = parse_value_expression(right_pair)?;
 
         Ok(ast::Expression::Comparison(ast::Comparison {
             left,
@@ -375,22 +636,36 @@ fn parse_aggregate_call(pair: Pair<Rule>) -> Result<ast::Expression> {
     let func = match func_str.as_str() {
         "COUNT" => ast::AggregateFunction::Count,
         "SUM" => ast::AggregateFunction::Sum,
+        "COLLECT" => ast::AggregateFunction::Collect,
         _ => return Err(anyhow!("Unknown aggregate function: {}", func_str)),
     };
 
-    let variable_pair = inner
-        .find(|p| p.as_rule() == Rule::variable)
-        .ok_or_else(|| anyhow!("Missing variable in aggregate function"))?;
-    let variable = variable_pair.as_str().to_string();
+    let distinct = inner
+        .peek()
+        .is_some_and(|p| p.as_rule() == Rule::distinct_modifier);
+    if distinct {
+        inner.next();
+    }
 
-    let property = inner
-        .find(|p| p.as_rule() == Rule::property_name)
-        .map(|p| p.as_str().to_string());
+    let argument_pair = inner
+        .next()
+        .ok_or_else(|| anyhow!("Missing argument in aggregate function"))?;
+    let argument = if argument_pair.as_rule() == Rule::count_star {
+        if func != ast::AggregateFunction::Count {
+            return Err(anyhow!("Only COUNT accepts *"));
+        }
+        if distinct {
+            return Err(anyhow!("DISTINCT cannot be used with *"));
+        }
+        None
+    } else {
+        Some(parse_value_expression(argument_pair)?)
+    };
 
     Ok(ast::Expression::Aggregate(ast::AggregateExpression {
         func,
-        variable,
-        property,
+        distinct,
+        argument,
     }))
 }
 
@@ -401,31 +676,35 @@ fn parse_property_or_variable(pair: Pair<Rule>) -> Result<ast::PropertyOrVariabl
     Ok(ast::PropertyOrVariable { variable, property })
 }
 
-fn parse_term(pair: Pair<Rule>) -> Result<ast::Term> {
-    let inner = pair.into_inner().next().unwrap();
-    match inner.as_rule() {
-        Rule::literal => {
-            let lit = inner.into_inner().next().unwrap();
-            match lit.as_rule() {
-                Rule::string_literal => {
-                    let s = lit.as_str();
-                    Ok(ast::Term::Literal(ast::Literal::String(
-                        s[1..s.len() - 1].to_string(),
-                    )))
-                }
-                Rule::number_literal => Ok(ast::Term::Literal(ast::Literal::Number(
-                    lit.as_str().parse().unwrap(),
-                ))),
-                _ => unreachable!(),
-            }
+fn parse_literal(pair: Pair<Rule>) -> Result<ast::Literal> {
+    let lit = pair.into_inner().next().unwrap();
+    match lit.as_rule() {
+        Rule::string_literal => {
+            let s = lit.as_str();
+            Ok(ast::Literal::String(unescape_string(&s[1..s.len() - 1])))
         }
-        Rule::property_or_variable => Ok(ast::Term::PropertyOrVariable(
-            parse_property_or_variable(inner)?,
-        )),
+        Rule::number_literal => Ok(ast::Literal::Number(lit.as_str().parse()?)),
         _ => unreachable!(),
     }
 }
 
+/// Resolve the `\"` and `\\` escapes in the body of a string literal.
+fn unescape_string(body: &str) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\'
+            && let Some(&next @ ('"' | '\\')) = chars.peek()
+        {
+            out.push(next);
+            chars.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -445,6 +724,97 @@ mod tests {
         assert_eq!(parsed.match_clause.patterns.len(), 1);
     }
 
+    #[test]
+    fn test_parse_relationship_range() {
+        let cases = [
+            ("[:friends*]", None, None),
+            ("[:friends*2]", Some(2), Some(2)),
+            ("[:friends*1..3]", Some(1), Some(3)),
+            ("[:friends*2..]", Some(2), None),
+        ];
+        for (rel, start, end) in cases {
+            let q = format!("MATCH (a)-{}->(b) RETURN b.id", rel);
+            let parsed = parse_query(&q).unwrap();
+            match &parsed.match_clause.patterns[0].chains[1] {
+                ast::PatternChain::Relationship(rel_pat, _) => {
+                    assert_eq!(rel_pat.range, Some(ast::Range { start, end }), "{}", rel);
+                }
+                _ => panic!("expected relationship pattern"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_relationship_range_rejects_negative_bounds() {
+        for rel in ["[*-2]", "[*1..-1]", "[*-1..3]"] {
+            let q = format!("MATCH (a)-{}->(b) RETURN b.id", rel);
+            let err = parse_query(&q).unwrap_err();
+            assert!(
+                err.to_string().contains("must not be negative"),
+                "{}: {}",
+                rel,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_query_variables() {
+        let query = parse_query(
+            "MATCH (u)-[r:knows]->(), (x) WHERE u:admin AND size(x.tags) > 0 \
+             WITH u, COUNT(*) AS c MATCH (u)-->(v) \
+             RETURN v.id AS id, COLLECT(w.name) ORDER BY id",
+        )
+        .unwrap();
+        let mut vars: Vec<String> = query.variables().into_iter().collect();
+        vars.sort();
+        assert_eq!(vars, ["c", "id", "r", "u", "v", "w", "x"]);
+
+        let query = parse_query("MATCH (n) SET n.total = m.a + 1").unwrap();
+        let mut vars: Vec<String> = query.variables().into_iter().collect();
+        vars.sort();
+        assert_eq!(vars, ["m", "n"]);
+        let query = parse_query("MATCH (n) DELETE k").unwrap();
+        assert!(query.variables().contains("k"));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let queries = [
+            "MATCH (n) RETURN n",
+            "match (n:UC)-[r:realized_by]->(m:FR) return n, m",
+            "MATCH (a)<-[:knows*1..3]-(b), (c:x:y {name: \"C\", age: 3}) RETURN DISTINCT a.id AS id",
+            "MATCH ()-[*]-(:admin) RETURN COUNT(*)",
+            "MATCH (u) WHERE u.age >= 30 AND u.name CONTAINS \"A\" OR u.active RETURN SUM(u.age) AS total",
+            "MATCH (u)-[r]->(v) WHERE u.id <> v.id RETURN u.id, r ORDER BY u.id DESC, v.name",
+            "MATCH (u) RETURN u.role, COUNT(u) AS c ORDER BY c DESC SKIP 1 LIMIT 5",
+            "MATCH (u) RETURN u.id ORDER BY size(u.friends) DESC, u.last + \" \" + u.first",
+            "MATCH (n) WHERE n.id = \"1\" SET n.tags = n.tags + [\"a\", 2], n.x = 1",
+            "MATCH (n)-[r]-(m) DETACH DELETE n, r",
+            "MATCH (n) WHERE toBoolean(n.flag) = toBoolean(\"true\") RETURN toBoolean(n.x) AS b",
+            "MATCH (n) WHERE n.x > -1 RETURN range(5, -5, -2)",
+            "MATCH (n) WHERE \"2\" IN n.friends OR n.id in [\"1\", 3] RETURN n",
+            "MATCH (u) RETURN COLLECT(DISTINCT u.role) AS roles, count(distinct u)",
+            "MATCH (n) WHERE n:admin AND n.age > 30 OR n:a:b RETURN n.id",
+            "MATCH (o) WHERE o.a * 2 > o.b + 1 RETURN o.id, SUM(o.price * o.quantity) AS total, COUNT(DISTINCT o.a + o.b)",
+            "MATCH (u) WITH COLLECT(u.id) AS ids MATCH (v) WHERE v.parent IN ids RETURN v.id",
+            "MATCH (u)-[]->(v) WITH DISTINCT u, COUNT(v) AS d WHERE d > 1 WITH u RETURN u.id",
+            "MATCH (n:admin|:moderator|user {x: 1})-[:a|b]->(m:c:d) RETURN n",
+            "MATCH (u) OPTIONAL MATCH (u)-[:friends]->(f) WHERE f.age > 1 OPTIONAL MATCH (u)<-[r]-() RETURN u.id, COUNT(f)",
+            r#"MATCH (n {q: "a \"b\""}) WHERE n.p = "C:\\dir\\" RETURN "\\\"" AS s"#,
+        ];
+        for q in queries {
+            let parsed = parse_query(q).unwrap();
+            let rendered = parsed.to_string();
+            let reparsed = parse_query(&rendered)
+                .unwrap_or_else(|e| panic!("{} rendered as {} failed: {}", q, rendered, e));
+            assert_eq!(parsed, reparsed, "{}", rendered);
+        }
+
+        let parsed = parse_query("match (n:a)  where n.x=1 return n.x").unwrap();
+        assert_eq!(parsed.to_string(), "MATCH (n:a) WHERE n.x = 1 RETURN n.x");
+    }
+
     #[test]
     fn test_parse_where() {
         let q = "MATCH (n) WHERE n.id = \"UC_001\" RETURN n";
@@ -474,6 +844,44 @@ mod tests {
         assert_eq!(parsed.return_clause.items.len(), 1);
     }
 
+    #[test]
+    fn test_parse_count_star() {
+        let q = "MATCH (:admin) RETURN COUNT(*)";
+        let parsed = parse_query(q).unwrap();
+        match &parsed.return_clause.items[0].expression {
+            ast::Expression::Aggregate(agg) => assert_eq!(agg.argument, None),
+            other => panic!("expected aggregate, got {:?}", other),
+        }
+        assert!(parse_query("MATCH (n) RETURN SUM(*)").is_err());
+    }
+
+    #[test]
+    fn test_parse_inline_properties() {
+        let q = "MATCH (u:users {role: \"admin\", age: 30}) RETURN u.id";
+        let parsed = parse_query(q).unwrap();
+        match &parsed.match_clause.patterns[0].chains[0] {
+            ast::PatternChain::Node(node_pat) => {
+                assert_eq!(node_pat.labels, vec!["users".to_string()]);
+                assert_eq!(node_pat.properties.len(), 2);
+                assert_eq!(node_pat.properties[0].0, "role");
+                assert_eq!(node_pat.properties[1].0, "age");
+            }
+            _ => panic!("expected node pattern"),
+        }
+    }
+
+    #[test]
+    fn test_inline_properties_are_equality_only() {
+        for q in [
+            "MATCH (u {name~: \"Al\"}) RETURN u.id",
+            "MATCH (u {name: STARTS WITH \"Al\"}) RETURN u.id",
+            "MATCH (u {name CONTAINS \"Al\"}) RETURN u.id",
+        ] {
+            assert!(parse_query(q).is_err(), "{}", q);
+        }
+        assert!(parse_query("MATCH (u) WHERE u.name STARTS WITH \"Al\" RETURN u.id").is_ok());
+    }
+
     #[test]
     fn test_parse_return_distinct() {
         let q = "MATCH (p:Patent) WHERE p.assignee CONTAINS \"Toyota\" RETURN DISTINCT p.assignee";
@@ -496,8 +904,12 @@ mod tests {
         assert!(parsed.order_by_clause.is_some());
         let order_by = parsed.order_by_clause.unwrap();
         assert_eq!(order_by.items.len(), 1);
-        assert_eq!(order_by.items[0].expression.variable, "n");
-        assert_eq!(order_by.items[0].expression.property.as_deref(), Some("id"));
+        let pv = order_by.items[0]
+            .expression
+            .as_property_or_variable()
+            .unwrap();
+        assert_eq!(pv.variable, "n");
+        assert_eq!(pv.property.as_deref(), Some("id"));
         assert_eq!(order_by.items[0].direction, ast::SortDirection::Asc);
     }
 
@@ -526,8 +938,12 @@ mod tests {
         let parsed = parse_query(q).unwrap();
         let order_by = parsed.order_by_clause.unwrap();
         assert_eq!(order_by.items.len(), 1);
-        assert_eq!(order_by.items[0].expression.variable, "n");
-        assert!(order_by.items[0].expression.property.is_none());
+        let pv = order_by.items[0]
+            .expression
+            .as_property_or_variable()
+            .unwrap();
+        assert_eq!(pv.variable, "n");
+        assert!(pv.property.is_none());
     }
 
     #[test]
@@ -538,19 +954,103 @@ mod tests {
     }
 
     #[test]
-    fn test_unsupported_feature_skip() {
-        let q = "MATCH (n) RETURN n SKIP 5";
-        let result = parse_query(q);
-        let err = result.unwrap_err().to_string();
-        assert!(err.contains("Unsupported feature: SKIP"));
+    fn test_parse_set_append() {
+        let q = "MATCH (u) WHERE u.id=\"1\" SET u.friends = u.friends + [\"4\"]";
+        let parsed = parse_query(q).unwrap();
+        let set = parsed.set_clause.unwrap();
+        assert_eq!(set.items.len(), 1);
+        assert_eq!(set.items[0].variable, "u");
+        assert_eq!(set.items[0].property, "friends");
+        match &set.items[0].value {
+            ast::ValueExpression::Add(left, right) => {
+                assert!(matches!(
+                    **left,
+                    ast::ValueExpression::PropertyOrVariable(_)
+                ));
+                assert!(matches!(&**right, ast::ValueExpression::List(items) if items.len() == 1));
+            }
+            other => panic!("expected Add, got {:?}", other),
+        }
     }
 
     #[test]
-    fn test_unsupported_feature_limit() {
-        let q = "MATCH (n) RETURN n LIMIT 10";
-        let result = parse_query(q);
-        let err = result.unwrap_err().to_string();
-        assert!(err.contains("Unsupported feature: LIMIT"));
+    fn test_parse_delete() {
+        let q = "MATCH (n) WHERE n.id = \"1\" DELETE n";
+        let parsed = parse_query(q).unwrap();
+        let delete = parsed.delete_clause.unwrap();
+        assert!(!delete.detach);
+        assert_eq!(delete.variables, vec!["n".to_string()]);
+        assert!(parsed.return_clause.items.is_empty());
+    }
+
+    #[test]
+    fn test_parse_detach_delete() {
+        let q = "MATCH (n)-[r]->(m) DETACH DELETE n, m";
+        let parsed = parse_query(q).unwrap();
+        let delete = parsed.delete_clause.unwrap();
+        assert!(delete.detach);
+        assert_eq!(delete.variables, vec!["n".to_string(), "m".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_skip_limit() {
+        let parsed = parse_query("MATCH (n) RETURN n SKIP 5").unwrap();
+        assert_eq!(parsed.skip, Some(5));
+        assert_eq!(parsed.limit, None);
+
+        let parsed = parse_query("MATCH (n) RETURN n ORDER BY n.id DESC SKIP 2 limit 10").unwrap();
+        assert!(parsed.order_by_clause.is_some());
+        assert_eq!(parsed.skip, Some(2));
+        assert_eq!(parsed.limit, Some(10));
+
+        assert!(parse_query("MATCH (n) RETURN n LIMIT 1 SKIP 2").is_err());
+
+        let parsed = parse_query("MATCH (n) RETURN n SKIP 0 LIMIT 0").unwrap();
+        assert_eq!((parsed.skip, parsed.limit), (Some(0), Some(0)));
+
+        let err = parse_query("MATCH (n) RETURN n LIMIT -1").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("LIMIT must not be negative, got -1")
+        );
+        let err = parse_query("MATCH (n) RETURN n SKIP -0").unwrap_err();
+        assert!(err.to_string().contains("SKIP must not be negative"));
+        let err = parse_query("MATCH (n) RETURN n SKIP 99999999999999999999999").unwrap_err();
+        assert!(err.to_string().contains("Invalid SKIP value"));
+    }
+
+    #[test]
+    fn test_split_queries() {
+        assert_eq!(
+            split_queries("MATCH (n) RETURN n;\n MATCH (n) WHERE n.s = \"a;b\" RETURN n ;;"),
+            vec![
+                "MATCH (n) RETURN n",
+                "MATCH (n) WHERE n.s = \"a;b\" RETURN n"
+            ]
+        );
+        assert!(split_queries(" ; ").is_empty());
+        // An unterminated string runs to the end of the script
+        assert_eq!(split_queries("a \"; b"), vec!["a \"; b"]);
+        assert_eq!(
+            split_queries(r#"RETURN "x\";y"; RETURN "z\\"; RETURN 1"#),
+            vec![r#"RETURN "x\";y""#, r#"RETURN "z\\""#, "RETURN 1"]
+        );
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let parsed =
+            parse_query(r#"MATCH (n) WHERE n.s = "say \"hi\" \\ C:\dir" RETURN n"#).unwrap();
+        let ast::Expression::Comparison(comp) = &parsed.where_clause.unwrap().expression else {
+            panic!("expected comparison");
+        };
+        assert_eq!(
+            comp.right.as_ref().unwrap(),
+            &ast::ValueExpression::Literal(ast::Literal::String(
+                r#"say "hi" \ C:\dir"#.to_string()
+            ))
+        );
+        assert!(parse_query(r#"MATCH (n) WHERE n.s = "a\" RETURN n"#).is_err());
     }
 
     #[test]
@@ -1,3 +1,4 @@
+use crate::engine::storage::json::{id_to_string, object_id, path_segments};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
@@ -29,6 +30,13 @@ pub struct NodeFieldInfo {
     pub is_id_candidate: bool,
     /// Whether this field could be a relation field (contains array of IDs)
     pub is_relation_candidate: bool,
+    /// Whether this field looks like a node label (a low-cardinality
+    /// category such as `role` or `type`); see [`AnalyzeOptions`]
+    pub is_label_candidate: bool,
+    /// Number of rows holding each JSON type for this field.
+    ///
+    /// More than one entry means the field has mixed types across rows.
+    pub type_distribution: HashMap<FieldType, usize>,
 }
 
 /// Field type classification.
@@ -42,6 +50,20 @@ pub enum FieldType {
     Null,
 }
 
+impl FieldType {
+    /// Classify a single JSON value.
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::String(_) => FieldType::String,
+            Value::Number(_) => FieldType::Number,
+            Value::Bool(_) => FieldType::Boolean,
+            Value::Array(_) => FieldType::Array,
+            Value::Object(_) => FieldType::Object,
+            Value::Null => FieldType::Null,
+        }
+    }
+}
+
 impl fmt::Display for FieldType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -55,6 +77,107 @@ impl fmt::Display for FieldType {
     }
 }
 
+/// How many targets a relation field links each element to, and how many
+/// elements share a target, as observed in the sampled elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cardinality {
+    /// At most one target per element, and no target shared
+    OneToOne,
+    /// Several targets per element, none shared with another element
+    OneToMany,
+    /// At most one target per element, some shared between elements
+    ManyToOne,
+    /// Several targets per element, some shared between elements
+    ManyToMany,
+}
+
+impl fmt::Display for Cardinality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cardinality::OneToOne => write!(f, "one-to-one"),
+            Cardinality::OneToMany => write!(f, "one-to-many"),
+            Cardinality::ManyToOne => write!(f, "many-to-one"),
+            Cardinality::ManyToMany => write!(f, "many-to-many"),
+        }
+    }
+}
+
+/// How strictly an id candidate's values must be unique.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdUniqueness {
+    /// Decide by field name alone (`id`, `_id`, `key`, `uuid`, ...).
+    NameOnly,
+    /// Every element must hold a distinct value.
+    Exact,
+    /// At least this fraction (0.0 to 1.0) of the values must be distinct.
+    Near(f64),
+}
+
+/// Tuning for the heuristics used by [`SchemaAnalyzer::analyze_with_options`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzeOptions {
+    /// Maximum ratio of distinct values to elements for a label candidate.
+    ///
+    /// A field present in at least half of the elements is a label
+    /// candidate when it has fewer than `ratio * element_count` distinct
+    /// values. Lower values only accept fields with few categories.
+    /// Defaults to `1.0`, i.e. at least one value repeats.
+    pub label_cardinality_ratio: f64,
+    /// Uniqueness required of id candidates. Defaults to
+    /// [`IdUniqueness::NameOnly`].
+    pub id_uniqueness: IdUniqueness,
+    /// Consider numeric fields as label candidates, not just strings.
+    /// Defaults to `false`.
+    pub numeric_labels: bool,
+    /// Infer field types and candidates from only the first `n` elements
+    /// of each array, trading completeness for speed on large inputs.
+    ///
+    /// [`ArraySchema::element_count`] still reports the full length, but
+    /// [`ArraySchema::fields`] and [`ArraySchema::field_values`] only
+    /// reflect the sample, so id uniqueness ([`IdUniqueness::Exact`] and
+    /// [`IdUniqueness::Near`]) is approximate: duplicates outside the sample
+    /// go unnoticed. Use [`SchemaAnalyzer::validate_id_uniqueness`] to check
+    /// the full array. `None` (analyze everything) by default.
+    pub sample_size: Option<usize>,
+}
+
+impl Default for AnalyzeOptions {
+    fn default() -> Self {
+        Self {
+            label_cardinality_ratio: 1.0,
+            id_uniqueness: IdUniqueness::NameOnly,
+            numeric_labels: false,
+            sample_size: None,
+        }
+    }
+}
+
+impl AnalyzeOptions {
+    /// Set the maximum distinct-values-to-elements ratio for label candidates.
+    pub fn with_label_cardinality_ratio(mut self, ratio: f64) -> Self {
+        self.label_cardinality_ratio = ratio;
+        self
+    }
+
+    /// Set the uniqueness required of id candidates.
+    pub fn with_id_uniqueness(mut self, id_uniqueness: IdUniqueness) -> Self {
+        self.id_uniqueness = id_uniqueness;
+        self
+    }
+
+    /// Set whether numeric fields can be label candidates.
+    pub fn with_numeric_labels(mut self, numeric_labels: bool) -> Self {
+        self.numeric_labels = numeric_labels;
+        self
+    }
+
+    /// Analyze only the first `sample_size` elements of each array.
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = Some(sample_size);
+        self
+    }
+}
+
 /// Detected schema for a JSON array.
 #[derive(Debug, Clone)]
 pub struct ArraySchema {
@@ -70,6 +193,34 @@ pub struct ArraySchema {
     pub recommended_id_field: Option<String>,
     /// Fields that likely contain relationships (arrays of IDs)
     pub recommended_relation_fields: Vec<String>,
+    /// Paths of the arrays each relation field points into, keyed by field.
+    ///
+    /// A path is listed when at least one referenced id belongs to one of
+    /// its elements, so a field only points at its own array when the ids
+    /// match. Fields whose ids match no array are absent.
+    pub relation_targets: HashMap<String, Vec<String>>,
+    /// Cardinality of each relation field, keyed by field.
+    ///
+    /// Judged from the sampled elements' arrays: whether any holds more
+    /// than one id, and whether any id appears under more than one element.
+    pub relation_cardinality: HashMap<String, Cardinality>,
+}
+
+impl ArraySchema {
+    /// The node label for this array: the last segment of its path.
+    fn label(&self) -> &str {
+        self.path.rsplit('.').next().unwrap_or(&self.path)
+    }
+
+    /// Node labels a relation field points at; empty when unknown.
+    fn target_labels(&self, field: &str) -> Vec<&str> {
+        self.relation_targets
+            .get(field)
+            .into_iter()
+            .flatten()
+            .map(|path| path.rsplit('.').next().unwrap_or(path))
+            .collect()
+    }
 }
 
 /// Schema for a root object that contains nested arrays.
@@ -115,14 +266,13 @@ impl SchemaDetection {
 
         output.push_str("Node Types:\n");
         for schema in &self.array_schemas {
-            let label = schema.path.rsplit('.').next().unwrap_or(&schema.path);
+            let label = schema.label();
             output.push_str(&format!("  (:{} {} nodes)\n", label, schema.element_count));
         }
 
         output.push_str("\nProperties:\n");
         for schema in &self.array_schemas {
-            let label = schema.path.rsplit('.').next().unwrap_or(&schema.path);
-            output.push_str(&format!(":{} {{", label));
+            output.push_str(&format!(":{} {{", schema.label()));
 
             let mut field_strings: Vec<String> = schema
                 .fields
@@ -143,9 +293,30 @@ impl SchemaDetection {
         if has_relations {
             output.push_str("\nRelationship Types:\n");
             for schema in &self.array_schemas {
-                let label = schema.path.rsplit('.').next().unwrap_or(&schema.path);
                 for rel_field in &schema.recommended_relation_fields {
-                    output.push_str(&format!("(:{})-[:{}]->()\n", label, rel_field));
+                    let cardinality = schema
+                        .relation_cardinality
+                        .get(rel_field)
+                        .map(|c| format!(" {}", c))
+                        .unwrap_or_default();
+                    let targets = schema.target_labels(rel_field);
+                    if targets.is_empty() {
+                        output.push_str(&format!(
+                            "(:{})-[:{}]->(){}\n",
+                            schema.label(),
+                            rel_field,
+                            cardinality
+                        ));
+                    }
+                    for target in targets {
+                        output.push_str(&format!(
+                            "(:{})-[:{}]->(:{}){}\n",
+                            schema.label(),
+                            rel_field,
+                            target,
+                            cardinality
+                        ));
+                    }
                 }
             }
         }
@@ -158,19 +329,35 @@ impl SchemaDetection {
         let mut patterns = Vec::new();
 
         for schema in &self.array_schemas {
-            let label = schema.path.rsplit('.').next().unwrap_or(&schema.path);
-            let pattern = if !schema.recommended_relation_fields.is_empty() {
-                format!(
-                    "(:{})-[{}]->(:{})",
-                    label,
-                    schema.recommended_relation_fields.join("|"),
-                    label
-                )
-            } else {
-                format!("(:{})", label)
-            };
+            let label = schema.label();
+            if schema.recommended_relation_fields.is_empty() {
+                patterns.push(format!("(:{})", label));
+                continue;
+            }
 
-            patterns.push(pattern);
+            // Group relation fields by the node type they point at, keeping
+            // the first-seen order of targets.
+            let mut by_target: Vec<(&str, Vec<&str>)> = Vec::new();
+            for rel_field in &schema.recommended_relation_fields {
+                let mut targets = schema.target_labels(rel_field);
+                if targets.is_empty() {
+                    targets.push("");
+                }
+                for target in targets {
+                    match by_target.iter_mut().find(|(t, _)| *t == target) {
+                        Some((_, fields)) => fields.push(rel_field),
+                        None => by_target.push((target, vec![rel_field])),
+                    }
+                }
+            }
+            for (target, fields) in by_target {
+                let target = if target.is_empty() {
+                    "()".to_string()
+                } else {
+                    format!("(:{})", target)
+                };
+                patterns.push(format!("(:{})-[{}]->{}", label, fields.join("|"), target));
+            }
         }
 
         patterns.join(", ")
@@ -199,9 +386,40 @@ impl SchemaAnalyzer {
     /// assert!(schema.is_root_object());
     /// ```
     pub fn analyze(data: &Value) -> SchemaResult<SchemaDetection> {
+        Self::analyze_with_options(data, &AnalyzeOptions::default())
+    }
+
+    /// Analyze a JSON document with tuned detection heuristics.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::schema::{AnalyzeOptions, IdUniqueness, SchemaAnalyzer};
+    /// use serde_json::json;
+    ///
+    /// let data = json!({
+    ///     "users": [
+    ///         { "id": "1", "tier": 1 },
+    ///         { "id": "2", "tier": 1 },
+    ///         { "id": "2", "tier": 2 }
+    ///     ]
+    /// });
+    ///
+    /// let options = AnalyzeOptions::default()
+    ///     .with_id_uniqueness(IdUniqueness::Exact)
+    ///     .with_numeric_labels(true);
+    /// let schema = SchemaAnalyzer::analyze_with_options(&data, &options).unwrap();
+    /// let users = &schema.array_schemas[0];
+    /// assert_eq!(users.recommended_id_field, None);
+    /// assert!(users.fields.iter().any(|f| f.name == "tier" && f.is_label_candidate));
+    /// ```
+    pub fn analyze_with_options(
+        data: &Value,
+        options: &AnalyzeOptions,
+    ) -> SchemaResult<SchemaDetection> {
         let obj = data.as_object().ok_or(SchemaError::NoArrayFound)?;
 
-        let root_schema = detect_root_object(obj);
+        let root_schema = detect_root_object(obj, options);
         if root_schema.nested_arrays.is_empty() {
             return Err(SchemaError::NoArrayFound);
         }
@@ -211,11 +429,66 @@ impl SchemaAnalyzer {
             root_schema,
         ))
     }
+
+    /// Check that `field` holds a distinct value in every element of the
+    /// array at `path`.
+    ///
+    /// Unlike the per-field uniqueness used during detection, this scans
+    /// the full array. See [`SchemaAnalyzer::duplicate_ids`] to get the
+    /// colliding values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cypher_rs::schema::SchemaAnalyzer;
+    /// use serde_json::json;
+    ///
+    /// let data = json!({"users": [{"id": "1"}, {"id": "1"}]});
+    /// assert!(!SchemaAnalyzer::validate_id_uniqueness(&data, "users", "id").unwrap());
+    /// ```
+    pub fn validate_id_uniqueness(data: &Value, path: &str, field: &str) -> SchemaResult<bool> {
+        Ok(Self::duplicate_ids(data, path, field)?.is_empty())
+    }
+
+    /// Return the values of `field` that occur more than once in the array
+    /// at `path`, sorted.
+    ///
+    /// `path` is dot-separated (e.g. `"data.users"`), with `\.` for a dot
+    /// inside a key. Ids are compared in the same canonical string form the
+    /// graph builder uses, so `1` and `"1"` collide. Elements without the
+    /// field are ignored.
+    pub fn duplicate_ids(data: &Value, path: &str, field: &str) -> SchemaResult<Vec<String>> {
+        let array = path_segments(path)
+            .iter()
+            .try_fold(data, |value, segment| value.get(segment))
+            .and_then(Value::as_array)
+            .ok_or_else(|| SchemaError::InvalidJson(format!("No array found at '{}'", path)))?;
+
+        let mut seen = HashSet::new();
+        let mut duplicates = HashSet::new();
+        for element in array {
+            if let Some(id) = element.get(field).and_then(id_to_string)
+                && !seen.insert(id.clone())
+            {
+                duplicates.insert(id);
+            }
+        }
+
+        let mut duplicates: Vec<String> = duplicates.into_iter().collect();
+        duplicates.sort();
+        Ok(duplicates)
+    }
 }
 
 /// Detect root object schema.
-fn detect_root_object(obj: &serde_json::Map<String, Value>) -> RootObjectSchema {
+fn detect_root_object(
+    obj: &serde_json::Map<String, Value>,
+    options: &AnalyzeOptions,
+) -> RootObjectSchema {
     let mut nested_arrays = Vec::new();
+    // Element ids of each entry in `nested_arrays`, as the graph builder
+    // registers them
+    let mut array_ids: Vec<HashSet<String>> = Vec::new();
 
     for (key, value) in obj {
         let elements: Vec<&Value> = match value {
@@ -231,12 +504,28 @@ fn detect_root_object(obj: &serde_json::Map<String, Value>) -> RootObjectSchema
 
         let mut all_fields: HashMap<String, usize> = HashMap::new();
         let mut field_values: HashMap<String, HashSet<Value>> = HashMap::new();
+        let mut type_counts: HashMap<String, HashMap<FieldType, usize>> = HashMap::new();
         let element_count = elements.len();
+        array_ids.push(
+            elements
+                .iter()
+                .filter_map(|e| e.as_object().and_then(object_id))
+                .collect(),
+        );
+
+        let sample_count = options
+            .sample_size
+            .map_or(element_count, |n| n.min(element_count));
 
-        for element in &elements {
+        for element in &elements[..sample_count] {
             if let Value::Object(elem_obj) = element {
                 for (fkey, fvalue) in elem_obj {
                     *all_fields.entry(fkey.clone()).or_insert(0) += 1;
+                    *type_counts
+                        .entry(fkey.clone())
+                        .or_default()
+                        .entry(FieldType::of(fvalue))
+                        .or_insert(0) += 1;
                     field_values
                         .entry(fkey.clone())
                         .or_default()
@@ -266,18 +555,34 @@ fn detect_root_object(obj: &serde_json::Map<String, Value>) -> RootObjectSchema
                 FieldType::Null
             };
 
-            let is_id_candidate = field_name.contains("id")
+            let occurrences = all_fields[field_name];
+            let distinct = values.map_or(0, HashSet::len);
+
+            let is_id_candidate = (field_name.contains("id")
                 || field_name == "key"
                 || field_name == "uuid"
-                || field_name == "_id";
+                || field_name == "_id")
+                && match options.id_uniqueness {
+                    IdUniqueness::NameOnly => true,
+                    IdUniqueness::Exact => distinct == occurrences,
+                    IdUniqueness::Near(ratio) => distinct as f64 >= ratio * occurrences as f64,
+                };
 
             let is_relation_candidate = field_type == FieldType::Array && !is_id_candidate;
 
+            let is_label_candidate = !is_id_candidate
+                && (field_type == FieldType::String
+                    || (options.numeric_labels && field_type == FieldType::Number))
+                && occurrences * 2 >= sample_count
+                && (distinct as f64) < options.label_cardinality_ratio * sample_count as f64;
+
             fields.push(NodeFieldInfo {
                 name: field_name.clone(),
                 field_type,
                 is_id_candidate,
                 is_relation_candidate,
+                is_label_candidate,
+                type_distribution: type_counts.remove(field_name).unwrap_or_default(),
             });
         }
 
@@ -292,6 +597,14 @@ fn detect_root_object(obj: &serde_json::Map<String, Value>) -> RootObjectSchema
             .map(|f| f.name.clone())
             .collect();
 
+        let relation_cardinality = recommended_relation_fields
+            .iter()
+            .map(|field| {
+                let cardinality = relation_cardinality(&elements[..sample_count], field);
+                (field.clone(), cardinality)
+            })
+            .collect();
+
         nested_arrays.push(ArraySchema {
             path: key.clone(),
             element_count,
@@ -299,15 +612,68 @@ fn detect_root_object(obj: &serde_json::Map<String, Value>) -> RootObjectSchema
             field_values,
             recommended_id_field,
             recommended_relation_fields,
+            relation_targets: HashMap::new(),
+            relation_cardinality,
         });
     }
 
+    // Resolve each relation field against the element ids of every array.
+    for i in 0..nested_arrays.len() {
+        let mut relation_targets = HashMap::new();
+        let schema = &nested_arrays[i];
+        for rel_field in &schema.recommended_relation_fields {
+            let referenced: HashSet<String> = schema
+                .field_values
+                .get(rel_field)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_array)
+                .flatten()
+                .filter_map(id_to_string)
+                .collect();
+            let targets: Vec<String> = nested_arrays
+                .iter()
+                .zip(&array_ids)
+                .filter(|(_, ids)| !ids.is_disjoint(&referenced))
+                .map(|(target, _)| target.path.clone())
+                .collect();
+            if !targets.is_empty() {
+                relation_targets.insert(rel_field.clone(), targets);
+            }
+        }
+        nested_arrays[i].relation_targets = relation_targets;
+    }
+
     RootObjectSchema {
         label: "Root".to_string(),
         nested_arrays,
     }
 }
 
+/// Classify the relation arrays under `field` across `elements`.
+fn relation_cardinality(elements: &[&Value], field: &str) -> Cardinality {
+    let mut many = false;
+    let mut shared = false;
+    let mut seen = HashSet::new();
+    for element in elements {
+        let Some(ids) = element.get(field).and_then(Value::as_array) else {
+            continue;
+        };
+        // Repeats within one element don't make a target shared
+        let ids: HashSet<String> = ids.iter().filter_map(id_to_string).collect();
+        many |= ids.len() > 1;
+        for id in ids {
+            shared |= !seen.insert(id);
+        }
+    }
+    match (many, shared) {
+        (false, false) => Cardinality::OneToOne,
+        (true, false) => Cardinality::OneToMany,
+        (false, true) => Cardinality::ManyToOne,
+        (true, true) => Cardinality::ManyToMany,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,6 +714,202 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_type_distribution_mixed_types() {
+        let mut users: Vec<Value> = (0..8)
+            .map(|i| json!({ "id": i.to_string(), "age": 20 + i }))
+            .collect();
+        users.push(json!({ "id": "8", "age": "unknown" }));
+        users.push(json!({ "id": "9", "age": "42" }));
+        let data = json!({ "users": users });
+
+        let schema = SchemaAnalyzer::analyze(&data).unwrap();
+        let age = schema.array_schemas[0]
+            .fields
+            .iter()
+            .find(|f| f.name == "age")
+            .unwrap();
+
+        assert_eq!(age.type_distribution.len(), 2);
+        assert_eq!(age.type_distribution.get(&FieldType::Number), Some(&8));
+        assert_eq!(age.type_distribution.get(&FieldType::String), Some(&2));
+
+        let id = schema.array_schemas[0]
+            .fields
+            .iter()
+            .find(|f| f.name == "id")
+            .unwrap();
+        assert_eq!(id.type_distribution.get(&FieldType::String), Some(&10));
+    }
+
+    #[test]
+    fn test_analyze_with_options() {
+        let data = json!({
+            "users": [
+                { "id": "1", "role": "admin", "level": 1, "name": "a" },
+                { "id": "2", "role": "user", "level": 1, "name": "b" },
+                { "id": "3", "role": "user", "level": 2, "name": "c" },
+                { "id": "3", "role": "user", "level": 2, "name": "d" }
+            ]
+        });
+        let field = |schema: &SchemaDetection, name: &str| {
+            schema.array_schemas[0]
+                .fields
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap()
+                .clone()
+        };
+
+        let default = SchemaAnalyzer::analyze(&data).unwrap();
+        assert!(field(&default, "role").is_label_candidate);
+        assert!(!field(&default, "name").is_label_candidate);
+        assert!(!field(&default, "level").is_label_candidate);
+        assert!(field(&default, "id").is_id_candidate);
+
+        let options = AnalyzeOptions::default()
+            .with_label_cardinality_ratio(0.25)
+            .with_numeric_labels(true)
+            .with_id_uniqueness(IdUniqueness::Exact);
+        let tuned = SchemaAnalyzer::analyze_with_options(&data, &options).unwrap();
+        assert!(!field(&tuned, "role").is_label_candidate);
+        assert!(!field(&tuned, "level").is_label_candidate);
+        assert!(!field(&tuned, "id").is_id_candidate);
+        assert_eq!(tuned.array_schemas[0].recommended_id_field, None);
+
+        let options = options
+            .with_label_cardinality_ratio(0.75)
+            .with_id_uniqueness(IdUniqueness::Near(0.75));
+        let near = SchemaAnalyzer::analyze_with_options(&data, &options).unwrap();
+        assert!(field(&near, "role").is_label_candidate);
+        assert!(field(&near, "level").is_label_candidate);
+        assert!(field(&near, "id").is_id_candidate);
+    }
+
+    #[test]
+    fn test_analyze_sample_size() {
+        let data = json!({
+            "users": [
+                { "id": "1", "role": "admin" },
+                { "id": "2", "role": "user" },
+                { "id": "3", "role": "user" },
+                { "id": "3", "role": "user", "note": "late" }
+            ]
+        });
+        let options = AnalyzeOptions::default()
+            .with_id_uniqueness(IdUniqueness::Exact)
+            .with_sample_size(3);
+        let schema = SchemaAnalyzer::analyze_with_options(&data, &options).unwrap();
+        let users = &schema.array_schemas[0];
+
+        assert_eq!(users.element_count, 4);
+        // The duplicate id and the extra field lie outside the sample
+        assert_eq!(users.recommended_id_field.as_deref(), Some("id"));
+        assert!(!users.fields.iter().any(|f| f.name == "note"));
+        assert!(
+            users
+                .fields
+                .iter()
+                .any(|f| f.name == "role" && f.is_label_candidate)
+        );
+
+        let full =
+            SchemaAnalyzer::analyze_with_options(&data, &options.with_sample_size(10)).unwrap();
+        assert_eq!(full.array_schemas[0].recommended_id_field, None);
+    }
+
+    #[test]
+    fn test_relation_targets_other_array() {
+        let data = json!({
+            "posts": [
+                { "id": "p1", "authors": ["u1"], "related": ["p2"] },
+                { "id": "p2", "authors": ["u2"], "related": [] }
+            ],
+            "users": [
+                { "id": "u1", "name": "Alice" },
+                { "id": "u2", "name": "Bob" }
+            ]
+        });
+
+        let schema = SchemaAnalyzer::analyze(&data).unwrap();
+        let posts = schema
+            .array_schemas
+            .iter()
+            .find(|s| s.path == "posts")
+            .unwrap();
+        assert_eq!(posts.relation_targets["authors"], vec!["users".to_string()]);
+        assert_eq!(posts.relation_targets["related"], vec!["posts".to_string()]);
+
+        let neo4j = schema.to_neo4j_schema();
+        assert!(neo4j.contains("(:posts)-[:authors]->(:users)"));
+        assert!(neo4j.contains("(:posts)-[:related]->(:posts)"));
+        assert!(!neo4j.contains("(:posts)-[:authors]->(:posts)"));
+
+        let pattern = schema.to_pattern();
+        assert!(pattern.contains("(:posts)-[authors]->(:users)"));
+        assert!(pattern.contains("(:posts)-[related]->(:posts)"));
+    }
+
+    #[test]
+    fn test_relation_cardinality() {
+        let data = json!({
+            "users": [
+                { "id": "1", "friends": ["2", "3"], "manager": ["4"], "buddy": ["2"], "tags": ["2", "4"] },
+                { "id": "2", "friends": ["4", "4"], "manager": ["4"], "buddy": ["1"], "tags": ["4"] },
+                { "id": "3", "friends": [], "manager": [], "buddy": [], "tags": [] },
+                { "id": "4", "friends": ["1"] }
+            ]
+        });
+
+        let schema = SchemaAnalyzer::analyze(&data).unwrap();
+        let users = &schema.array_schemas[0];
+        let cardinality = |field: &str| users.relation_cardinality[field];
+        assert_eq!(cardinality("friends"), Cardinality::OneToMany);
+        assert_eq!(cardinality("manager"), Cardinality::ManyToOne);
+        assert_eq!(cardinality("buddy"), Cardinality::OneToOne);
+        assert_eq!(cardinality("tags"), Cardinality::ManyToMany);
+
+        assert!(
+            schema
+                .to_neo4j_schema()
+                .contains("(:users)-[:friends]->(:users) one-to-many\n")
+        );
+
+        // Lines without a known target carry the cardinality too, and
+        // fields without one are listed bare
+        let mut schema = schema;
+        let users = &mut schema.array_schemas[0];
+        users.relation_targets.remove("manager");
+        users.relation_cardinality.remove("tags");
+        let neo4j = schema.to_neo4j_schema();
+        assert!(neo4j.contains("(:users)-[:manager]->() many-to-one\n"));
+        assert!(neo4j.contains("(:users)-[:tags]->(:users)\n"));
+    }
+
+    #[test]
+    fn test_duplicate_ids() {
+        let data = json!({
+            "data": {
+                "users": [
+                    { "id": "1" },
+                    { "id": 1 },
+                    { "id": "2" },
+                    { "name": "no id" },
+                    { "id": "3" },
+                    { "id": "3" }
+                ]
+            }
+        });
+
+        let duplicates = SchemaAnalyzer::duplicate_ids(&data, "data.users", "id").unwrap();
+        assert_eq!(duplicates, vec!["1".to_string(), "3".to_string()]);
+        assert!(!SchemaAnalyzer::validate_id_uniqueness(&data, "data.users", "id").unwrap());
+
+        let unique = json!({"users": [{ "id": "1" }, { "id": "2" }]});
+        assert!(SchemaAnalyzer::validate_id_uniqueness(&unique, "users", "id").unwrap());
+        assert!(SchemaAnalyzer::duplicate_ids(&unique, "missing", "id").is_err());
+    }
+
     #[test]
     fn test_empty_json() {
         let data = json!({});